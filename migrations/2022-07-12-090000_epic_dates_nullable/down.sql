@@ -0,0 +1,8 @@
+UPDATE epics SET start_date = CURRENT_TIMESTAMP WHERE start_date IS NULL;
+UPDATE epics SET due_date = start_date + INTERVAL '1' day WHERE due_date IS NULL;
+
+ALTER TABLE epics
+    ALTER COLUMN start_date SET DEFAULT CURRENT_TIMESTAMP,
+    ALTER COLUMN start_date SET NOT NULL,
+    ALTER COLUMN due_date SET DEFAULT CURRENT_TIMESTAMP + INTERVAL '1' day,
+    ALTER COLUMN due_date SET NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE epics
+    ALTER COLUMN start_date DROP NOT NULL,
+    ALTER COLUMN start_date DROP DEFAULT,
+    ALTER COLUMN due_date DROP NOT NULL,
+    ALTER COLUMN due_date DROP DEFAULT;
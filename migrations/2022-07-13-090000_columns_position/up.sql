@@ -0,0 +1,11 @@
+ALTER TABLE columns ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+-- Columns have no creation time, so existing ones keep the id order they
+-- were listed in before positions existed.
+UPDATE columns
+SET position = numbered.position
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY board_id ORDER BY id) - 1 AS position
+    FROM columns
+) AS numbered
+WHERE columns.id = numbered.id;
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS set_updated_at ON issues;
+DROP TRIGGER IF EXISTS set_updated_at ON epics;
+
+ALTER TABLE issues DROP COLUMN updated_at;
+ALTER TABLE epics DROP COLUMN updated_at;
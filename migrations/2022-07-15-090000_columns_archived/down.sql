@@ -0,0 +1 @@
+ALTER TABLE columns DROP COLUMN archived;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE columns ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
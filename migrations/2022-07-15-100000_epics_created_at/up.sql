@@ -0,0 +1 @@
+ALTER TABLE epics ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE epics DROP COLUMN progress_percent;
\ No newline at end of file
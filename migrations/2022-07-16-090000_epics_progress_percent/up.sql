@@ -0,0 +1,2 @@
+ALTER TABLE epics ADD COLUMN progress_percent INTEGER NOT NULL DEFAULT 0
+    CHECK (progress_percent BETWEEN 0 AND 100);
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE dependencies DROP COLUMN dependency_type;
\ No newline at end of file
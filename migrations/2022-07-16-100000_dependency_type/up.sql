@@ -0,0 +1,2 @@
+-- Values follow the DependencyType proto enum; NULL is treated as BLOCKS.
+ALTER TABLE dependencies ADD COLUMN dependency_type INTEGER;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE epics DROP COLUMN metadata;
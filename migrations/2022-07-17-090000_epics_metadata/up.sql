@@ -0,0 +1 @@
+ALTER TABLE epics ADD COLUMN metadata JSONB;
@@ -0,0 +1 @@
+ALTER TABLE boards ADD COLUMN deletion_protected BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,10 @@
+CREATE TABLE issue_moves (
+    id CHAR(36) PRIMARY KEY,
+    issue_id CHAR(36) NOT NULL REFERENCES issues (id) ON DELETE CASCADE,
+    from_column CHAR(36) NOT NULL,
+    to_column CHAR(36) NOT NULL,
+    moved_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX issue_moves_issue_id_moved_at ON issue_moves (issue_id, moved_at);
+CREATE INDEX issue_moves_to_column_moved_at ON issue_moves (to_column, moved_at);
@@ -0,0 +1 @@
+DROP TABLE issue_links;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE issue_links (
+    id CHAR(36) PRIMARY KEY,
+    issue_id CHAR(36) NOT NULL REFERENCES issues (id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    title VARCHAR(50),
+    kind INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX issue_links_issue_id ON issue_links (issue_id);
@@ -0,0 +1 @@
+ALTER TABLE issues ADD COLUMN status SMALLINT NOT NULL DEFAULT 0;
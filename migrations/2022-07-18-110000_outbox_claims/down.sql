@@ -0,0 +1,3 @@
+DROP INDEX outbox_delivered_at;
+
+ALTER TABLE outbox DROP COLUMN claimed_until;
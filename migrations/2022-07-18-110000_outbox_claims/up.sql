@@ -0,0 +1,3 @@
+ALTER TABLE outbox ADD COLUMN claimed_until TIMESTAMPTZ;
+
+CREATE INDEX outbox_delivered_at ON outbox (delivered_at) WHERE delivered_at IS NOT NULL;
@@ -0,0 +1,9 @@
+ALTER TABLE issues ADD COLUMN rank INTEGER NOT NULL DEFAULT 0;
+
+UPDATE issues
+SET rank = ranked.rank
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY column_id ORDER BY created_at, id) - 1 AS rank
+    FROM issues
+) AS ranked
+WHERE issues.id = ranked.id;
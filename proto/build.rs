@@ -1,5 +1,6 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::compile_protos("./proto/issues/issues.proto")?;
     tonic_build::compile_protos("./proto/eventbus/*")?;
+    tonic_build::compile_protos("./proto/health/health.proto")?;
     Ok(())
-}
\ No newline at end of file
+}
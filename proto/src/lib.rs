@@ -3,4 +3,7 @@ pub mod issues {
 }
 pub mod eventbus {
     tonic::include_proto!("eventbus");
-}
\ No newline at end of file
+}
+pub mod health {
+    tonic::include_proto!("grpc.health.v1");
+}
@@ -0,0 +1,25 @@
+use std::env;
+
+/// What `createBoard` does when the project already has a board, read from
+/// `BOARDS_PER_PROJECT`: `many` (default) always creates a new one, `reuse`
+/// returns the existing board and `reject` fails with `already_exists`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BoardsPerProject {
+    Many,
+    Reuse,
+    Reject,
+}
+
+impl BoardsPerProject {
+    pub fn from_env() -> Result<BoardsPerProject, String> {
+        match env::var("BOARDS_PER_PROJECT") {
+            Err(_) => Ok(BoardsPerProject::Many),
+            Ok(value) => match value.as_str() {
+                "many" => Ok(BoardsPerProject::Many),
+                "reuse" => Ok(BoardsPerProject::Reuse),
+                "reject" => Ok(BoardsPerProject::Reject),
+                other => Err(format!("BOARDS_PER_PROJECT must be many, reuse or reject, got {}", other)),
+            },
+        }
+    }
+}
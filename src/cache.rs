@@ -0,0 +1,67 @@
+use std::{env, time::Duration};
+
+use moka::sync::Cache;
+
+/// Optional TTL/LRU cache for get-by-id reads, keyed by entity id.
+/// Disabled unless `ENABLE_READ_CACHE=true`; sized by `READ_CACHE_SIZE`
+/// (entries, default 10000) and `READ_CACHE_TTL_SECS` (default 30).
+#[derive(Clone)]
+pub struct ReadCache<T> {
+    inner: Option<Cache<String, T>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> ReadCache<T> {
+    pub fn from_env() -> Result<ReadCache<T>, String> {
+        let enabled = match env::var("ENABLE_READ_CACHE") {
+            Err(_) => false,
+            Ok(value) => match value.as_str() {
+                "true" => true,
+                "false" => false,
+                other => return Err(format!("ENABLE_READ_CACHE must be true or false, got {}", other)),
+            },
+        };
+        if !enabled {
+            return Ok(ReadCache { inner: None });
+        }
+
+        let size = parse_env("READ_CACHE_SIZE", 10_000)?;
+        let ttl = parse_env("READ_CACHE_TTL_SECS", 30)?;
+        let cache = Cache::builder()
+            .max_capacity(size)
+            .time_to_live(Duration::from_secs(ttl))
+            .build();
+
+        Ok(ReadCache { inner: Some(cache) })
+    }
+
+    pub fn get(&self, key: &str) -> Option<T> {
+        self.inner.as_ref().and_then(|cache| cache.get(key))
+    }
+
+    pub fn insert(&self, key: String, value: T) {
+        if let Some(cache) = &self.inner {
+            cache.insert(key, value);
+        }
+    }
+
+    pub fn invalidate(&self, key: &str) {
+        if let Some(cache) = &self.inner {
+            cache.invalidate(key);
+        }
+    }
+
+    pub fn invalidate_all(&self) {
+        if let Some(cache) = &self.inner {
+            cache.invalidate_all();
+        }
+    }
+}
+
+fn parse_env(name: &str, default: u64) -> Result<u64, String> {
+    match env::var(name) {
+        Err(_) => Ok(default),
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("{} must be a non-negative integer, got {}", name, value)),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,512 @@
+use diesel::result::Error::NotFound;
+use chrono::NaiveDateTime;
+use tonic::{Request, Response, Status, Code, transport::Channel};
+use proto::{
+    issues::{
+        admin_service_server::AdminService,
+        BoardId,
+        ReconcileCounts,
+        PoolWaitBucket,
+        PoolWaitMetrics as ProtoPoolWaitMetrics,
+        Dependency as ProtoDependency,
+        DependencyAudit as ProtoDependencyAudit,
+        DependencyCycle,
+        DependencyFixReport,
+        FixDependenciesRequest,
+        ReplayEventsRequest,
+        ReplayedEvents,
+    },
+    eventbus::{
+        self,
+        boards_events_service_client::BoardsEventsServiceClient,
+        columns_events_service_client::ColumnsEventsServiceClient,
+        epics_events_service_client::EpicsEventsServiceClient,
+        issues_events_service_client::IssuesEventsServiceClient,
+        dependencies_events_service_client::DependenciesEventsServiceClient,
+        BoardEvent, ColumnEvent, EpicEvent, IssueEvent, DependencyEvent, ReconcileEvent, DependencyAuditEvent,
+        ReplayEventsEvent,
+    },
+};
+
+use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    events::{EventPayload, db_failure, pooled_connection, rejection, send_event},
+    controllers::epics::from_timestamp,
+    outbox::{self, replay_methods, ReplayError},
+    db::{
+        repos::{
+            board::{Board, BoardSnapshot, SnapshotBoard},
+            dependency::{Dependency, DependencyAudit, AuditDependencies},
+        },
+        connection::PgPool,
+        pool_metrics::{PoolWaitMetrics, WAIT_BUCKETS_MS},
+    },
+};
+
+pub struct AdminController {
+    pub pool: PgPool,
+    pub boards_eventbus_service_client: Option<BoardsEventsServiceClient<Channel>>,
+    pub columns_eventbus_service_client: Option<ColumnsEventsServiceClient<Channel>>,
+    pub epics_eventbus_service_client: Option<EpicsEventsServiceClient<Channel>>,
+    pub issues_eventbus_service_client: Option<IssuesEventsServiceClient<Channel>>,
+    pub dependencies_eventbus_service_client: Option<DependenciesEventsServiceClient<Channel>>,
+    pub event_payload: EventPayload,
+    pub pool_wait_metrics: PoolWaitMetrics,
+}
+
+#[tonic::async_trait]
+impl AdminService for AdminController {
+    async fn reconcile(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<ReconcileCounts>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ReconcileEvent {
+                    board_id: data.board_id.clone(),
+                    columns: None,
+                    epics: None,
+                    issues: None,
+                    dependencies: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.boards_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, reconcile_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Board::snapshot(&data.board_id, db_connection).await {
+            Ok(BoardSnapshot { board: brd, columns: clmns, epics: board_epics, issues: board_issues, dependencies: deps }) => {
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected),
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.boards_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_board_event, req).await;
+                });
+
+                for clmn in &clmns {
+                    let column = eventbus::Column {
+                        id: Some(clmn.id.clone()),
+                        board_id: Some(clmn.board_id.clone()),
+                        name: Some(clmn.name.clone()),
+                        archived: Some(clmn.archived),
+                    };
+                    let req = Request::new(ColumnEvent {
+                        column: Some(self.event_payload.apply(column)),
+                        error: None,
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.columns_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_column_event, req).await;
+                    });
+                }
+
+                for ep in &board_epics {
+                    let epic = eventbus::Epic {
+                        id: Some(ep.id.clone()),
+                        column_id: Some(ep.column_id.clone()),
+                        assignee_id: ep.assignee_id.clone(),
+                        reporter_id: Some(ep.reporter_id.clone()),
+                        name: Some(ep.name.clone()),
+                        description: ep.description.clone(),
+                        start_date: ep.start_date.map(|date| date.to_string()),
+                        due_date: ep.due_date.map(|date| date.to_string()),
+                        progress_percent: Some(ep.progress_percent),
+                        metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+                    };
+                    let req = Request::new(EpicEvent {
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: None,
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.epics_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_epic_event, req).await;
+                    });
+                }
+
+                for iss in &board_issues {
+                    let issue = eventbus::Issue {
+                        id: Some(iss.id.clone()),
+                        column_id: Some(iss.column_id.clone()),
+                        epic_id: Some(iss.epic_id.clone()),
+                        title: Some(iss.title.clone()),
+                        description: Some(iss.description.clone()),
+                        status: Some(iss.status.into()),
+                    };
+                    let req = Request::new(IssueEvent {
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: None,
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.issues_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_issue_event, req).await;
+                    });
+                }
+
+                for dep in &deps {
+                    let dependency = eventbus::Dependency {
+                        id: Some(dep.id.clone()),
+                        blocking_epic_id: Some(dep.blocking_epic_id.clone()),
+                        blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                        dependency_type: Some(dep.dependency_type.unwrap_or_default()),
+                    };
+                    let req = Request::new(DependencyEvent {
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: None,
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.dependencies_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_dependency_event, req).await;
+                    });
+                }
+
+                let counts = ReconcileCounts {
+                    boards: 1,
+                    columns: clmns.len() as i32,
+                    epics: board_epics.len() as i32,
+                    issues: board_issues.len() as i32,
+                    dependencies: deps.len() as i32,
+                };
+
+                let req = Request::new(ReconcileEvent {
+                    board_id: data.board_id.clone(),
+                    columns: Some(counts.columns),
+                    epics: Some(counts.epics),
+                    issues: Some(counts.issues),
+                    dependencies: Some(counts.dependencies),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.boards_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, reconcile_event, req).await;
+                });
+
+                Ok(Response::new(counts))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Board not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let req = Request::new(ReconcileEvent {
+                    board_id: data.board_id.clone(),
+                    columns: None,
+                    epics: None,
+                    issues: None,
+                    dependencies: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.boards_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, reconcile_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn get_pool_wait_metrics(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<ProtoPoolWaitMetrics>, Status> {
+        let snapshot = self.pool_wait_metrics.snapshot();
+
+        let buckets: Vec<PoolWaitBucket> = snapshot.buckets
+            .iter()
+            .enumerate()
+            .map(|(index, count)| PoolWaitBucket {
+                upper_bound_ms: WAIT_BUCKETS_MS.get(index).map(|bound| *bound as i64),
+                count: *count as i64,
+            })
+            .collect();
+
+        Ok(Response::new(ProtoPoolWaitMetrics {
+            buckets,
+            count: snapshot.count as i64,
+            total_wait_micros: snapshot.total_wait_micros as i64,
+            max_wait_micros: snapshot.max_wait_micros as i64,
+            timeouts: snapshot.timeouts as i64,
+        }))
+    }
+
+    async fn audit_dependencies(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<ProtoDependencyAudit>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: Some(data.board_id.clone()),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: None,
+                    removed_ids: Vec::new(),
+                    dry_run: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.dependencies_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, audit_dependencies_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Dependency::audit(Some(&data.board_id), db_connection).await {
+            Ok(audit) => {
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: Some(data.board_id.clone()),
+                    self_references: Some(audit.self_references.len() as i32),
+                    duplicates: Some(audit.duplicates.len() as i32),
+                    cycles: Some(audit.cycles.len() as i32),
+                    removed_ids: Vec::new(),
+                    dry_run: None,
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.dependencies_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, audit_dependencies_event, req).await;
+                });
+
+                Ok(Response::new(to_proto_audit(&audit)))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: Some(data.board_id.clone()),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: None,
+                    removed_ids: Vec::new(),
+                    dry_run: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.dependencies_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, audit_dependencies_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn fix_dependencies(
+        &self,
+        request: Request<FixDependenciesRequest>,
+    ) -> Result<Response<DependencyFixReport>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: data.board_id.clone(),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: None,
+                    removed_ids: Vec::new(),
+                    dry_run: Some(data.dry_run),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.dependencies_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, fix_dependencies_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Dependency::fix(data.board_id.as_deref(), data.dry_run, db_connection).await {
+            Ok((audit, removed_ids)) => {
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: data.board_id.clone(),
+                    self_references: Some(audit.self_references.len() as i32),
+                    duplicates: Some(audit.duplicates.len() as i32),
+                    cycles: Some(audit.cycles.len() as i32),
+                    removed_ids: removed_ids.clone(),
+                    dry_run: Some(data.dry_run),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.dependencies_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, fix_dependencies_event, req).await;
+                });
+
+                Ok(Response::new(DependencyFixReport {
+                    audit: Some(to_proto_audit(&audit)),
+                    removed_ids,
+                    dry_run: data.dry_run,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: data.board_id.clone(),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: None,
+                    removed_ids: Vec::new(),
+                    dry_run: Some(data.dry_run),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.dependencies_eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, fix_dependencies_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn replay_events(
+        &self,
+        request: Request<ReplayEventsRequest>,
+    ) -> Result<Response<ReplayedEvents>, Status> {
+        let data = request.get_ref();
+
+        let replayed: Result<usize, (eventbus::Error, Status)> = async {
+            let (from, to, methods) = replay_window(data)
+                .map_err(|message| rejection(Code::InvalidArgument, message))?;
+            let issues_client = self.issues_eventbus_service_client.as_ref()
+                .ok_or_else(|| rejection(Code::FailedPrecondition, "no issues eventbus is configured"))?;
+            let lock_connection = pooled_connection(&self.pool).map_err(|rejected| *rejected)?;
+            let db_connection = pooled_connection(&self.pool).map_err(|rejected| *rejected)?;
+
+            outbox::replay(lock_connection, db_connection, issues_client, from, to, methods).await
+                .map_err(|err| match err {
+                    ReplayError::InProgress => rejection(Code::Aborted, "another replay is running"),
+                    ReplayError::Db(err) => db_failure(&err),
+                    ReplayError::Send(sent, status) => rejection(
+                        Code::Unavailable,
+                        &format!("eventbus failed after {} events were replayed: {}", sent, status.message()),
+                    ),
+                })
+        }.await;
+
+        let (count, error, result) = match replayed {
+            Ok(count) => {
+                let count = count as i32;
+                (Some(count), None, Ok(Response::new(ReplayedEvents { count })))
+            }
+            Err((error, status)) => (None, Some(error), Err(status)),
+        };
+        let req = Request::new(ReplayEventsEvent {
+            error,
+            from: data.from.clone(),
+            to: data.to.clone(),
+            entity_type: data.entity_type.clone(),
+            count,
+            correlation_id: correlation_id()
+        });
+        let service = self.boards_eventbus_service_client.clone();
+        spawn_tracked(async move {
+            send_event!(service, replay_events_event, req).await;
+        });
+        result
+    }
+}
+
+/// The window and outbox methods of a replay request, or why it is invalid.
+fn replay_window(data: &ReplayEventsRequest) -> Result<(NaiveDateTime, NaiveDateTime, Option<&'static [&'static str]>), &'static str> {
+    let (from, to) = match (&data.from, &data.to) {
+        (Some(from), Some(to)) => (from_timestamp(from), from_timestamp(to)),
+        _ => return Err("from and to are required"),
+    };
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) if from < to => (from, to),
+        (Some(_), Some(_)) => return Err("from must be before to"),
+        _ => return Err("from and to must be valid timestamps"),
+    };
+    let methods = match data.entity_type.as_deref() {
+        None => None,
+        Some(entity_type) => Some(replay_methods(entity_type).ok_or("entityType must be issue")?),
+    };
+    Ok((from, to, methods))
+}
+
+fn to_proto_audit(audit: &DependencyAudit) -> ProtoDependencyAudit {
+    let to_proto = |dep: &Dependency| ProtoDependency {
+        id: dep.id.clone(),
+        blocking_epic_id: dep.blocking_epic_id.clone(),
+        blocked_epic_id: dep.blocked_epic_id.clone(),
+        dependency_type: dep.dependency_type.unwrap_or_default(),
+    };
+
+    ProtoDependencyAudit {
+        self_references: audit.self_references.iter().map(to_proto).collect(),
+        duplicates: audit.duplicates.iter().map(to_proto).collect(),
+        cycles: audit.cycles
+            .iter()
+            .map(|cycle| DependencyCycle {
+                dependencies: cycle.iter().map(to_proto).collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::Timestamp;
+
+    fn replay_request(from: i64, to: i64, entity_type: Option<&str>) -> ReplayEventsRequest {
+        ReplayEventsRequest {
+            from: Some(Timestamp { seconds: from, nanos: 0 }),
+            to: Some(Timestamp { seconds: to, nanos: 0 }),
+            entity_type: entity_type.map(String::from),
+        }
+    }
+
+    #[test]
+    fn replay_window_needs_an_ordered_window_and_a_known_entity() {
+        let (from, to, methods) = replay_window(&replay_request(10, 20, Some("issue"))).unwrap();
+        assert_eq!((from.and_utc().timestamp(), to.and_utc().timestamp()), (10, 20));
+        assert_eq!(methods, replay_methods("issue"));
+
+        assert!(matches!(replay_window(&replay_request(10, 20, None)), Ok((_, _, None))));
+        assert_eq!(replay_window(&replay_request(20, 20, None)).unwrap_err(), "from must be before to");
+        assert_eq!(replay_window(&replay_request(10, 20, Some("board"))).unwrap_err(), "entityType must be issue");
+
+        let missing = ReplayEventsRequest { to: None, ..replay_request(10, 20, None) };
+        assert_eq!(replay_window(&missing).unwrap_err(), "from and to are required");
+    }
+}
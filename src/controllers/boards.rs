@@ -1,81 +1,229 @@
-use diesel::{RunQueryDsl, QueryDsl, ExpressionMethods, QueryResult, result::Error::NotFound};
+use std::{pin::Pin, time::SystemTime, collections::{HashMap, HashSet}};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use prost_types::Timestamp;
+use diesel::{
+    RunQueryDsl,
+    QueryDsl,
+    ExpressionMethods, BoolExpressionMethods, QueryResult, result::Error::NotFound,
+    JoinOnDsl, PgConnection,
+    pg::Pg,
+};
 use tonic::{Request, Response, Status, Code, transport::Channel};
+use futures::Stream;
 use proto::{
     issues::{
         Board as ProtoBoard,
         BoardId,
+        BoardLookup,
+        GetBoardRequest,
+        BoardExpand,
+        ColumnTree,
+        Column as ProtoColumn,
         ProjectId,
+        GetBoardByProjectIdRequest,
+        IssueId,
+        EpicId,
+        UpdateBoardRequest,
+        DeleteBoardRequest,
+        BoardDeletionPlan,
+        SearchBoardsParams,
+        BoardsOrder,
         boards_service_server::BoardsService
     }, 
     eventbus::{
         self,
         boards_events_service_client::BoardsEventsServiceClient,
+        columns_events_service_client::ColumnsEventsServiceClient,
+        epics_events_service_client::EpicsEventsServiceClient,
+        issues_events_service_client::IssuesEventsServiceClient,
+        dependencies_events_service_client::DependenciesEventsServiceClient,
         BoardEvent,
+        SearchBoardsEvent,
+        ColumnEvent,
+        EpicEvent,
+        IssueEvent,
+        DependencyEvent,
     },
 };
 
 use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    controllers::{epics::{to_proto_epic, StoredDateOutOfRange}, issues::to_proto_issue},
+    ids::new_id,
+    board_policy::BoardsPerProject,
+    default_sort::BoardSort,
+    limits::{TextLimits, check_not_blank},
+    cache::ReadCache,
+    deletion_tokens::DeletionTokens,
+    events::{EventPayload, db_failure, pooled_connection, rejection, send_event},
+    pagination::{Page, max_page_size, requested},
+    streams::StreamLimit,
     db::{
-        repos::board::{Board, NewBoard, DeleteBoard, CreateBoard},
-        schema::boards::dsl::*, 
+        repos::{
+            board::{Board, DeletedBoard, NewBoard, BoardChangeSet, UpdateBoard, DeleteBoard, DeleteBoardError, CreateBoard, CreateOnlyBoard, CreatedBoard,
+                GetOrCreateBoard},
+            column::Column,
+            epic::Epic,
+            issue::Issue,
+        },
+        schema::{boards::{self as boards_schema, dsl::*}, columns, epics, issues, dependencies}, 
         connection::PgPool,
     },
 };
 
 pub struct BoardsController {
     pub pool: PgPool,
-    pub eventbus_service_client: BoardsEventsServiceClient<Channel>
+    /// Serves the get and search handlers; see `establish_read_connection`.
+    pub read_pool: PgPool,
+    pub eventbus_service_client: Option<BoardsEventsServiceClient<Channel>>,
+    /// Clients for the events about what a board deletion takes with it.
+    pub columns_eventbus_service_client: Option<ColumnsEventsServiceClient<Channel>>,
+    pub epics_eventbus_service_client: Option<EpicsEventsServiceClient<Channel>>,
+    pub issues_eventbus_service_client: Option<IssuesEventsServiceClient<Channel>>,
+    pub dependencies_eventbus_service_client: Option<DependenciesEventsServiceClient<Channel>>,
+    pub event_payload: EventPayload,
+    pub stream_limit: StreamLimit,
+    pub read_cache: ReadCache<ProtoBoard>,
+    /// The `ColumnsController` cache, so a board deletion can drop the
+    /// columns it removed.
+    pub column_cache: ReadCache<ProtoColumn>,
+    pub deletion_tokens: DeletionTokens,
+    pub boards_per_project: BoardsPerProject,
+    pub text_limits: TextLimits,
+    pub default_sort: BoardSort,
 }
 
 #[tonic::async_trait]
 impl BoardsService for BoardsController {
     async fn get_board_by_id(
         &self,
-        request: Request<BoardId>,
+        request: Request<GetBoardRequest>,
     ) -> Result<Response<ProtoBoard>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let expand = BoardExpand::from_i32(data.expand).unwrap_or(BoardExpand::None);
 
-        let result: QueryResult<Vec<Board>> = boards
+        // Only unexpanded boards are cached; children change independently.
+        let cached = match expand {
+            BoardExpand::None => self.read_cache.get(&data.board_id),
+            _ => None,
+        };
+
+        if let Some(cached) = cached {
+            let board = eventbus::Board {
+                id: Some(cached.id.clone()),
+                project_id: Some(cached.project_id.clone()),
+                deletion_protected: Some(cached.deletion_protected)
+            };
+            let req = Request::new(BoardEvent {
+                board: Some(self.event_payload.apply(board)),
+                error: None,
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, get_board_by_id_event, req).await;
+            });
+            return Ok(Response::new(cached));
+        }
+
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<(Vec<Board>, Vec<ColumnRows>)> = boards
             .filter(id.eq(data.board_id.clone()))
             .limit(1)
-            .load::<Board>(&*db_connection);
+            .load::<Board>(&*db_connection)
+            .and_then(|vec| {
+                let column_rows = match (vec.first(), expand) {
+                    (Some(brd), BoardExpand::Columns | BoardExpand::Full) => {
+                        load_column_trees(&brd.id, expand, &db_connection)?
+                    }
+                    _ => Vec::new(),
+                };
+                Ok((vec, column_rows))
+            });
 
         match result {
-            Ok(vec) => {
+            Ok((vec, column_rows)) => {
                 if let Some(brd) = vec.first() {
                     let board = eventbus::Board {
                         id: Some(brd.id.clone()),
-                        project_id: Some(brd.project_id.clone())
+                        project_id: Some(brd.project_id.clone()),
+                        deletion_protected: Some(brd.deletion_protected)
+                    };
+                    let column_trees = match column_rows
+                        .into_iter()
+                        .map(to_column_tree)
+                        .collect::<Result<Vec<ColumnTree>, _>>()
+                    {
+                        Ok(column_trees) => column_trees,
+                        Err(out_of_range) => {
+                            let (error, status) = out_of_range.failure();
+                            let req = Request::new(BoardEvent {
+                                board: Some(self.event_payload.apply(board)),
+                                error: Some(error),
+                                correlation_id: correlation_id()
+                            });
+                            let service = self.eventbus_service_client.clone();
+                            spawn_tracked(async move {
+                                send_event!(service, get_board_by_id_event, req).await;
+                            });
+                            return Err(status);
+                        }
                     };
                     let req = Request::new(BoardEvent {
-                        board: Some(board),
-                        error: None
+                        board: Some(self.event_payload.apply(board)),
+                        error: None,
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_board_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_board_by_id_event, req).await;
                     });
-                    Ok(Response::new(ProtoBoard {
+                    let proto_board = ProtoBoard {
                         id: brd.id.clone(),
                         project_id: brd.project_id.clone(),
-                    }))
+                        columns: column_trees,
+                        deletion_protected: brd.deletion_protected,
+                    };
+                    if expand == BoardExpand::None {
+                        self.read_cache.insert(brd.id.clone(), proto_board.clone());
+                    }
+                    Ok(Response::new(proto_board))
                 } else {
                     let board = eventbus::Board {
                         id: Some(data.board_id.clone()),
-                        project_id: None
+                        project_id: None,
+                        deletion_protected: None
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: String::from("Board not found")
                     };
                     let req = Request::new(BoardEvent {
-                        board: Some(board),
-                        error: Some(error)
+                        board: Some(self.event_payload.apply(board)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn( async move {
-                        service.get_board_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_board_by_id_event, req).await;
                     });
                     Err(Status::not_found("Board not found"))
                 }
@@ -83,34 +231,69 @@ impl BoardsService for BoardsController {
             Err(err) => {
                 let board = eventbus::Board {
                     id: Some(data.board_id.clone()),
-                    project_id: None
-                };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                    project_id: None,
+                    deletion_protected: None
                 };
+                let (error, status) = db_failure(&err);
                 let req = Request::new(BoardEvent {
-                    board: Some(board),
-                    error: Some(error)
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.get_board_by_id_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_id_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             }
         }
     }
 
+    async fn find_board_by_id(
+        &self,
+        request: Request<GetBoardRequest>,
+    ) -> Result<Response<BoardLookup>, Status> {
+        match self.get_board_by_id(request).await {
+            Ok(response) => Ok(Response::new(BoardLookup {
+                found: true,
+                board: Some(response.into_inner()),
+            })),
+            Err(status) if status.code() == Code::NotFound => Ok(Response::new(BoardLookup {
+                found: false,
+                board: Some(ProtoBoard::default()),
+            })),
+            Err(status) => Err(status),
+        }
+    }
+
     async fn get_board_by_project_id(
         &self,
-        request: Request<ProjectId>,
+        request: Request<GetBoardByProjectIdRequest>,
     ) -> Result<Response<ProtoBoard>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        if data.create_if_missing {
+            return self.get_or_create_board(data).await;
+        }
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_project_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         let result: QueryResult<Vec<Board>> = boards
             .filter(project_id.eq(&request.get_ref().project_id))
+            .order(id.asc())
             .limit(1)
             .load::<Board>(&*db_connection);
 
@@ -119,36 +302,42 @@ impl BoardsService for BoardsController {
                 if let Some(brd) = vec.first() {
                     let board = eventbus::Board {
                         id: Some(brd.id.clone()),
-                        project_id: Some(brd.project_id.clone())
+                        project_id: Some(brd.project_id.clone()),
+                        deletion_protected: Some(brd.deletion_protected)
                     };
                     let req = Request::new(BoardEvent {
-                        board: Some(board),
-                        error: None
+                        board: Some(self.event_payload.apply(board)),
+                        error: None,
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_board_by_project_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_board_by_project_id_event, req).await;
                     });
                     Ok(Response::new(ProtoBoard {
                         id: brd.id.clone(),
                         project_id: brd.project_id.clone(),
+                        columns: Vec::new(),
+                        deletion_protected: brd.deletion_protected,
                     }))
                 } else {
                     let board = eventbus::Board {
                         id: None,
-                        project_id: Some(data.project_id.clone())
+                        project_id: Some(data.project_id.clone()),
+                        deletion_protected: None
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: String::from("Board not found")
                     };
                     let req = Request::new(BoardEvent {
-                        board: Some(board),
-                        error: Some(error)
+                        board: Some(self.event_payload.apply(board)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_board_by_project_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_board_by_project_id_event, req).await;
                     });
                     Err(Status::not_found("Board not found"))
                 }
@@ -156,21 +345,351 @@ impl BoardsService for BoardsController {
             Err(err) => {
                 let board = eventbus::Board {
                     id: None,
-                    project_id: Some(data.project_id.clone())
+                    project_id: Some(data.project_id.clone()),
+                    deletion_protected: None
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                let (error, status) = db_failure(&err);
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_project_id_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn get_board_for_issue(
+        &self,
+        request: Request<IssueId>,
+    ) -> Result<Response<ProtoBoard>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_for_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<Board> = boards
+            .inner_join(columns::table.on(columns::board_id.eq(id)))
+            .inner_join(issues::table.on(issues::column_id.eq(columns::id)))
+            .filter(issues::id.eq(&data.issue_id))
+            .select((id, project_id, deletion_protected))
+            .first::<Board>(&*db_connection);
+
+        match result {
+            Ok(brd) => {
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_for_issue_event, req).await;
+                });
+                Ok(Response::new(ProtoBoard {
+                    id: brd.id,
+                    project_id: brd.project_id,
+                    columns: Vec::new(),
+                    deletion_protected: brd.deletion_protected,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Board not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let board = eventbus::Board {
+                    id: None,
+                    project_id: None,
+                    deletion_protected: None
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_for_issue_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn get_board_for_epic(
+        &self,
+        request: Request<EpicId>,
+    ) -> Result<Response<ProtoBoard>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_for_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<Board> = boards
+            .inner_join(columns::table.on(columns::board_id.eq(id)))
+            .inner_join(epics::table.on(epics::column_id.eq(columns::id)))
+            .filter(epics::id.eq(&data.epic_id))
+            .select((id, project_id, deletion_protected))
+            .first::<Board>(&*db_connection);
+
+        match result {
+            Ok(brd) => {
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_for_epic_event, req).await;
+                });
+                Ok(Response::new(ProtoBoard {
+                    id: brd.id,
+                    project_id: brd.project_id,
+                    columns: Vec::new(),
+                    deletion_protected: brd.deletion_protected,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Board not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let board = eventbus::Board {
+                    id: None,
+                    project_id: None,
+                    deletion_protected: None
                 };
                 let req = Request::new(BoardEvent {
-                    board: Some(board),
-                    error: Some(error)
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.get_board_by_project_id_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_for_epic_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
+            }
+        }
+    }
+
+    type getBoardsByProjectIdStream = Pin<Box<dyn Stream<Item = Result<ProtoBoard, Status>> + Send>>;
+
+    async fn get_boards_by_project_id(
+        &self,
+        request: Request<ProjectId>,
+    ) -> Result<Response<Self::getBoardsByProjectIdStream>, Status> {
+        // Not the default page size: projects rarely have many boards and
+        // callers expect all of them, up to the usual cap on a search.
+        let params = SearchBoardsParams {
+            project_id: Some(request.into_inner().project_id),
+            limit: i32::try_from(max_page_size()).ok(),
+            ..Default::default()
+        };
+        self.search_boards(Request::new(params)).await
+    }
+
+    type searchBoardsStream = Pin<Box<dyn Stream<Item = Result<ProtoBoard, Status>> + Send>>;
+
+    async fn search_boards(
+        &self,
+        request: Request<SearchBoardsParams>,
+    ) -> Result<Response<Self::searchBoardsStream>, Status> {
+        let data = request.get_ref();
+
+        let (limit, offset) = requested(data.page.as_ref(), data.limit, data.offset);
+        let page = match Page::from_params(limit, offset) {
+            Ok(page) => page,
+            Err(message) => {
+                let search_params = eventbus::SearchBoardsParams {
+                    boards_ids: data.boards_ids.clone(),
+                    project_id: data.project_id.clone(),
+                    limit,
+                    offset,
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(SearchBoardsEvent {
+                    boards: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_boards_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        let limit = page.effective_limit();
+
+        let permit = self.stream_limit.acquire()?;
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SearchBoardsEvent {
+                    boards: Vec::new(),
+                    error: Some(error),
+                    search_params: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_boards_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let mut query = boards.into_boxed();
+
+        if !data.boards_ids.is_empty() {
+            query = query.filter(id.eq_any(&data.boards_ids));
+        }
+
+        if let Some(prjct_id) = &data.project_id {
+            query = query.filter(project_id.eq(prjct_id));
+        }
+
+        query = query.limit(page.limit);
+
+        if let Some(page_offset) = page.offset {
+            query = query.offset(page_offset);
+        }
+
+        let sort = self.default_sort.or_requested(data.order_by, data.descending);
+        let result: QueryResult<Vec<Board>> = order_boards(query, sort)
+            .load::<Board>(&*db_connection);
+
+        let search_params = eventbus::SearchBoardsParams {
+            boards_ids: data.boards_ids.clone(),
+            project_id: data.project_id.clone(),
+            limit,
+            offset,
+        };
+
+        match result {
+            Ok(vec) => {
+                let brds = vec
+                    .iter()
+                    .map(|board| eventbus::Board {
+                        id: Some(board.id.clone()),
+                        project_id: Some(board.project_id.clone()),
+                        deletion_protected: Some(board.deletion_protected)
+                    })
+                    .collect::<Vec<eventbus::Board>>();
+                let req = Request::new(SearchBoardsEvent {
+                    boards: self.event_payload.apply_all(brds),
+                    error: None,
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                let proto_boards: Vec<ProtoBoard> = vec.into_iter().map(|board| ProtoBoard {
+                    id: board.id,
+                    project_id: board.project_id,
+                    columns: Vec::new(),
+                    deletion_protected: board.deletion_protected,
+                }).collect();
+
+                let mut stream = tokio_stream::iter(proto_boards);
+                let (sender, receiver) = mpsc::channel(1);
+
+                spawn_tracked(async move {
+                    let _permit = permit;
+                    let mut delivered = true;
+                    while let Some(board) = stream.next().await {
+                        if sender.send(Result::<ProtoBoard, Status>::Ok(board)).await.is_err() {
+                            delivered = false;
+                            break;
+                        }
+                    }
+                    if delivered {
+                        send_event!(service, search_boards_event, req).await;
+                    }
+                });
+                let output_stream = ReceiverStream::new(receiver);
+
+                Ok(Response::new(
+                    Box::pin(output_stream) as Self::searchBoardsStream
+                ))
+            }
+            Err(err) => {
+                let brds = data.boards_ids
+                    .iter()
+                    .map(|board_id| eventbus::Board {
+                        id: Some(board_id.to_owned()),
+                        project_id: None,
+                        deletion_protected: None
+                    })
+                    .collect::<Vec<eventbus::Board>>();
+                let (error, status) = db_failure(&err);
+                let req = Request::new(SearchBoardsEvent {
+                    boards: self.event_payload.apply_all(brds),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_boards_event, req).await;
+                });
+                Err(status)
             }
         }
     }
@@ -180,118 +699,735 @@ impl BoardsService for BoardsController {
         request: Request<ProjectId>,
     ) -> Result<Response<ProtoBoard>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_board_event, req).await;
+                });
+                return Err(status);
+            }
+        };
         let new_board = NewBoard {
-            id: &uuid::Uuid::new_v4().to_string(),
+            id: &new_id(),
             project_id: &request.get_ref().project_id,
         };
 
-        match Board::create(new_board, db_connection).await {
-            Ok(brd) => {
+        let created = match self.boards_per_project {
+            BoardsPerProject::Many => Board::create(new_board, db_connection).await.map(CreatedBoard::Created),
+            BoardsPerProject::Reuse | BoardsPerProject::Reject => Board::create_only(new_board, db_connection).await,
+        };
+
+        match created {
+            Ok(CreatedBoard::Existing(brd)) => {
                 let board = eventbus::Board {
                     id: Some(brd.id.clone()),
-                    project_id: Some(brd.project_id.clone())
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
+                };
+                // Reusing the board answers the call like a create, so it is
+                // reported like one, the way reconcile re-emits create events
+                // for boards that already exist.
+                let (error, result) = match self.boards_per_project {
+                    BoardsPerProject::Reject => {
+                        let (error, status) = rejection(Code::AlreadyExists, "Project already has a board");
+                        (Some(error), Err(status))
+                    }
+                    _ => (None, Ok(())),
                 };
                 let req = Request::new(BoardEvent {
-                    board: Some(board),
-                    error: None
+                    board: Some(self.event_payload.apply(board)),
+                    error,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_board_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_board_event, req).await;
+                });
+                result?;
+                Ok(Response::new(ProtoBoard {
+                    id: brd.id,
+                    project_id: brd.project_id,
+                    columns: Vec::new(),
+                    deletion_protected: brd.deletion_protected,
+                }))
+            }
+            Ok(CreatedBoard::Created(brd)) => {
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_board_event, req).await;
                 });
                 Ok(Response::new(ProtoBoard {
                     id: brd.id.clone(),
                     project_id: brd.project_id.clone(),
+                    columns: Vec::new(),
+                    deletion_protected: brd.deletion_protected,
                 }))
             }
             Err(err) => {
                 let board = eventbus::Board {
                     id: None,
-                    project_id: Some(data.project_id.clone())
+                    project_id: Some(data.project_id.clone()),
+                    deletion_protected: None
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                let (error, status) = db_failure(&err);
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_board_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn update_board(
+        &self,
+        request: Request<UpdateBoardRequest>,
+    ) -> Result<Response<ProtoBoard>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_board_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let change_set = BoardChangeSet {
+            deletion_protected: data.deletion_protected,
+        };
+
+        match Board::update(&data.board_id, change_set, db_connection).await {
+            Ok(brd) => {
+                self.read_cache.invalidate(&brd.id);
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
                 };
                 let req = Request::new(BoardEvent {
-                    board: Some(board),
-                    error: Some(error)
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_board_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_board_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Ok(Response::new(ProtoBoard {
+                    id: brd.id,
+                    project_id: brd.project_id,
+                    columns: Vec::new(),
+                    deletion_protected: brd.deletion_protected,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Board not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let board = eventbus::Board {
+                    id: Some(data.board_id.clone()),
+                    project_id: None,
+                    deletion_protected: data.deletion_protected
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_board_event, req).await;
+                });
+                Err(status)
             }
         }
     }
 
-    async fn delete_board(
+    async fn prepare_board_deletion(
         &self,
         request: Request<BoardId>,
+    ) -> Result<Response<BoardDeletionPlan>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, prepare_board_deletion_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<(Board, i64, i64, i64, i64)> = (|| {
+            let brd: Board = boards
+                .filter(id.eq(&data.board_id))
+                .first::<Board>(&*db_connection)?;
+
+            let board_columns = columns::dsl::columns
+                .filter(columns::dsl::board_id.eq(&data.board_id))
+                .select(columns::dsl::id);
+            let board_epics = epics::dsl::epics
+                .filter(epics::dsl::column_id.eq_any(board_columns))
+                .select(epics::dsl::id);
+
+            let columns_count: i64 = columns::dsl::columns
+                .filter(columns::dsl::board_id.eq(&data.board_id))
+                .count()
+                .get_result(&*db_connection)?;
+            let epics_count: i64 = epics::dsl::epics
+                .filter(epics::dsl::column_id.eq_any(board_columns))
+                .count()
+                .get_result(&*db_connection)?;
+            let issues_count: i64 = issues::dsl::issues
+                .filter(issues::dsl::column_id.eq_any(board_columns))
+                .count()
+                .get_result(&*db_connection)?;
+            let dependencies_count: i64 = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq_any(board_epics)
+                    .or(dependencies::dsl::blocked_epic_id.eq_any(board_epics)))
+                .count()
+                .get_result(&*db_connection)?;
+
+            Ok((brd, columns_count, epics_count, issues_count, dependencies_count))
+        })();
+
+        match result {
+            Ok((brd, columns_count, epics_count, issues_count, dependencies_count)) => {
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, prepare_board_deletion_event, req).await;
+                });
+
+                Ok(Response::new(BoardDeletionPlan {
+                    token: self.deletion_tokens.issue(&brd.id),
+                    expires_at: Some(Timestamp::from(SystemTime::now() + self.deletion_tokens.ttl())),
+                    columns: columns_count as i32,
+                    epics: epics_count as i32,
+                    issues: issues_count as i32,
+                    dependencies: dependencies_count as i32,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Board not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let board = eventbus::Board {
+                    id: Some(data.board_id.clone()),
+                    project_id: None,
+                    deletion_protected: None
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, prepare_board_deletion_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn delete_board(
+        &self,
+        request: Request<DeleteBoardRequest>,
     ) -> Result<Response<ProtoBoard>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
-        
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_board_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        // Put back if the deletion fails, so the caller can retry with it.
+        let token_claim = match self.deletion_tokens.claim(&data.confirmation_token, &data.board_id) {
+            Some(token_claim) => token_claim,
+            None => {
+                let board = eventbus::Board {
+                    id: Some(data.board_id.clone()),
+                    project_id: None,
+                    deletion_protected: None
+                };
+                let (error, status) = rejection(Code::FailedPrecondition, "Invalid or expired confirmation token");
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_board_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
         match Board::delete(&data.board_id, db_connection).await {
-            Ok(brd) => {
+            Ok(deleted) => {
+                token_claim.confirm();
+                let brd = &deleted.board;
+                self.read_cache.invalidate(&brd.id);
+                for clmn in &deleted.columns {
+                    self.column_cache.invalidate(&clmn.id);
+                }
+                self.send_cascade_events(&deleted);
                 let board = eventbus::Board {
                     id: Some(brd.id.clone()),
-                    project_id: Some(brd.project_id.clone())
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
                 };
                 let req = Request::new(BoardEvent {
-                    board: Some(board),
-                    error: None
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.delete_board_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_board_event, req).await;
                 });
                 Ok(Response::new(ProtoBoard {
                     id: brd.id.clone(),
                     project_id: brd.project_id.clone(),
+                    columns: Vec::new(),
+                    deletion_protected: brd.deletion_protected,
                 }))
             }
             Err(err) => {
-                if err == NotFound {
-                    let board = eventbus::Board {
-                        id: Some(data.board_id.clone()),
-                        project_id: None
-                    };
-                    let error = eventbus::Error {
-                        code: Code::NotFound.into(),
-                        message: err.to_string()
-                    };
+                let (code, message, status) = match err {
+                    DeleteBoardError::Protected => {
+                        let message = "board is deletion-protected";
+                        (Code::FailedPrecondition, message.to_string(), Status::failed_precondition(message))
+                    }
+                    DeleteBoardError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Board not found"),
+                    ),
+                    DeleteBoardError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let board = eventbus::Board {
+                    id: Some(data.board_id.clone()),
+                    project_id: None,
+                    deletion_protected: None
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_board_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+}
+
+impl BoardsController {
+    /// The default columns of a new board get the checks `createColumn`
+    /// makes, and must have distinct names.
+    fn check_default_columns(&self, column_names: &[String]) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for column_name in column_names {
+            check_not_blank(Some(column_name), "column name required")?;
+            self.text_limits.check_title("defaultColumns", Some(column_name))?;
+            if !seen.insert(column_name.as_str()) {
+                return Err(format!("defaultColumns has {} more than once", column_name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete events for everything removed together with a board.
+    fn send_cascade_events(&self, deleted: &DeletedBoard) {
+        for dep in &deleted.dependencies {
+            let dependency = eventbus::Dependency {
+                id: Some(dep.id.clone()),
+                blocking_epic_id: Some(dep.blocking_epic_id.clone()),
+                blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                dependency_type: Some(dep.dependency_type.unwrap_or_default()),
+            };
+            let req = Request::new(DependencyEvent {
+                dependency: Some(self.event_payload.apply(dependency)),
+                error: None,
+                correlation_id: correlation_id()
+            });
+            let service = self.dependencies_eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, delete_dependency_event, req).await;
+            });
+        }
+
+        for iss in &deleted.issues {
+            let issue = eventbus::Issue {
+                id: Some(iss.id.clone()),
+                column_id: Some(iss.column_id.clone()),
+                epic_id: Some(iss.epic_id.clone()),
+                title: Some(iss.title.clone()),
+                description: Some(iss.description.clone()),
+                status: Some(iss.status.into()),
+            };
+            let req = Request::new(IssueEvent {
+                issue: Some(self.event_payload.apply(issue)),
+                error: None,
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.issues_eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, delete_issue_event, req).await;
+            });
+        }
+
+        for ep in &deleted.epics {
+            let epic = eventbus::Epic {
+                id: Some(ep.id.clone()),
+                column_id: Some(ep.column_id.clone()),
+                assignee_id: ep.assignee_id.clone(),
+                reporter_id: Some(ep.reporter_id.clone()),
+                name: Some(ep.name.clone()),
+                description: ep.description.clone(),
+                start_date: ep.start_date.map(|date| date.to_string()),
+                due_date: ep.due_date.map(|date| date.to_string()),
+                progress_percent: Some(ep.progress_percent),
+                metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+            };
+            let req = Request::new(EpicEvent {
+                epic: Some(self.event_payload.apply(epic)),
+                error: None,
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.epics_eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, delete_epic_event, req).await;
+            });
+        }
+
+        for clmn in &deleted.columns {
+            let column = eventbus::Column {
+                id: Some(clmn.id.clone()),
+                board_id: Some(clmn.board_id.clone()),
+                name: Some(clmn.name.clone()),
+                archived: Some(clmn.archived),
+            };
+            let req = Request::new(ColumnEvent {
+                column: Some(self.event_payload.apply(column)),
+                error: None,
+                correlation_id: correlation_id()
+            });
+            let service = self.columns_eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, delete_column_event, req).await;
+            });
+        }
+    }
+
+    /// `getBoardByProjectId` with `createIfMissing`. Runs on the primary pool
+    /// so a board another call just created is found rather than duplicated.
+    async fn get_or_create_board(
+        &self,
+        data: &GetBoardByProjectIdRequest,
+    ) -> Result<Response<ProtoBoard>, Status> {
+        if let Err(message) = self.check_default_columns(&data.default_columns) {
+            let board = eventbus::Board {
+                id: None,
+                project_id: Some(data.project_id.clone()),
+                deletion_protected: None
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(BoardEvent {
+                board: Some(self.event_payload.apply(board)),
+                error: Some(error),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, get_board_by_project_id_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(BoardEvent {
+                    board: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_project_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        let new_board = NewBoard {
+            id: &new_id(),
+            project_id: &data.project_id,
+        };
+
+        match Board::get_or_create(new_board, &data.default_columns, db_connection).await {
+            Ok((created, board_columns)) => {
+                let (brd, was_created) = match created {
+                    CreatedBoard::Created(brd) => (brd, true),
+                    CreatedBoard::Existing(brd) => (brd, false),
+                };
+                let board = eventbus::Board {
+                    id: Some(brd.id.clone()),
+                    project_id: Some(brd.project_id.clone()),
+                    deletion_protected: Some(brd.deletion_protected)
+                };
+                if was_created {
                     let req = Request::new(BoardEvent {
-                        board: Some(board),
-                        error: Some(error)
+                        board: Some(self.event_payload.apply(board.clone())),
+                        error: None,
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_board_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_board_event, req).await;
                     });
-                    Err(Status::not_found("Board not found"))
-                } else {
-                    let board = eventbus::Board {
-                        id: Some(data.board_id.clone()),
-                        project_id: None
-                    };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
+                }
+                for clmn in &board_columns {
+                    let column = eventbus::Column {
+                        id: Some(clmn.id.clone()),
+                        board_id: Some(clmn.board_id.clone()),
+                        name: Some(clmn.name.clone()),
+                        archived: Some(clmn.archived),
                     };
-                    let req = Request::new(BoardEvent {
-                        board: Some(board),
-                        error: Some(error)
+                    let req = Request::new(ColumnEvent {
+                        column: Some(self.event_payload.apply(column)),
+                        error: None,
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_board_event(req).await;
+                    let service = self.columns_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_column_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
                 }
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_project_id_event, req).await;
+                });
+                Ok(Response::new(ProtoBoard {
+                    id: brd.id,
+                    project_id: brd.project_id,
+                    columns: board_columns
+                        .into_iter()
+                        .map(|clmn| ColumnTree {
+                            column: Some(ProtoColumn {
+                                id: clmn.id,
+                                board_id: clmn.board_id,
+                                name: clmn.name,
+                                position: clmn.position,
+                                archived: clmn.archived,
+                            }),
+                            epics: Vec::new(),
+                            issues: Vec::new(),
+                        })
+                        .collect(),
+                    deletion_protected: brd.deletion_protected,
+                }))
+            }
+            Err(err) => {
+                let board = eventbus::Board {
+                    id: None,
+                    project_id: Some(data.project_id.clone()),
+                    deletion_protected: None
+                };
+                let (error, status) = db_failure(&err);
+                let req = Request::new(BoardEvent {
+                    board: Some(self.event_payload.apply(board)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_board_by_project_id_event, req).await;
+                });
+                Err(status)
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn order_boards(
+    query: boards_schema::BoxedQuery<'_, Pg>,
+    sort: BoardSort,
+) -> boards_schema::BoxedQuery<'_, Pg> {
+    match (sort.order, sort.descending) {
+        (BoardsOrder::Id, false) => query.order(id.asc()),
+        (BoardsOrder::Id, true) => query.order(id.desc()),
+        (BoardsOrder::ProjectId, false) => query.order((project_id.asc(), id.asc())),
+        (BoardsOrder::ProjectId, true) => query.order((project_id.desc(), id.desc())),
+    }
+}
+
+/// A column with, for `BoardExpand::Full`, its epics and issues.
+type ColumnRows = (Column, Vec<Epic>, Vec<Issue>);
+
+/// Loads the board's columns in position order and, for `Full`, their epics
+/// and their issues in rank order, joining through columns so each level is
+/// a single query.
+fn load_column_trees(
+    board: &str,
+    expand: BoardExpand,
+    db_connection: &PgConnection,
+) -> QueryResult<Vec<ColumnRows>> {
+    let board_columns: Vec<Column> = columns::table
+        .filter(columns::board_id.eq(board))
+        .order((columns::position.asc(), columns::id.asc()))
+        .load::<Column>(db_connection)?;
+
+    let mut epics_by_column: HashMap<String, Vec<Epic>> = HashMap::new();
+    let mut issues_by_column: HashMap<String, Vec<Issue>> = HashMap::new();
+
+    if expand == BoardExpand::Full {
+        let board_epics: Vec<Epic> = epics::table
+            .inner_join(columns::table.on(epics::column_id.eq(columns::id)))
+            .filter(columns::board_id.eq(board))
+            .select(epics::all_columns)
+            .order(epics::id.asc())
+            .load::<Epic>(db_connection)?;
+
+        let board_issues: Vec<Issue> = issues::table
+            .inner_join(columns::table.on(issues::column_id.eq(columns::id)))
+            .filter(columns::board_id.eq(board))
+            .select(issues::all_columns)
+            .order((issues::rank.asc(), issues::id.asc()))
+            .load::<Issue>(db_connection)?;
+
+        for ep in board_epics {
+            epics_by_column
+                .entry(ep.column_id.clone())
+                .or_default()
+                .push(ep);
+        }
+
+        for iss in board_issues {
+            issues_by_column
+                .entry(iss.column_id.clone())
+                .or_default()
+                .push(iss);
+        }
+    }
+
+    Ok(board_columns
+        .into_iter()
+        .map(|clmn| {
+            let column_epics = epics_by_column.remove(&clmn.id).unwrap_or_default();
+            let column_issues = issues_by_column.remove(&clmn.id).unwrap_or_default();
+            (clmn, column_epics, column_issues)
+        })
+        .collect())
+}
+
+/// Builds the API tree of one column, with its dates checked by
+/// `to_proto_epic` and `to_proto_issue`.
+fn to_column_tree((clmn, column_epics, column_issues): ColumnRows) -> Result<ColumnTree, StoredDateOutOfRange> {
+    Ok(ColumnTree {
+        epics: column_epics.iter().map(to_proto_epic).collect::<Result<_, _>>()?,
+        issues: column_issues.iter().map(to_proto_issue).collect::<Result<_, _>>()?,
+        column: Some(ProtoColumn {
+            id: clmn.id,
+            board_id: clmn.board_id,
+            name: clmn.name,
+            position: clmn.position,
+            archived: clmn.archived,
+        }),
+    })
+}
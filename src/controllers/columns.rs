@@ -10,27 +10,56 @@ use proto::{
         columns_service_server::ColumnsService, 
         Column as ProtoColumn, 
         ColumnId,
+        ColumnLookup,
         BoardIdAndColumnName,
         ColumnIdAndName,
+        ColumnIdPair,
+        Columns,
+        ColumnsIds,
+        ColumnsByIds,
+        BoardId,
+        NormalizedPositions,
+        ColumnsChunk,
+        ReorderColumnsRequest,
     },
     eventbus::{
         self,
         columns_events_service_client::ColumnsEventsServiceClient, 
         ColumnEvent, 
         SearchColumnsEvent,
+        SwapColumnsEvent,
+        NormalizePositionsEvent,
+        ReorderColumnsEvent,
     },
 };
 
 use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    ids::new_id,
+    cache::ReadCache,
+    events::{EventPayload, db_failure, pooled_connection, rejection, send_event},
+    pagination::{Page, requested, chunk_size, chunked},
+    streams::StreamLimit,
+    limits::TextLimits,
     db::{
-        repos::column::{NewColumn, Column, CreateColumn, UpdateColumn, ColumnChangeSet, DeleteColumn},
+        repos::column::{
+            NewColumn, Column, CreateColumn, UpdateColumn, UpdateColumnError, ColumnChangeSet, DeleteColumn,
+            SwapColumns, SwapColumnsError, NormalizePositions, NormalizedCounts, ReorderColumns, ReorderColumnsError,
+        },
         schema::columns::dsl::*, 
         connection::PgPool,
     },
 };
 pub struct ColumnsController {
     pub pool: PgPool,
-    pub eventbus_service_client: ColumnsEventsServiceClient<Channel>
+    /// Serves the get and search handlers; see `establish_read_connection`.
+    pub read_pool: PgPool,
+    pub eventbus_service_client: Option<ColumnsEventsServiceClient<Channel>>,
+    pub event_payload: EventPayload,
+    pub stream_limit: StreamLimit,
+    pub read_cache: ReadCache<ProtoColumn>,
+    pub text_limits: TextLimits,
 }
 
 #[tonic::async_trait]
@@ -40,7 +69,42 @@ impl ColumnsService for ColumnsController {
         request: Request<ColumnId>,
     ) -> Result<Response<ProtoColumn>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+
+        if let Some(cached) = self.read_cache.get(&data.column_id) {
+            let column = eventbus::Column {
+                id: Some(cached.id.clone()),
+                board_id: Some(cached.board_id.clone()),
+                name: Some(cached.name.clone()),
+                archived: Some(cached.archived),
+            };
+            let req = Request::new(ColumnEvent {
+                column: Some(self.event_payload.apply(column)),
+                error: None,
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, get_column_by_id_event, req).await;
+            });
+            return Ok(Response::new(cached));
+        }
+
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ColumnEvent {
+                    column: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_column_by_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         let result: QueryResult<Vec<Column>> = columns
             .filter(id.eq(&request.get_ref().column_id))
@@ -54,37 +118,45 @@ impl ColumnsService for ColumnsController {
                         id: Some(clmn.id.clone()),
                         board_id: Some(clmn.board_id.clone()),
                         name: Some(clmn.name.clone()),
+                        archived: Some(clmn.archived),
                     };
                     let req = Request::new(ColumnEvent {
-                        column: Some(column),
-                        error: None
+                        column: Some(self.event_payload.apply(column)),
+                        error: None,
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_column_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_column_by_id_event, req).await;
                     });
-                    Ok(Response::new(ProtoColumn {
+                    let proto_column = ProtoColumn {
                         id: clmn.id.clone(),
                         board_id: clmn.board_id.clone(),
                         name: clmn.name.clone(),
-                    }))
+                        position: clmn.position,
+                        archived: clmn.archived,
+                    };
+                    self.read_cache.insert(clmn.id.clone(), proto_column.clone());
+                    Ok(Response::new(proto_column))
                 } else {
                     let column = eventbus::Column {
                         id: Some(data.column_id.clone()),
                         board_id: None,
                         name: None,
+                        archived: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: String::from("Column not found")
                     };
                     let req = Request::new(ColumnEvent {
-                        column: Some(column),
-                        error: Some(error)
+                        column: Some(self.event_payload.apply(column)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_column_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_column_by_id_event, req).await;
                     });
                     Err(Status::not_found("Column not found"))
                 }
@@ -94,20 +166,133 @@ impl ColumnsService for ColumnsController {
                     id: Some(data.column_id.clone()),
                     board_id: None,
                     name: None,
+                    archived: None,
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
+                let (error, status) = db_failure(&err);
                 let req = Request::new(ColumnEvent {
-                    column: Some(column),
-                    error: Some(error)
+                    column: Some(self.event_payload.apply(column)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.get_column_by_id_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_column_by_id_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
+            }
+        }
+    }
+
+    async fn find_column_by_id(
+        &self,
+        request: Request<ColumnId>,
+    ) -> Result<Response<ColumnLookup>, Status> {
+        match self.get_column_by_id(request).await {
+            Ok(response) => Ok(Response::new(ColumnLookup {
+                found: true,
+                column: Some(response.into_inner()),
+            })),
+            Err(status) if status.code() == Code::NotFound => Ok(Response::new(ColumnLookup {
+                found: false,
+                column: Some(ProtoColumn::default()),
+            })),
+            Err(status) => Err(status),
+        }
+    }
+
+    async fn get_columns_by_ids(
+        &self,
+        request: Request<ColumnsIds>,
+    ) -> Result<Response<ColumnsByIds>, Status> {
+        let data = request.get_ref();
+        let search_params = eventbus::SearchColumnsParams {
+            board_id: None,
+            columns_ids: data.columns_ids.clone(),
+            limit: None,
+            offset: None,
+        };
+
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SearchColumnsEvent {
+                    columns: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_columns_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<Vec<Column>> = columns
+            .filter(id.eq_any(&data.columns_ids))
+            .order((position.asc(), id.asc()))
+            .load::<Column>(&*db_connection);
+
+        match result {
+            Ok(vec) => {
+                let mut missing_ids: Vec<String> = Vec::new();
+                for column_id in &data.columns_ids {
+                    if !vec.iter().any(|column| &column.id == column_id) && !missing_ids.contains(column_id) {
+                        missing_ids.push(column_id.clone());
+                    }
+                }
+
+                let clmns = vec
+                    .iter()
+                    .map(|column| eventbus::Column {
+                        id: Some(column.id.clone()),
+                        board_id: Some(column.board_id.clone()),
+                        name: Some(column.name.clone()),
+                        archived: Some(column.archived),
+                    })
+                    .collect::<Vec<eventbus::Column>>();
+                let req = Request::new(SearchColumnsEvent {
+                    columns: self.event_payload.apply_all(clmns),
+                    error: None,
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_columns_event, req).await;
+                });
+
+                let proto_columns: Vec<ProtoColumn> = vec
+                    .into_iter()
+                    .map(|column| ProtoColumn {
+                        id: column.id,
+                        board_id: column.board_id,
+                        name: column.name,
+                        position: column.position,
+                        archived: column.archived,
+                    })
+                    .collect();
+
+                Ok(Response::new(ColumnsByIds {
+                    columns: proto_columns,
+                    missing_ids,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(SearchColumnsEvent {
+                    columns: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_columns_event, req).await;
+                });
+                Err(status)
             }
         }
     }
@@ -119,7 +304,51 @@ impl ColumnsService for ColumnsController {
         request: Request<issues::SearchColumnsParams>,
     ) -> Result<Response<Self::searchColumnsStream>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+
+        let (limit, offset) = requested(data.page.as_ref(), data.limit, data.offset);
+        let page = match Page::from_params(limit, offset) {
+            Ok(page) => page,
+            Err(message) => {
+                let search_params = eventbus::SearchColumnsParams {
+                    board_id: data.board_id.clone(),
+                    columns_ids: data.columns_ids.clone(),
+                    limit,
+                    offset,
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(SearchColumnsEvent {
+                    columns: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_columns_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        let limit = page.effective_limit();
+
+        let permit = self.stream_limit.acquire()?;
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SearchColumnsEvent {
+                    columns: Vec::new(),
+                    error: Some(error),
+                    search_params: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_columns_event, req).await;
+                });
+                return Err(status);
+            }
+        };
         
         let mut query = columns.into_boxed();
 
@@ -136,7 +365,14 @@ impl ColumnsService for ColumnsController {
             query = query.filter(board_id.eq(brd_id));
         }
 
+        query = query.limit(page.limit);
+
+        if let Some(page_offset) = page.offset {
+            query = query.offset(page_offset);
+        }
+
         let result: QueryResult<Vec<Column>> = query
+            .order((position.asc(), id.asc()))
             .load::<Column>(&*db_connection);
 
         match result {
@@ -147,38 +383,46 @@ impl ColumnsService for ColumnsController {
                         id: Some(column.id.clone()),
                         board_id: Some(column.board_id.clone()),
                         name: Some(column.name.clone()),
+                        archived: Some(column.archived),
                     })
                     .collect::<Vec<eventbus::Column>>();
                 let search_params = eventbus::SearchColumnsParams {
                     board_id: data.board_id.clone(),
                     columns_ids: data.columns_ids.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
+                    limit,
+                    offset,
                 };
 
                 let req = Request::new(SearchColumnsEvent {
-                    columns: clmns,
+                    columns: self.event_payload.apply_all(clmns),
                     error: None,
-                    search_params: Some(search_params)
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
+                let service = self.eventbus_service_client.clone();
                 let proto_columns: Vec<ProtoColumn> = vec.iter().map(|column| ProtoColumn {
                     id: column.id.clone(),
                     board_id: column.board_id.clone(),
                     name: column.name.clone(),
+                    position: column.position,
+                    archived: column.archived,
                 }).collect();
         
                 let mut stream = tokio_stream::iter(proto_columns);
                 let (sender, receiver) = mpsc::channel(1);
         
-                tokio::spawn(async move {
+                spawn_tracked(async move {
+                    let _permit = permit;
+                    let mut delivered = true;
                     while let Some(column) = stream.next().await {
-                        match sender.send(Result::<ProtoColumn, Status>::Ok(column)).await {
-                            Ok(_) => {},
-                            Err(_err) => break
-                        };
-                    };
-                    service.search_columns_event(req).await;
+                        if sender.send(Result::<ProtoColumn, Status>::Ok(column)).await.is_err() {
+                            delivered = false;
+                            break;
+                        }
+                    }
+                    if delivered {
+                        send_event!(service, search_columns_event, req).await;
+                    }
                 });
                 let output_stream = ReceiverStream::new(receiver);
         
@@ -193,40 +437,90 @@ impl ColumnsService for ColumnsController {
                         id: Some(column_id.to_owned()),
                         board_id: None,
                         name: None,
+                        archived: None,
                     })
                     .collect::<Vec<eventbus::Column>>();
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
+                let (error, status) = db_failure(&err);
                 let req = Request::new(SearchColumnsEvent {
-                    columns: clmns,
+                    columns: self.event_payload.apply_all(clmns),
                     error: Some(error),
                     search_params: Some(eventbus::SearchColumnsParams {
                         board_id: data.board_id.clone(),
                         columns_ids: data.columns_ids.clone(),
-                        limit: data.limit.clone(),
-                        offset: data.offset.clone(),
-                    })
+                        limit,
+                        offset,
+                    }),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.search_columns_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_columns_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             }
         }
     }
 
+    type searchColumnsInChunksStream = Pin<Box<dyn Stream<Item = Result<ColumnsChunk, Status>> + Send>>;
+
+    async fn search_columns_in_chunks(
+        &self,
+        request: Request<issues::ChunkedSearchColumnsParams>,
+    ) -> Result<Response<Self::searchColumnsInChunksStream>, Status> {
+        let data = request.into_inner();
+        let size = chunk_size(data.chunk_size).map_err(Status::invalid_argument)?;
+        let rows = self.search_columns(Request::new(data.params.unwrap_or_default())).await?.into_inner();
+
+        Ok(Response::new(
+            Box::pin(chunked(rows, size, |rows| ColumnsChunk { columns: rows })) as Self::searchColumnsInChunksStream
+        ))
+    }
+
     async fn create_column(
         &self,
         request: Request<BoardIdAndColumnName>,
     ) -> Result<Response<ProtoColumn>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+
+        if let Err(message) = self.text_limits.check_title("columnName", Some(&data.column_name)) {
+            let column = eventbus::Column {
+                id: None,
+                board_id: Some(data.board_id.clone()),
+                name: Some(data.column_name.clone()),
+                archived: None,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(ColumnEvent {
+                column: Some(self.event_payload.apply(column)),
+                error: Some(error),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_column_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ColumnEvent {
+                    column: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_column_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         let new_column = NewColumn {
-            id: &uuid::Uuid::new_v4().to_string(),
+            id: &new_id(),
             board_id: &data.board_id,
             name: &data.column_name
         };
@@ -237,20 +531,24 @@ impl ColumnsService for ColumnsController {
                     id: Some(col.id.clone()),
                     board_id: Some(col.board_id.clone()),
                     name: Some(col.name.clone()),
+                    archived: Some(col.archived),
                 };
                 let req = Request::new(ColumnEvent {
-                    column: Some(column),
-                    error: None
+                    column: Some(self.event_payload.apply(column)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_column_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_column_event, req).await;
                 });
 
                 Ok(Response::new(ProtoColumn {
                     id: col.id.clone(),
                     board_id: col.board_id.clone(),
                     name: col.name.clone(),
+                    position: col.position,
+                    archived: col.archived,
                 }))
             },
             Err(err) => {
@@ -258,20 +556,19 @@ impl ColumnsService for ColumnsController {
                     id: None,
                     board_id: Some(data.board_id.clone()),
                     name: Some(data.column_name.clone()),
+                    archived: None,
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
+                let (error, status) = db_failure(&err);
                 let req = Request::new(ColumnEvent {
-                    column: Some(column),
-                    error: Some(error)
+                    column: Some(self.event_payload.apply(column)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_column_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_column_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             },
         }
     }
@@ -281,74 +578,117 @@ impl ColumnsService for ColumnsController {
         request: Request<ColumnIdAndName>,
     ) -> Result<Response<ProtoColumn>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+
+        if let Err(message) = self.text_limits.check_title("columnName", data.column_name.as_deref()) {
+            let column = eventbus::Column {
+                id: Some(data.column_id.clone()),
+                board_id: None,
+                name: data.column_name.clone(),
+                archived: data.archived,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(ColumnEvent {
+                column: Some(self.event_payload.apply(column)),
+                error: Some(error),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, update_column_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ColumnEvent {
+                    column: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_column_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         let change_set = ColumnChangeSet {
-            name: Some(data.column_name.clone()),
+            name: data.column_name.clone(),
+            archived: data.archived,
         };
         
-        match Column::update(&data.column_id, change_set, db_connection).await {
+        match Column::update(&data.column_id, change_set, data.enforce_unique_names, db_connection).await {
             Ok(col) => {
+                self.read_cache.invalidate(&col.id);
                 let column = eventbus::Column {
                     id: Some(col.id.clone()),
                     board_id: Some(col.board_id.clone()),
                     name: Some(col.name.clone()),
+                    archived: Some(col.archived),
                 };
                 let req = Request::new(ColumnEvent {
-                    column: Some(column),
-                    error: None
+                    column: Some(self.event_payload.apply(column)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.update_column_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_column_event, req).await;
                 });
 
                 Ok(Response::new(ProtoColumn {
                     id: col.id.clone(),
                     board_id: col.board_id.clone(),
                     name: col.name.clone(),
+                    position: col.position,
+                    archived: col.archived,
                 }))
             },
             Err(err) => {
-                if err == NotFound {
-                    let column = eventbus::Column {
-                        id: Some(data.column_id.clone()),
-                        board_id: None,
-                        name: Some(data.column_name.clone()),
-                    };
-                    let error = eventbus::Error {
-                        code: Code::NotFound.into(),
-                        message: err.to_string()
-                    };
-                    let req = Request::new(ColumnEvent {
-                        column: Some(column),
-                        error: Some(error)
-                    });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.update_column_event(req).await;
-                    });
-                    Err(Status::not_found("Column not found"))
-                } else {
-                    let column = eventbus::Column {
-                        id: Some(data.column_id.clone()),
-                        board_id: None,
-                        name: Some(data.column_name.clone()),
-                    };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
-                    };
-                    let req = Request::new(ColumnEvent {
-                        column: Some(column),
-                        error: Some(error)
-                    });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.update_column_event(req).await;
-                    });
-                    Err(Status::unavailable("Database is unavailable"))
-                }
+                let (code, message, status, brd_id) = match err {
+                    UpdateColumnError::Archived(brd_id) => {
+                        let message = "Column is archived";
+                        (Code::FailedPrecondition, message.to_string(), Status::failed_precondition(message), Some(brd_id))
+                    }
+                    UpdateColumnError::NameTaken(brd_id) => {
+                        let message = "Column name is already used on this board";
+                        (Code::AlreadyExists, message.to_string(), Status::already_exists(message), Some(brd_id))
+                    }
+                    UpdateColumnError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Column not found"),
+                        None,
+                    ),
+                    UpdateColumnError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status, None)
+                    }
+                };
+                let column = eventbus::Column {
+                    id: Some(data.column_id.clone()),
+                    board_id: brd_id,
+                    name: data.column_name.clone(),
+                    archived: data.archived,
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(ColumnEvent {
+                    column: Some(self.event_payload.apply(column)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_column_event, req).await;
+                });
+                Err(status)
             },
         }
     }
@@ -358,27 +698,47 @@ impl ColumnsService for ColumnsController {
         request: Request<ColumnId>,
     ) -> Result<Response<ProtoColumn>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ColumnEvent {
+                    column: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_column_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         match Column::delete(&data.column_id, db_connection).await {
             Ok(clmn) => {
+                self.read_cache.invalidate(&clmn.id);
                 let column = eventbus::Column {
                     id: Some(clmn.id.clone()),
                     board_id: Some(clmn.board_id.clone()),
                     name: Some(clmn.name.clone()),
+                    archived: Some(clmn.archived),
                 };
                 let req = Request::new(ColumnEvent {
-                    column: Some(column),
-                    error: None
+                    column: Some(self.event_payload.apply(column)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.delete_column_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_column_event, req).await;
                 });
                 Ok(Response::new(ProtoColumn {
                     id: clmn.id.clone(),
                     board_id: clmn.board_id.clone(),
                     name: clmn.name.clone(),
+                    position: clmn.position,
+                    archived: clmn.archived,
                 }))
             }
             Err(err) => {
@@ -387,18 +747,20 @@ impl ColumnsService for ColumnsController {
                         id: Some(data.column_id.clone()),
                         board_id: None,
                         name: None,
+                        archived: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: err.to_string()
                     };
                     let req = Request::new(ColumnEvent {
-                        column: Some(column),
-                        error: Some(error)
+                        column: Some(self.event_payload.apply(column)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_column_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_column_event, req).await;
                     });
                     Err(Status::not_found("Column not found"))
                 } else {
@@ -406,22 +768,276 @@ impl ColumnsService for ColumnsController {
                         id: Some(data.column_id.clone()),
                         board_id: None,
                         name: None,
+                        archived: None,
                     };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
-                    };
+                    let (error, status) = db_failure(&err);
                     let req = Request::new(ColumnEvent {
-                        column: Some(column),
-                        error: Some(error)
+                        column: Some(self.event_payload.apply(column)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_column_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_column_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
+                    Err(status)
                 }
             }
         }
     }
+
+    async fn swap_columns(
+        &self,
+        request: Request<ColumnIdPair>,
+    ) -> Result<Response<Columns>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SwapColumnsEvent {
+                    columns: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, swap_columns_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Column::swap(&data.first_column_id, &data.second_column_id, db_connection).await {
+            Ok((first, second)) => {
+                self.read_cache.invalidate(&first.id);
+                self.read_cache.invalidate(&second.id);
+                let clmns = [&first, &second]
+                    .iter()
+                    .map(|column| eventbus::Column {
+                        id: Some(column.id.clone()),
+                        board_id: Some(column.board_id.clone()),
+                        name: Some(column.name.clone()),
+                        archived: Some(column.archived),
+                    })
+                    .collect::<Vec<eventbus::Column>>();
+                let req = Request::new(SwapColumnsEvent {
+                    columns: self.event_payload.apply_all(clmns),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, swap_columns_event, req).await;
+                });
+
+                let proto_columns: Vec<ProtoColumn> = [first, second]
+                    .into_iter()
+                    .map(|column| ProtoColumn {
+                        id: column.id,
+                        board_id: column.board_id,
+                        name: column.name,
+                        position: column.position,
+                        archived: column.archived,
+                    })
+                    .collect();
+
+                Ok(Response::new(Columns { columns: proto_columns }))
+            }
+            Err(err) => {
+                let (code, message, status) = match err {
+                    SwapColumnsError::DifferentBoards => (
+                        Code::FailedPrecondition,
+                        String::from("Columns belong to different boards"),
+                        Status::failed_precondition("Columns belong to different boards"),
+                    ),
+                    SwapColumnsError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Column not found"),
+                    ),
+                    SwapColumnsError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let clmns = [&data.first_column_id, &data.second_column_id]
+                    .iter()
+                    .map(|column_id| eventbus::Column {
+                        id: Some(column_id.to_string()),
+                        board_id: None,
+                        name: None,
+                        archived: None,
+                    })
+                    .collect::<Vec<eventbus::Column>>();
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(SwapColumnsEvent {
+                    columns: self.event_payload.apply_all(clmns),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, swap_columns_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn normalize_positions(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<NormalizedPositions>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(NormalizePositionsEvent {
+                    board_id: data.board_id.clone(),
+                    columns_adjusted: None,
+                    issues_adjusted: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, normalize_positions_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Column::normalize_positions(&data.board_id, db_connection).await {
+            Ok(NormalizedCounts { columns_adjusted, issues_adjusted }) => {
+                // Positions may have moved on any of the board's columns.
+                self.read_cache.invalidate_all();
+                let req = Request::new(NormalizePositionsEvent {
+                    board_id: data.board_id.clone(),
+                    columns_adjusted: Some(columns_adjusted),
+                    issues_adjusted: Some(issues_adjusted),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, normalize_positions_event, req).await;
+                });
+
+                Ok(Response::new(NormalizedPositions { columns_adjusted, issues_adjusted }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(NormalizePositionsEvent {
+                    board_id: data.board_id.clone(),
+                    columns_adjusted: None,
+                    issues_adjusted: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, normalize_positions_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn reorder_columns(
+        &self,
+        request: Request<ReorderColumnsRequest>,
+    ) -> Result<Response<Columns>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ReorderColumnsEvent {
+                    board_id: data.board_id.clone(),
+                    columns: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, reorder_columns_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Column::reorder(&data.board_id, &data.columns_ids, db_connection).await {
+            Ok(board_columns) => {
+                self.read_cache.invalidate_all();
+                let clmns = board_columns
+                    .iter()
+                    .map(|column| eventbus::Column {
+                        id: Some(column.id.clone()),
+                        board_id: Some(column.board_id.clone()),
+                        name: Some(column.name.clone()),
+                        archived: Some(column.archived),
+                    })
+                    .collect::<Vec<eventbus::Column>>();
+                let req = Request::new(ReorderColumnsEvent {
+                    board_id: data.board_id.clone(),
+                    columns: self.event_payload.apply_all(clmns),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, reorder_columns_event, req).await;
+                });
+
+                let proto_columns: Vec<ProtoColumn> = board_columns
+                    .into_iter()
+                    .map(|column| ProtoColumn {
+                        id: column.id,
+                        board_id: column.board_id,
+                        name: column.name,
+                        position: column.position,
+                        archived: column.archived,
+                    })
+                    .collect();
+
+                Ok(Response::new(Columns { columns: proto_columns }))
+            }
+            Err(err) => {
+                let (code, message, status) = match err {
+                    ReorderColumnsError::DuplicateColumn(column_id) => {
+                        let message = format!("Column {} is listed more than once", column_id);
+                        (Code::InvalidArgument, message.clone(), Status::invalid_argument(message))
+                    }
+                    ReorderColumnsError::UnknownColumns(column_ids) => {
+                        let message = format!("Not columns of the board: {}", column_ids.join(", "));
+                        (Code::FailedPrecondition, message.clone(), Status::failed_precondition(message))
+                    }
+                    ReorderColumnsError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(ReorderColumnsEvent {
+                    board_id: data.board_id.clone(),
+                    columns: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, reorder_columns_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
 }
@@ -4,7 +4,8 @@ use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use diesel::{
     RunQueryDsl,
     QueryDsl,
-    ExpressionMethods, QueryResult, result::Error::NotFound,
+    ExpressionMethods, BoolExpressionMethods, QueryResult, result::Error::NotFound,
+    pg::Pg,
 };
 use tonic::{Request, Response, Status, Code, transport::Channel};
 use futures::Stream;
@@ -13,23 +14,54 @@ use proto::{
         dependencies_service_server::DependenciesService, 
         Dependency as ProtoDependency, 
         DependencyId,
+        DependencyLookup,
+        BlockingEpicIdAndBlockedEpicId,
         CreateDependencyRequest,
+        UpdateDependencyRequest,
         SearchDependenciesParams,
+        ChunkedSearchDependenciesParams,
+        DependenciesChunk,
+        DependenciesCount,
+        BoardId,
+        ScheduleConflict,
+        ScheduleConflicts,
+        BoardDependencyCycles,
+        EpicCycle,
+        EpicSchedule,
+        DependencyType,
+        DependenciesOrder,
     }, 
-    eventbus::{dependencies_events_service_client::DependenciesEventsServiceClient, DependencyEvent, self, SearchDependenciesEvent}
+    eventbus::{dependencies_events_service_client::DependenciesEventsServiceClient, DependencyEvent, self, SearchDependenciesEvent, DependenciesCountEvent, ValidateScheduleEvent, DependencyAuditEvent, EpicScheduleEvent}
 };
 
 use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    ids::new_id,
+    controllers::epics::{to_proto_epic, to_timestamp, StoredDateOutOfRange},
+    events::{EventPayload, db_failure, pooled_connection, rejection, send_event},
+    pagination::{Page, requested, chunk_size, chunked},
+    streams::StreamLimit,
+    default_sort::DependencySort,
     db::{
-        repos::dependency::{NewDependency, Dependency, CreateDependency, DeleteDependency},
-        schema::dependencies::dsl::*, 
+        repos::dependency::{
+            NewDependency, Dependency, CreateDependency, CreateDependencyError, DeleteDependency, AuditDependencies,
+            DependencyChangeSet, UpdateDependency, UpdateDependencyError,
+            ScheduleEpics, EpicScheduleError, ValidateSchedule,
+        },
+        schema::dependencies::{self as dependencies_schema, dsl::*}, 
         connection::PgPool,
     },
 };
 
 pub struct DependenciesController {
     pub pool: PgPool,
-    pub eventbus_service_client: DependenciesEventsServiceClient<Channel>
+    /// Serves the get and search handlers; see `establish_read_connection`.
+    pub read_pool: PgPool,
+    pub eventbus_service_client: Option<DependenciesEventsServiceClient<Channel>>,
+    pub event_payload: EventPayload,
+    pub stream_limit: StreamLimit,
+    pub default_sort: DependencySort,
 }
 
 #[tonic::async_trait]
@@ -39,7 +71,22 @@ impl DependenciesService for DependenciesController {
         request: Request<DependencyId>,
     ) -> Result<Response<ProtoDependency>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyEvent {
+                    dependency: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_dependency_by_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         let result: QueryResult<Vec<Dependency>> = dependencies
             .filter(id.eq(&request.get_ref().dependency_id))
@@ -52,25 +99,29 @@ impl DependenciesService for DependenciesController {
                     let dependency = eventbus::Dependency {
                         id: Some(dep.id.clone()),
                         blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                        dependency_type: Some(dep.dependency_type.unwrap_or_default()),
                         blocking_epic_id: Some(dep.blocking_epic_id.clone()),
                     };
                     let req = Request::new(DependencyEvent {
-                        dependency: Some(dependency),
-                        error: None
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: None,
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_dependency_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_dependency_by_id_event, req).await;
                     });
                     Ok(Response::new(ProtoDependency {
                         id: dep.id.clone(),
                         blocking_epic_id: dep.blocking_epic_id.clone(),
                         blocked_epic_id: dep.blocked_epic_id.clone(),
+                        dependency_type: dep.dependency_type.unwrap_or_default(),
                     }))
                 } else {
                     let dependency = eventbus::Dependency {
                         id: Some(data.dependency_id.clone()),
                         blocked_epic_id: None,
+                        dependency_type: None,
                         blocking_epic_id: None,
                     };
                     let error = eventbus::Error {
@@ -78,12 +129,13 @@ impl DependenciesService for DependenciesController {
                         message: String::from("Dependency not found")
                     };
                     let req = Request::new(DependencyEvent {
-                        dependency: Some(dependency),
-                        error: Some(error)
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_dependency_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_dependency_by_id_event, req).await;
                     });
                     Err(Status::not_found("Dependency not found"))
                 }
@@ -92,21 +144,134 @@ impl DependenciesService for DependenciesController {
                 let dependency = eventbus::Dependency {
                     id: Some(data.dependency_id.clone()),
                     blocked_epic_id: None,
+                    dependency_type: None,
                     blocking_epic_id: None,
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                let (error, status) = db_failure(&err);
+                let req = Request::new(DependencyEvent {
+                    dependency: Some(self.event_payload.apply(dependency)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_dependency_by_id_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn find_dependency_by_id(
+        &self,
+        request: Request<DependencyId>,
+    ) -> Result<Response<DependencyLookup>, Status> {
+        match self.get_dependency_by_id(request).await {
+            Ok(response) => Ok(Response::new(DependencyLookup {
+                found: true,
+                dependency: Some(response.into_inner()),
+            })),
+            Err(status) if status.code() == Code::NotFound => Ok(Response::new(DependencyLookup {
+                found: false,
+                dependency: Some(ProtoDependency::default()),
+            })),
+            Err(status) => Err(status),
+        }
+    }
+
+    async fn get_dependency_by_epics(
+        &self,
+        request: Request<BlockingEpicIdAndBlockedEpicId>,
+    ) -> Result<Response<ProtoDependency>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyEvent {
+                    dependency: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_dependency_by_epics_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<Vec<Dependency>> = dependencies
+            .filter(blocking_epic_id.eq(&data.blocking_epic_id))
+            .filter(blocked_epic_id.eq(&data.blocked_epic_id))
+            .limit(1)
+            .load::<Dependency>(&*db_connection);
+
+        match result {
+            Ok(vec) => {
+                if let Some(dep) = vec.first() {
+                    let dependency = eventbus::Dependency {
+                        id: Some(dep.id.clone()),
+                        blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                        dependency_type: Some(dep.dependency_type.unwrap_or_default()),
+                        blocking_epic_id: Some(dep.blocking_epic_id.clone()),
+                    };
+                    let req = Request::new(DependencyEvent {
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: None,
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_dependency_by_epics_event, req).await;
+                    });
+                    Ok(Response::new(ProtoDependency {
+                        id: dep.id.clone(),
+                        blocking_epic_id: dep.blocking_epic_id.clone(),
+                        blocked_epic_id: dep.blocked_epic_id.clone(),
+                        dependency_type: dep.dependency_type.unwrap_or_default(),
+                    }))
+                } else {
+                    let dependency = eventbus::Dependency {
+                        id: None,
+                        blocked_epic_id: Some(data.blocked_epic_id.clone()),
+                        dependency_type: None,
+                        blocking_epic_id: Some(data.blocking_epic_id.clone()),
+                    };
+                    let error = eventbus::Error {
+                        code: Code::NotFound.into(),
+                        message: String::from("Dependency not found")
+                    };
+                    let req = Request::new(DependencyEvent {
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_dependency_by_epics_event, req).await;
+                    });
+                    Err(Status::not_found("Dependency not found"))
+                }
+            }
+            Err(err) => {
+                let dependency = eventbus::Dependency {
+                    id: None,
+                    blocked_epic_id: Some(data.blocked_epic_id.clone()),
+                    dependency_type: None,
+                    blocking_epic_id: Some(data.blocking_epic_id.clone()),
                 };
+                let (error, status) = db_failure(&err);
                 let req = Request::new(DependencyEvent {
-                    dependency: Some(dependency),
-                    error: Some(error)
+                    dependency: Some(self.event_payload.apply(dependency)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.get_dependency_by_id_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_dependency_by_epics_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             }
         }
     }
@@ -118,28 +283,64 @@ impl DependenciesService for DependenciesController {
         request: Request<SearchDependenciesParams>,
     ) -> Result<Response<Self::searchDependenciesStream>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
-        
-        let mut query = dependencies.into_boxed();
 
-        let dependencies_ids = match data.dependencies_ids.is_empty() {
-            false => Some(&data.dependencies_ids),
-            true => None,
+        let (limit, offset) = requested(data.page.as_ref(), data.limit, data.offset);
+        let page = match Page::from_params(limit, offset) {
+            Ok(page) => page,
+            Err(message) => {
+                let search_params = eventbus::SearchDependenciesParams {
+                    dependencies_ids: data.dependencies_ids.clone(),
+                    blocked_epic_id: data.blocked_epic_id.clone(),
+                    blocking_epic_id: data.blocking_epic_id.clone(),
+                    limit,
+                    offset,
+                    dependency_type: data.dependency_type,
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(SearchDependenciesEvent {
+                    dependencies: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_dependencies_event, req).await;
+                });
+                return Err(status);
+            }
         };
+        let limit = page.effective_limit();
 
-        if let Some(dep_ids) = dependencies_ids {
-            query = query.filter(id.eq_any(dep_ids));
-        }
+        let permit = self.stream_limit.acquire()?;
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SearchDependenciesEvent {
+                    dependencies: Vec::new(),
+                    error: Some(error),
+                    search_params: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_dependencies_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        
+        let mut query = filtered_dependencies(data);
 
-        if let Some(blocking_ep_id) = &data.blocking_epic_id {
-            query = query.filter(blocking_epic_id.eq(blocking_ep_id));
-        }
+        query = query.limit(page.limit);
 
-        if let Some(blocked_ep_id) = &data.blocked_epic_id {
-            query = query.filter(blocked_epic_id.eq(blocked_ep_id));
+        if let Some(page_offset) = page.offset {
+            query = query.offset(page_offset);
         }
 
-        let result: QueryResult<Vec<Dependency>> = query
+        let sort = self.default_sort.or_requested(data.order_by, data.descending);
+        let result: QueryResult<Vec<Dependency>> = order_dependencies(query, sort)
             .load::<Dependency>(&*db_connection);
 
         match result {
@@ -149,6 +350,7 @@ impl DependenciesService for DependenciesController {
                     .map(|dependency| eventbus::Dependency {
                         id: Some(dependency.id.clone()),
                         blocked_epic_id: Some(dependency.blocked_epic_id.clone()),
+                        dependency_type: Some(dependency.dependency_type.unwrap_or_default()),
                         blocking_epic_id: Some(dependency.blocking_epic_id.clone()),
                     })
                     .collect::<Vec<eventbus::Dependency>>();
@@ -156,22 +358,25 @@ impl DependenciesService for DependenciesController {
                     dependencies_ids: data.dependencies_ids.clone(),
                     blocked_epic_id: data.blocked_epic_id.clone(),
                     blocking_epic_id: data.blocking_epic_id.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
+                    limit,
+                    offset,
+                    dependency_type: data.dependency_type,
                 };
 
                 let req = Request::new(SearchDependenciesEvent {
-                    dependencies: deps,
+                    dependencies: self.event_payload.apply_all(deps),
                     error: None,
-                    search_params: Some(search_params)
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
+                let service = self.eventbus_service_client.clone();
 
                 let proto_dependencies: Vec<ProtoDependency> = vec
                     .iter()
                     .map(|dependency| ProtoDependency {
                         id: dependency.id.clone(),
                         blocked_epic_id: dependency.blocked_epic_id.clone(),
+                        dependency_type: dependency.dependency_type.unwrap_or_default(),
                         blocking_epic_id: dependency.blocking_epic_id.clone(),
                     })
                     .collect();
@@ -179,14 +384,18 @@ impl DependenciesService for DependenciesController {
                 let mut stream = tokio_stream::iter(proto_dependencies);
                 let (sender, receiver) = mpsc::channel(1);
         
-                tokio::spawn(async move {
+                spawn_tracked(async move {
+                    let _permit = permit;
+                    let mut delivered = true;
                     while let Some(dependency) = stream.next().await {
-                        match sender.send(Result::<ProtoDependency, Status>::Ok(dependency)).await {
-                            Ok(_) => {},
-                            Err(_err) => break
+                        if sender.send(Result::<ProtoDependency, Status>::Ok(dependency)).await.is_err() {
+                            delivered = false;
+                            break;
                         }
                     }
-                    service.search_dependencies_event(req).await;
+                    if delivered {
+                        send_event!(service, search_dependencies_event, req).await;
+                    }
                 });
         
                 let output_stream = ReceiverStream::new(receiver);
@@ -201,31 +410,113 @@ impl DependenciesService for DependenciesController {
                     .map(|dependency_id| eventbus::Dependency {
                         id: Some(dependency_id.clone()),
                         blocked_epic_id: None,
+                        dependency_type: None,
                         blocking_epic_id: None,
                     })
                     .collect::<Vec<eventbus::Dependency>>();
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
+                let (error, status) = db_failure(&err);
                 let search_params = eventbus::SearchDependenciesParams {
                     dependencies_ids: data.dependencies_ids.clone(),
                     blocked_epic_id: data.blocked_epic_id.clone(),
                     blocking_epic_id: data.blocking_epic_id.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
+                    limit,
+                    offset,
+                    dependency_type: data.dependency_type,
                 };
 
                 let req = Request::new(SearchDependenciesEvent {
-                    dependencies: deps,
+                    dependencies: self.event_payload.apply_all(deps),
                     error: Some(error),
-                    search_params: Some(search_params)
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.search_dependencies_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_dependencies_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
+            }
+        }
+    }
+
+    type searchDependenciesInChunksStream = Pin<Box<dyn Stream<Item = Result<DependenciesChunk, Status>> + Send>>;
+
+    async fn search_dependencies_in_chunks(
+        &self,
+        request: Request<ChunkedSearchDependenciesParams>,
+    ) -> Result<Response<Self::searchDependenciesInChunksStream>, Status> {
+        let data = request.into_inner();
+        let size = chunk_size(data.chunk_size).map_err(Status::invalid_argument)?;
+        let rows = self.search_dependencies(Request::new(data.params.unwrap_or_default())).await?.into_inner();
+
+        Ok(Response::new(
+            Box::pin(chunked(rows, size, |rows| DependenciesChunk { dependencies: rows })) as Self::searchDependenciesInChunksStream
+        ))
+    }
+
+    async fn count_dependencies(
+        &self,
+        request: Request<SearchDependenciesParams>,
+    ) -> Result<Response<DependenciesCount>, Status> {
+        let data = request.get_ref();
+        let search_params = eventbus::SearchDependenciesParams {
+            dependencies_ids: data.dependencies_ids.clone(),
+            blocked_epic_id: data.blocked_epic_id.clone(),
+            blocking_epic_id: data.blocking_epic_id.clone(),
+            limit: None,
+            offset: None,
+            dependency_type: data.dependency_type,
+        };
+
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependenciesCountEvent {
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    count: None,
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_dependencies_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<i64> = filtered_dependencies(data)
+            .count()
+            .get_result(&*db_connection);
+
+        match result {
+            Ok(total) => {
+                let req = Request::new(DependenciesCountEvent {
+                    error: None,
+                    search_params: Some(search_params),
+                    count: Some(total),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_dependencies_event, req).await;
+                });
+                Ok(Response::new(DependenciesCount { count: total }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(DependenciesCountEvent {
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    count: None,
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_dependencies_event, req).await;
+                });
+                Err(status)
             }
         }
     }
@@ -235,12 +526,56 @@ impl DependenciesService for DependenciesController {
         request: Request<CreateDependencyRequest>,
     ) -> Result<Response<ProtoDependency>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+
+        let invalid = if data.blocking_epic_id == data.blocked_epic_id {
+            Some("An epic cannot depend on itself")
+        } else if data.dependency_type.is_some_and(|dep_type| DependencyType::from_i32(dep_type).is_none()) {
+            Some("Unknown dependency type")
+        } else {
+            None
+        };
+        if let Some(message) = invalid {
+            let dependency = eventbus::Dependency {
+                id: None,
+                blocking_epic_id: Some(data.blocking_epic_id.clone()),
+                blocked_epic_id: Some(data.blocked_epic_id.clone()),
+                dependency_type: data.dependency_type,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, message);
+            let req = Request::new(DependencyEvent {
+                dependency: Some(self.event_payload.apply(dependency)),
+                error: Some(error),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_dependency_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyEvent {
+                    dependency: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_dependency_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         let new_dependency = NewDependency {
-            id: &uuid::Uuid::new_v4().to_string(),
+            id: &new_id(),
             blocking_epic_id: &data.blocking_epic_id,
             blocked_epic_id: &data.blocked_epic_id,
+            dependency_type: Some(data.dependency_type.unwrap_or(DependencyType::Blocks as i32)),
         };
 
         match Dependency::create(new_dependency, db_connection).await {
@@ -249,71 +584,230 @@ impl DependenciesService for DependenciesController {
                     id: Some(dep.id.clone()),
                     blocking_epic_id: Some(dep.blocking_epic_id.clone()),
                     blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                    dependency_type: Some(dep.dependency_type.unwrap_or_default()),
                 };
                 let req = Request::new(DependencyEvent {
-                    dependency: Some(dependency),
-                    error: None
+                    dependency: Some(self.event_payload.apply(dependency)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_dependency_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_dependency_event, req).await;
                 });
 
                 Ok(Response::new(ProtoDependency {
                     id: dep.id.clone(),
                     blocking_epic_id: dep.blocking_epic_id.clone(),
                     blocked_epic_id: dep.blocked_epic_id.clone(),
+                    dependency_type: dep.dependency_type.unwrap_or_default(),
                 }))
             },
             Err(err) => {
+                let (code, message, status) = match err {
+                    CreateDependencyError::Cycle(cycle) => {
+                        let message = format!(
+                            "Dependency would create a cycle: {} -> {}",
+                            cycle.join(" -> "),
+                            cycle.first().map(String::as_str).unwrap_or_default(),
+                        );
+                        (Code::FailedPrecondition, message.clone(), Status::failed_precondition(message))
+                    }
+                    CreateDependencyError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
                 let dependency = eventbus::Dependency {
                     id: None,
                     blocking_epic_id: Some(data.blocking_epic_id.clone()),
                     blocked_epic_id: Some(data.blocked_epic_id.clone()),
+                    dependency_type: data.dependency_type,
                 };
                 let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                    code: code.into(),
+                    message
                 };
                 let req = Request::new(DependencyEvent {
-                    dependency: Some(dependency),
-                    error: Some(error)
+                    dependency: Some(self.event_payload.apply(dependency)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_dependency_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_dependency_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             },
         }
     }
 
+    async fn update_dependency(
+        &self,
+        request: Request<UpdateDependencyRequest>,
+    ) -> Result<Response<ProtoDependency>, Status> {
+        let data = request.get_ref();
+        let requested_dependency = eventbus::Dependency {
+            id: Some(data.dependency_id.clone()),
+            blocking_epic_id: data.blocking_epic_id.clone(),
+            blocked_epic_id: data.blocked_epic_id.clone(),
+            dependency_type: None,
+        };
+
+        let invalid = if data.blocking_epic_id.is_none() && data.blocked_epic_id.is_none() {
+            Some("Either blocking_epic_id or blocked_epic_id is required")
+        } else if data.blocking_epic_id.is_some() && data.blocking_epic_id == data.blocked_epic_id {
+            Some("An epic cannot depend on itself")
+        } else {
+            None
+        };
+        if let Some(message) = invalid {
+            let (error, status) = rejection(Code::InvalidArgument, message);
+            let req = Request::new(DependencyEvent {
+                dependency: Some(self.event_payload.apply(requested_dependency)),
+                error: Some(error),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, update_dependency_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyEvent {
+                    dependency: Some(self.event_payload.apply(requested_dependency)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_dependency_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let change_set = DependencyChangeSet {
+            blocking_epic_id: data.blocking_epic_id.clone(),
+            blocked_epic_id: data.blocked_epic_id.clone(),
+        };
+
+        match Dependency::update(&data.dependency_id, change_set, db_connection).await {
+            Ok(dep) => {
+                let dependency = eventbus::Dependency {
+                    id: Some(dep.id.clone()),
+                    blocking_epic_id: Some(dep.blocking_epic_id.clone()),
+                    blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                    dependency_type: Some(dep.dependency_type.unwrap_or_default()),
+                };
+                let req = Request::new(DependencyEvent {
+                    dependency: Some(self.event_payload.apply(dependency)),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_dependency_event, req).await;
+                });
+
+                Ok(Response::new(ProtoDependency {
+                    id: dep.id.clone(),
+                    blocking_epic_id: dep.blocking_epic_id.clone(),
+                    blocked_epic_id: dep.blocked_epic_id.clone(),
+                    dependency_type: dep.dependency_type.unwrap_or_default(),
+                }))
+            },
+            Err(err) => {
+                let (code, message, status) = match err {
+                    UpdateDependencyError::SelfReference => {
+                        let message = "An epic cannot depend on itself";
+                        (Code::InvalidArgument, message.to_string(), Status::invalid_argument(message))
+                    }
+                    UpdateDependencyError::Cycle(cycle) => {
+                        let message = format!(
+                            "Dependency would create a cycle: {} -> {}",
+                            cycle.join(" -> "),
+                            cycle.first().map(String::as_str).unwrap_or_default(),
+                        );
+                        (Code::FailedPrecondition, message.clone(), Status::failed_precondition(message))
+                    }
+                    UpdateDependencyError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Dependency not found"),
+                    ),
+                    UpdateDependencyError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(DependencyEvent {
+                    dependency: Some(self.event_payload.apply(requested_dependency)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_dependency_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
     async fn delete_dependency(
         &self,
         request: Request<DependencyId>,
     ) -> Result<Response<ProtoDependency>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyEvent {
+                    dependency: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_dependency_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         match Dependency::delete(&data.dependency_id, db_connection).await {
             Ok(dep) => {
                 let dependency = eventbus::Dependency {
                     id: Some(dep.id.clone()),
                     blocked_epic_id: Some(dep.blocked_epic_id.clone()),
+                    dependency_type: Some(dep.dependency_type.unwrap_or_default()),
                     blocking_epic_id: Some(dep.blocking_epic_id.clone()),
                 };
                 let req = Request::new(DependencyEvent {
-                    dependency: Some(dependency),
-                    error: None
+                    dependency: Some(self.event_payload.apply(dependency)),
+                    error: None,
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.delete_dependency_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_dependency_event, req).await;
                 });
                 Ok(Response::new(ProtoDependency {
                     id: dep.id.clone(),
                     blocking_epic_id: dep.blocking_epic_id.clone(),
                     blocked_epic_id: dep.blocked_epic_id.clone(),
+                    dependency_type: dep.dependency_type.unwrap_or_default(),
                 }))
             }
             Err(err) => {
@@ -321,6 +815,7 @@ impl DependenciesService for DependenciesController {
                     let dependency = eventbus::Dependency {
                         id: Some(data.dependency_id.clone()),
                         blocked_epic_id: None,
+                        dependency_type: None,
                         blocking_epic_id: None,
                     };
                     let error = eventbus::Error {
@@ -328,35 +823,339 @@ impl DependenciesService for DependenciesController {
                         message: err.to_string()
                     };
                     let req = Request::new(DependencyEvent {
-                        dependency: Some(dependency),
-                        error: Some(error)
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_dependency_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_dependency_event, req).await;
                     });
                     Err(Status::not_found("Dependency not found"))
                 } else {
                     let dependency = eventbus::Dependency {
                         id: Some(data.dependency_id.clone()),
                         blocked_epic_id: None,
+                        dependency_type: None,
                         blocking_epic_id: None,
                     };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
-                    };
+                    let (error, status) = db_failure(&err);
                     let req = Request::new(DependencyEvent {
-                        dependency: Some(dependency),
-                        error: Some(error)
+                        dependency: Some(self.event_payload.apply(dependency)),
+                        error: Some(error),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_dependency_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_dependency_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
+                    Err(status)
                 }
             }
         }
     }
+
+    async fn validate_schedule(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<ScheduleConflicts>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ValidateScheduleEvent {
+                    board_id: Some(data.board_id.clone()),
+                    conflicts: vec![],
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, validate_schedule_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Dependency::date_conflicts(&data.board_id, db_connection).await {
+            Ok(date_conflicts) => {
+                let converted = date_conflicts
+                    .iter()
+                    .map(|conflict| Ok(ScheduleConflict {
+                        dependency_id: conflict.dependency.id.clone(),
+                        blocking_epic_id: conflict.dependency.blocking_epic_id.clone(),
+                        blocked_epic_id: conflict.dependency.blocked_epic_id.clone(),
+                        blocking_epic_due_date: Some(to_timestamp(conflict.blocking_due)?),
+                        blocked_epic_start_date: Some(to_timestamp(conflict.blocked_start)?),
+                    }))
+                    .collect::<Result<Vec<ScheduleConflict>, StoredDateOutOfRange>>();
+                let conflicts = match converted {
+                    Ok(conflicts) => conflicts,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(ValidateScheduleEvent {
+                            board_id: Some(data.board_id.clone()),
+                            conflicts: vec![],
+                            error: Some(error),
+                            correlation_id: correlation_id()
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, validate_schedule_event, req).await;
+                        });
+                        return Err(status);
+                    }
+                };
+
+                let deps = conflicts
+                    .iter()
+                    .map(|conflict| eventbus::Dependency {
+                        id: Some(conflict.dependency_id.clone()),
+                        blocking_epic_id: Some(conflict.blocking_epic_id.clone()),
+                        blocked_epic_id: Some(conflict.blocked_epic_id.clone()),
+                        dependency_type: Some(DependencyType::Blocks as i32),
+                    })
+                    .collect::<Vec<eventbus::Dependency>>();
+                let req = Request::new(ValidateScheduleEvent {
+                    board_id: Some(data.board_id.clone()),
+                    conflicts: self.event_payload.apply_all(deps),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, validate_schedule_event, req).await;
+                });
+
+                Ok(Response::new(ScheduleConflicts { conflicts }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(ValidateScheduleEvent {
+                    board_id: Some(data.board_id.clone()),
+                    conflicts: vec![],
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, validate_schedule_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn validate_board_dependencies(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<BoardDependencyCycles>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: Some(data.board_id.clone()),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: None,
+                    removed_ids: Vec::new(),
+                    dry_run: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, validate_board_dependencies_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Dependency::audit(Some(&data.board_id), db_connection).await {
+            Ok(audit) => {
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: Some(data.board_id.clone()),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: Some(audit.cycles.len() as i32),
+                    removed_ids: Vec::new(),
+                    dry_run: None,
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, validate_board_dependencies_event, req).await;
+                });
+
+                Ok(Response::new(BoardDependencyCycles {
+                    cycles: audit.cycle_epic_ids()
+                        .into_iter()
+                        .map(|epics_ids| EpicCycle { epics_ids })
+                        .collect(),
+                }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(DependencyAuditEvent {
+                    board_id: Some(data.board_id.clone()),
+                    self_references: None,
+                    duplicates: None,
+                    cycles: None,
+                    removed_ids: Vec::new(),
+                    dry_run: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, validate_board_dependencies_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+    async fn get_epic_schedule(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<EpicSchedule>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicScheduleEvent {
+                    board_id: Some(data.board_id.clone()),
+                    epics_ids: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epic_schedule_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Dependency::schedule(&data.board_id, db_connection).await {
+            Ok(scheduled) => {
+                let proto_epics = match scheduled
+                    .iter()
+                    .map(to_proto_epic)
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(proto_epics) => proto_epics,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(EpicScheduleEvent {
+                            board_id: Some(data.board_id.clone()),
+                            epics_ids: Vec::new(),
+                            error: Some(error),
+                            correlation_id: correlation_id()
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, get_epic_schedule_event, req).await;
+                        });
+                        return Err(status);
+                    }
+                };
+                let req = Request::new(EpicScheduleEvent {
+                    board_id: Some(data.board_id.clone()),
+                    epics_ids: scheduled.into_iter().map(|epic| epic.id).collect(),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epic_schedule_event, req).await;
+                });
+
+                Ok(Response::new(EpicSchedule { epics: proto_epics }))
+            }
+            Err(err) => {
+                let (code, message, status, epics_ids) = match err {
+                    EpicScheduleError::Cycle(cycle) => {
+                        let message = format!(
+                            "Blocking dependencies form a cycle: {} -> {}",
+                            cycle.join(" -> "),
+                            cycle.first().map(String::as_str).unwrap_or_default(),
+                        );
+                        (Code::FailedPrecondition, message.clone(), Status::failed_precondition(message), cycle)
+                    }
+                    EpicScheduleError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status, Vec::new())
+                    }
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(EpicScheduleEvent {
+                    board_id: Some(data.board_id.clone()),
+                    epics_ids,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epic_schedule_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+}
+
+/// Shared by searchDependencies and countDependencies so the count always
+/// matches what a search with the same filters returns.
+fn filtered_dependencies(data: &SearchDependenciesParams) -> dependencies_schema::BoxedQuery<'_, Pg> {
+    let mut query = dependencies.into_boxed();
+
+    let dependencies_ids = match data.dependencies_ids.is_empty() {
+        false => Some(&data.dependencies_ids),
+        true => None,
+    };
+
+    if let Some(dep_ids) = dependencies_ids {
+        query = query.filter(id.eq_any(dep_ids));
+    }
+
+    if let Some(blocking_ep_id) = &data.blocking_epic_id {
+        query = query.filter(blocking_epic_id.eq(blocking_ep_id));
+    }
+
+    if let Some(blocked_ep_id) = &data.blocked_epic_id {
+        query = query.filter(blocked_epic_id.eq(blocked_ep_id));
+    }
+
+    if let Some(dep_type) = data.dependency_type {
+        query = match DependencyType::from_i32(dep_type) {
+            Some(DependencyType::Blocks) => query.filter(
+                dependency_type.eq(dep_type).or(dependency_type.is_null())
+            ),
+            _ => query.filter(dependency_type.eq(dep_type)),
+        };
+    }
+
+    query
+}
+
+fn order_dependencies(
+    query: dependencies_schema::BoxedQuery<'_, Pg>,
+    sort: DependencySort,
+) -> dependencies_schema::BoxedQuery<'_, Pg> {
+    match (sort.order, sort.descending) {
+        (DependenciesOrder::Id, false) => query.order(id.asc()),
+        (DependenciesOrder::Id, true) => query.order(id.desc()),
+        (DependenciesOrder::BlockingEpic, false) => query.order((blocking_epic_id.asc(), id.asc())),
+        (DependenciesOrder::BlockingEpic, true) => query.order((blocking_epic_id.desc(), id.desc())),
+        (DependenciesOrder::BlockedEpic, false) => query.order((blocked_epic_id.asc(), id.asc())),
+        (DependenciesOrder::BlockedEpic, true) => query.order((blocked_epic_id.desc(), id.desc())),
+    }
 }
@@ -1,12 +1,20 @@
-use std::{pin::Pin, time::SystemTime};
-use chrono::{NaiveDateTime, DateTime, Utc};
+use std::pin::Pin;
+use chrono::{DateTime, Duration, NaiveDateTime};
 use prost_types::Timestamp;
+use serde_json::Value;
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use diesel::{
     RunQueryDsl,
     QueryDsl,
-    ExpressionMethods, QueryResult, result::Error::NotFound,
+    ExpressionMethods, QueryResult,
+    JoinOnDsl,
+    TextExpressionMethods,
+    PgTextExpressionMethods,
+    expression::{bound::Bound, operators::Concat},
+    sql_types::{Nullable, Text},
+    pg::Pg,
+    result::Error::NotFound,
 };
 use tonic::{Request, Response, Status, Code, transport::Channel};
 use futures::Stream;
@@ -15,49 +23,154 @@ use proto::{
         epics_service_server::EpicsService, 
         Epic as ProtoEpic, 
         EpicId,
+        GetEpicRequest,
+        EpicExpand,
+        EpicLookup,
         SearchEpicsParams,
+        ChunkedSearchEpicsParams,
+        EpicsChunk,
+        ProjectEpicsParams,
+        EpicsOrder,
         CreateEpicRequest, 
-        UpdateEpicRequest
+        UpdateEpicRequest,
+        CloneEpicRequest,
+        ClonedEpic,
+        ShiftEpicDatesRequest,
+        ShiftedEpics,
+        EpicDeletability,
+        MergeEpicsRequest,
+        MergedEpics,
+        Issue as ProtoIssue,
     }, 
     eventbus::{
         self,
-        epics_events_service_client::EpicsEventsServiceClient, EpicEvent, SearchEpicsEvent,
+        epics_events_service_client::EpicsEventsServiceClient, EpicEvent, SearchEpicsEvent, ProjectEpicsEvent,
+        ShiftEpicDatesEvent, EpicDeletabilityEvent, MergeEpicsEvent,
+        issues_events_service_client::IssuesEventsServiceClient, IssueEvent,
     }
 };
 
 use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    ids::new_id,
+    events::{EventPayload, db_failure, pooled_connection, rejection, send_event},
+    pagination::{Page, requested, chunk_size, chunked},
+    streams::StreamLimit,
+    limits::TextLimits,
+    users::UserValidation,
     db::{
         repos::{
-            epic::{NewEpic, Epic, EpicChangeSet, CreateEpic, UpdateEpic, DeleteEpic},
-            column::Column
+            epic::{
+                NewEpic, Epic, EpicChangeSet, CreateEpic, UpdateEpic, UpdateEpicError, DeleteEpic, TouchEpic,
+                CloneEpic, EpicOverrides, ShiftEpicDates, ShiftEpicDatesError,
+                CountEpicReferences, MergeEpics, MergeEpicsError,
+            },
+            column::Column,
+            board::Board,
+        },
+        schema::{
+            epics::{self as epics_schema, dsl::*, BoxedQuery as BoxedEpicsQuery},
+            columns as columns_schema,
+            boards,
         },
-        schema::{epics::dsl::*, columns::dsl::columns}, 
         connection::PgPool,
+        full_text::TextQuery,
     },
+    controllers::issues::{escape_like, to_proto_issue},
 };
 
 pub struct EpicsController {
     pub pool: PgPool,
-    pub eventbus_service_client: EpicsEventsServiceClient<Channel>
+    /// Serves the get and search handlers; see `establish_read_connection`.
+    pub read_pool: PgPool,
+    pub eventbus_service_client: Option<EpicsEventsServiceClient<Channel>>,
+    /// Cloning an epic with its issues reports the created issues here.
+    pub issues_eventbus_service_client: Option<IssuesEventsServiceClient<Channel>>,
+    pub event_payload: EventPayload,
+    pub stream_limit: StreamLimit,
+    pub text_limits: TextLimits,
+    pub user_validation: UserValidation,
 }
 
 #[tonic::async_trait]
 impl EpicsService for EpicsController {
     async fn get_epic_by_id(
         &self,
-        request: Request<EpicId>,
+        request: Request<GetEpicRequest>,
     ) -> Result<Response<ProtoEpic>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
-        let result: QueryResult<Vec<Epic>> = epics
-            .filter(id.eq(&data.epic_id))
-            .limit(1)
-            .load::<Epic>(&*db_connection);
-
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicEvent {
+                    epic: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epic_by_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        let result: QueryResult<Vec<(Epic, Option<Column>, Option<Board>)>> =
+            if data.expand == EpicExpand::Placement as i32 {
+                epics
+                    .left_join(columns_schema::table.on(columns_schema::id.eq(column_id)))
+                    .left_join(boards::table.on(boards::id.eq(columns_schema::board_id)))
+                    .filter(id.eq(&data.epic_id))
+                    .limit(1)
+                    .load(&*db_connection)
+            } else {
+                epics
+                    .filter(id.eq(&data.epic_id))
+                    .limit(1)
+                    .load::<Epic>(&*db_connection)
+                    .map(|rows| rows.into_iter().map(|ep| (ep, None, None)).collect())
+            };
 
         match result {
             Ok(vec) => {
-                if let Some(ep) = vec.first() {
+                if let Some((ep, clmn, brd)) = vec.first() {
+                    let proto_epic = match to_proto_epic(ep) {
+                        Ok(proto_epic) => proto_epic,
+                        Err(out_of_range) => {
+                            let (error, status) = out_of_range.failure();
+                            let epic = eventbus::Epic {
+                                id: Some(data.epic_id.clone()),
+                                column_id: None,
+                                assignee_id: None,
+                                reporter_id: None,
+                                name: None,
+                                description: None,
+                                start_date: None,
+                                due_date: None,
+                                progress_percent: None,
+                                metadata: None,
+                            };
+                            let req = Request::new(EpicEvent {
+                                epic: Some(self.event_payload.apply(epic)),
+                                error: Some(error),
+                                changed_fields: Vec::new(),
+                                correlation_id: correlation_id()
+                            });
+                            let service = self.eventbus_service_client.clone();
+                            spawn_tracked(async move {
+                                send_event!(service, get_epic_by_id_event, req).await;
+                            });
+                            return Err(status);
+                        }
+                    };
+                    let proto_epic = ProtoEpic {
+                        column_name: clmn.as_ref().map(|clmn| clmn.name.clone()),
+                        board_id: brd.as_ref().map(|brd| brd.id.clone()),
+                        project_id: brd.as_ref().map(|brd| brd.project_id.clone()),
+                        ..proto_epic
+                    };
                     let epic = eventbus::Epic {
                         id: Some(ep.id.clone()),
                         column_id: Some(ep.column_id.clone()),
@@ -65,35 +178,22 @@ impl EpicsService for EpicsController {
                         reporter_id: Some(ep.reporter_id.clone()),
                         name: Some(ep.name.clone()),
                         description: ep.description.clone(),
-                        start_date: Some(ep.start_date.clone().to_string()),
-                        due_date: Some(ep.due_date.clone().to_string()),
+                        start_date: ep.start_date.map(|date| date.to_string()),
+                        due_date: ep.due_date.map(|date| date.to_string()),
+                        progress_percent: Some(ep.progress_percent),
+                        metadata: ep.metadata.as_ref().map(|value| value.to_string()),
                     };
                     let req = Request::new(EpicEvent {
-                        epic: Some(epic),
-                        error: None
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: None,
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_epic_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_epic_by_id_event, req).await;
                     });
-                    let start_timestamp = Option::from(Timestamp {
-                        seconds: ep.start_date.timestamp(),
-                        nanos: ep.start_date.timestamp_subsec_nanos().try_into().unwrap(),
-                    });
-                    let due_timestamp = Option::from(Timestamp {
-                        seconds: ep.due_date.timestamp(),
-                        nanos: ep.due_date.timestamp_subsec_nanos().try_into().unwrap(),
-                    });
-                    Ok(Response::new(ProtoEpic {
-                        id: ep.id.clone(),
-                        column_id: ep.column_id.clone(),
-                        assignee_id: ep.assignee_id.clone(),
-                        reporter_id: ep.reporter_id.clone(),
-                        name: ep.name.clone(),
-                        description: ep.description.clone(),
-                        start_date: start_timestamp,
-                        due_date: due_timestamp,
-                    }))
+                    Ok(Response::new(proto_epic))
                 } else {
                     let epic = eventbus::Epic {
                         id: Some(data.epic_id.clone()),
@@ -104,18 +204,22 @@ impl EpicsService for EpicsController {
                         description: None,
                         start_date: None,
                         due_date: None,
+                        progress_percent: None,
+                        metadata: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: String::from("Epic not found")
                     };
                     let req = Request::new(EpicEvent {
-                        epic: Some(epic),
-                        error: Some(error)
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_epic_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_epic_by_id_event, req).await;
                     });
                     Err(Status::not_found("Epic not found"))
                 }
@@ -130,24 +234,46 @@ impl EpicsService for EpicsController {
                     description: None,
                     start_date: None,
                     due_date: None,
+                    progress_percent: None,
+                    metadata: None,
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
+                let (error, status) = db_failure(&err);
                 let req = Request::new(EpicEvent {
-                    epic: Some(epic),
-                    error: Some(error)
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.get_epic_by_id_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epic_by_id_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             }
         }
     }
 
+    async fn find_epic_by_id(
+        &self,
+        request: Request<EpicId>,
+    ) -> Result<Response<EpicLookup>, Status> {
+        let request = Request::new(GetEpicRequest {
+            epic_id: request.into_inner().epic_id,
+            expand: EpicExpand::None as i32,
+        });
+        match self.get_epic_by_id(request).await {
+            Ok(response) => Ok(Response::new(EpicLookup {
+                found: true,
+                epic: Some(response.into_inner()),
+            })),
+            Err(status) if status.code() == Code::NotFound => Ok(Response::new(EpicLookup {
+                found: false,
+                epic: Some(ProtoEpic::default()),
+            })),
+            Err(status) => Err(status),
+        }
+    }
+
     type searchEpicsStream = Pin<Box<dyn Stream<Item = Result<ProtoEpic, Status>> + Send>>;
 
     async fn search_epics(
@@ -155,60 +281,125 @@ impl EpicsService for EpicsController {
         request: Request<SearchEpicsParams>,
     ) -> Result<Response<Self::searchEpicsStream>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
 
-        let mut query = epics.into_boxed();
-
-        let epics_ids = match data.epics_ids.is_empty() {
-            false => Some(&data.epics_ids),
-            true => None,
+        let (limit, offset) = requested(data.page.as_ref(), data.limit, data.offset);
+        let page = match Page::from_params(limit, offset) {
+            Ok(page) => page,
+            Err(message) => {
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(SearchEpicsEvent {
+                    epics: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(eventbus::SearchEpicsParams {
+                        epics_ids: data.epics_ids.clone(),
+                        column_id: data.column_id.clone(),
+                        min_start_date: data.min_start_date.clone(),
+                        max_due_date: data.max_due_date.clone(),
+                        limit,
+                        offset,
+                        created_after: data.created_after.clone(),
+                        created_before: data.created_before.clone(),
+                        min_progress_percent: data.min_progress_percent,
+                        empty_ids_match_none: data.empty_ids_match_none,
+                        text_query: data.text_query.clone(),
+                    }),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_epics_event, req).await;
+                });
+                return Err(status);
+            }
         };
+        let limit = page.effective_limit();
 
-        if let Some(ep_ids) = epics_ids {
-            query = query.filter(id.eq_any(ep_ids));
+        if let Err(message) = check_filter_dates(data) {
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(SearchEpicsEvent {
+                epics: Vec::new(),
+                error: Some(error),
+                search_params: Some(eventbus::SearchEpicsParams {
+                    epics_ids: data.epics_ids.clone(),
+                    column_id: data.column_id.clone(),
+                    min_start_date: data.min_start_date.clone(),
+                    max_due_date: data.max_due_date.clone(),
+                    limit,
+                    offset,
+                    created_after: data.created_after.clone(),
+                    created_before: data.created_before.clone(),
+                    min_progress_percent: data.min_progress_percent,
+                    empty_ids_match_none: data.empty_ids_match_none,
+                    text_query: data.text_query.clone(),
+                }),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, search_epics_event, req).await;
+            });
+            return Err(status);
         }
 
-        if let Some(col_id) = &data.column_id {
-            query = query.filter(column_id.eq(col_id));
-        }
-        
-        if let Some(start) = Option::from({
-            if let Some(seconds) = data.min_start_date.as_ref().map(|x| x.seconds) {
-                if let Some(nanos) = data.min_start_date.as_ref().map(|x| x.nanos) {
-                    Option::from(
-                        NaiveDateTime::from_timestamp(seconds, nanos.try_into().unwrap())
-                    )
-                } else {None}
-            } else {None}
-        }) as Option<NaiveDateTime> {
-            query = query.filter(start_date.ge(start));
-        }
-        
-        if let Some(due) = Option::from({
-            if let Some(seconds) = data.max_due_date.as_ref().map(|x| x.seconds) {
-                if let Some(nanos) = data.max_due_date.as_ref().map(|x| x.nanos) {
-                    Option::from(
-                        NaiveDateTime::from_timestamp(seconds, nanos.try_into().unwrap())
-                    )
-                } else {None}
-            } else {None}
-        }) as Option<NaiveDateTime> {
-            query = query.filter(start_date.le(due));
-        }
+        let permit = self.stream_limit.acquire()?;
 
-        if let Some(limit) = data.limit.clone() {
-            query = query.limit(limit.try_into().unwrap());
-        }
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SearchEpicsEvent {
+                    epics: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(eventbus::SearchEpicsParams {
+                        epics_ids: data.epics_ids.clone(),
+                        column_id: data.column_id.clone(),
+                        min_start_date: data.min_start_date.clone(),
+                        max_due_date: data.max_due_date.clone(),
+                        limit,
+                        offset,
+                        created_after: data.created_after.clone(),
+                        created_before: data.created_before.clone(),
+                        min_progress_percent: data.min_progress_percent,
+                        empty_ids_match_none: data.empty_ids_match_none,
+                        text_query: data.text_query.clone(),
+                    }),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_epics_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let text_query = data.text_query.as_deref().and_then(TextQuery::new);
+
+        let mut query = match &text_query {
+            Some(text_query) => filter_epics(data)
+                .order((text_query.rank(epic_document()).desc(), id.asc())),
+            None => order_epics(filter_epics(data), data),
+        };
+
+        query = query.limit(page.limit);
 
-        if let Some(offset) = data.offset.clone() {
-            query = query.offset(offset.try_into().unwrap());
+        if let Some(page_offset) = page.offset {
+            query = query.offset(page_offset);
         }
 
-        let result: QueryResult<Vec<Epic>> = query
-            .load::<Epic>(&*db_connection);
+        let result: QueryResult<Vec<(Epic, Option<String>)>> = match &text_query {
+            Some(text_query) => query
+                .select((epics_schema::all_columns, text_query.headline(epic_document())))
+                .load::<(Epic, String)>(&*db_connection)
+                .map(|rows| rows.into_iter().map(|(ep, highlight)| (ep, Some(highlight))).collect()),
+            None => query
+                .load::<Epic>(&*db_connection)
+                .map(|rows| rows.into_iter().map(|ep| (ep, None)).collect()),
+        };
 
         match result {
-            Ok(vec) => {
+            Ok(rows) => {
+                let (vec, highlights): (Vec<Epic>, Vec<Option<String>>) = rows.into_iter().unzip();
                 let eps = vec
                     .iter()
                     .map(|epic| eventbus::Epic {
@@ -218,8 +409,10 @@ impl EpicsService for EpicsController {
                         reporter_id: Some(epic.reporter_id.clone()),
                         name: Some(epic.name.clone()),
                         description: epic.description.clone(),
-                        start_date: Some(epic.start_date.clone().to_string()),
-                        due_date: Some(epic.due_date.clone().to_string()),
+                        start_date: epic.start_date.map(|date| date.to_string()),
+                        due_date: epic.due_date.map(|date| date.to_string()),
+                        progress_percent: Some(epic.progress_percent),
+                        metadata: epic.metadata.as_ref().map(|value| value.to_string()),
                     })
                     .collect::<Vec<eventbus::Epic>>();
                 let search_params = eventbus::SearchEpicsParams {
@@ -227,43 +420,60 @@ impl EpicsService for EpicsController {
                     column_id: data.column_id.clone(),
                     min_start_date: data.min_start_date.clone(),
                     max_due_date: data.max_due_date.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
+                    limit,
+                    offset,
+                    created_after: data.created_after.clone(),
+                    created_before: data.created_before.clone(),
+                    min_progress_percent: data.min_progress_percent,
+                    empty_ids_match_none: data.empty_ids_match_none,
+                    text_query: data.text_query.clone(),
+                };
+
+                let converted: Result<Vec<ProtoEpic>, StoredDateOutOfRange> = vec.iter().zip(highlights).map(|(ep, highlight)| Ok(ProtoEpic {
+                    highlight,
+                    ..to_proto_epic(ep)?
+                })).collect();
+                let proto_epics = match converted {
+                    Ok(proto_epics) => proto_epics,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(SearchEpicsEvent {
+                            epics: Vec::new(),
+                            error: Some(error),
+                            search_params: Some(search_params),
+                            correlation_id: correlation_id()
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, search_epics_event, req).await;
+                        });
+                        return Err(status);
+                    }
                 };
 
                 let req = Request::new(SearchEpicsEvent {
-                    epics: eps,
+                    epics: self.event_payload.apply_all(eps),
                     error: None,
-                    search_params: Some(search_params)
-                });
-                let mut service = self.eventbus_service_client.clone();
-
-                let proto_epics: Vec<ProtoEpic> = vec.iter().map(|epic| ProtoEpic {
-                    id: epic.id.clone(),
-                    column_id: epic.column_id.clone(),
-                    assignee_id: epic.assignee_id.clone(),
-                    reporter_id: epic.reporter_id.clone(),
-                    name: epic.name.clone(),
-                    description: epic.description.clone(),
-                    start_date: Option::from(Timestamp::from(SystemTime::from(
-                        DateTime::<Utc>::from_utc(epic.start_date.clone(), Utc)
-                    ))),
-                    due_date: Option::from(Timestamp::from(SystemTime::from(
-                        DateTime::<Utc>::from_utc(epic.due_date.clone(), Utc)
-                    ))),
-                }).collect();
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
         
                 let mut stream = tokio_stream::iter(proto_epics);
                 let (sender, receiver) = mpsc::channel(1);
         
-                tokio::spawn(async move {
+                spawn_tracked(async move {
+                    let _permit = permit;
+                    let mut delivered = true;
                     while let Some(epic) = stream.next().await {
-                        match sender.send(Result::<ProtoEpic, Status>::Ok(epic)).await {
-                            Ok(_) => {},
-                            Err(_err) => break
+                        if sender.send(Result::<ProtoEpic, Status>::Ok(epic)).await.is_err() {
+                            delivered = false;
+                            break;
                         }
                     }
-                    service.search_epics_event(req).await;
+                    if delivered {
+                        send_event!(service, search_epics_event, req).await;
+                    }
                 });
         
                 let output_stream = ReceiverStream::new(receiver);
@@ -284,31 +494,236 @@ impl EpicsService for EpicsController {
                         description: None,
                         start_date: None,
                         due_date: None,
+                        progress_percent: None,
+                        metadata: None,
                     })
                     .collect::<Vec<eventbus::Epic>>();
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
+                let (error, status) = db_failure(&err);
                 let search_params = eventbus::SearchEpicsParams {
                     epics_ids: data.epics_ids.clone(),
                     column_id: data.column_id.clone(),
                     min_start_date: data.min_start_date.clone(),
                     max_due_date: data.max_due_date.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
+                    limit,
+                    offset,
+                    created_after: data.created_after.clone(),
+                    created_before: data.created_before.clone(),
+                    min_progress_percent: data.min_progress_percent,
+                    empty_ids_match_none: data.empty_ids_match_none,
+                    text_query: data.text_query.clone(),
                 };
 
                 let req = Request::new(SearchEpicsEvent {
-                    epics: eps,
+                    epics: self.event_payload.apply_all(eps),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_epics_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    type searchEpicsInChunksStream = Pin<Box<dyn Stream<Item = Result<EpicsChunk, Status>> + Send>>;
+
+    async fn search_epics_in_chunks(
+        &self,
+        request: Request<ChunkedSearchEpicsParams>,
+    ) -> Result<Response<Self::searchEpicsInChunksStream>, Status> {
+        let data = request.into_inner();
+        let size = chunk_size(data.chunk_size).map_err(Status::invalid_argument)?;
+        let rows = self.search_epics(Request::new(data.params.unwrap_or_default())).await?.into_inner();
+
+        Ok(Response::new(
+            Box::pin(chunked(rows, size, |rows| EpicsChunk { epics: rows })) as Self::searchEpicsInChunksStream
+        ))
+    }
+
+    type getEpicsForProjectStream = Pin<Box<dyn Stream<Item = Result<ProtoEpic, Status>> + Send>>;
+
+    async fn get_epics_for_project(
+        &self,
+        request: Request<ProjectEpicsParams>,
+    ) -> Result<Response<Self::getEpicsForProjectStream>, Status> {
+        let data = request.get_ref();
+        let filters = data.filters.clone().unwrap_or_default();
+
+        let (limit, offset) = requested(filters.page.as_ref(), filters.limit, filters.offset);
+        let mut search_params = eventbus::SearchEpicsParams {
+            epics_ids: filters.epics_ids.clone(),
+            column_id: filters.column_id.clone(),
+            min_start_date: filters.min_start_date.clone(),
+            max_due_date: filters.max_due_date.clone(),
+            limit,
+            offset,
+            created_after: filters.created_after.clone(),
+            created_before: filters.created_before.clone(),
+            min_progress_percent: filters.min_progress_percent,
+            empty_ids_match_none: filters.empty_ids_match_none,
+            text_query: filters.text_query.clone(),
+        };
+
+        let page = match Page::from_params(limit, offset) {
+            Ok(page) => page,
+            Err(message) => {
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(ProjectEpicsEvent {
+                    project_id: data.project_id.clone(),
+                    search_params: Some(search_params),
+                    epics: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epics_for_project_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        search_params.limit = page.effective_limit();
+
+        if let Err(message) = check_filter_dates(&filters) {
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(ProjectEpicsEvent {
+                project_id: data.project_id.clone(),
+                search_params: Some(search_params),
+                epics: Vec::new(),
+                error: Some(error),
+                correlation_id: correlation_id(),
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, get_epics_for_project_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let permit = self.stream_limit.acquire()?;
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ProjectEpicsEvent {
+                    project_id: data.project_id.clone(),
+                    search_params: Some(search_params),
+                    epics: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epics_for_project_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let project_columns = columns_schema::table
+            .select(columns_schema::id)
+            .filter(columns_schema::board_id.eq_any(
+                boards::table
+                    .select(boards::id)
+                    .filter(boards::project_id.eq(&data.project_id))
+            ));
+
+        let mut query = order_epics(filter_epics(&filters), &filters)
+            .filter(column_id.eq_any(project_columns));
+
+        query = query.limit(page.limit);
+
+        if let Some(page_offset) = page.offset {
+            query = query.offset(page_offset);
+        }
+
+        let result: QueryResult<Vec<Epic>> = query
+            .load::<Epic>(&*db_connection);
+
+        match result {
+            Ok(vec) => {
+                let eps = vec
+                    .iter()
+                    .map(|epic| eventbus::Epic {
+                        id: Some(epic.id.clone()),
+                        column_id: Some(epic.column_id.clone()),
+                        assignee_id: epic.assignee_id.clone(),
+                        reporter_id: Some(epic.reporter_id.clone()),
+                        name: Some(epic.name.clone()),
+                        description: epic.description.clone(),
+                        start_date: epic.start_date.map(|date| date.to_string()),
+                        due_date: epic.due_date.map(|date| date.to_string()),
+                        progress_percent: Some(epic.progress_percent),
+                        metadata: epic.metadata.as_ref().map(|value| value.to_string()),
+                    })
+                    .collect::<Vec<eventbus::Epic>>();
+                let proto_epics: Vec<ProtoEpic> = match vec.iter().map(to_proto_epic).collect() {
+                    Ok(proto_epics) => proto_epics,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(ProjectEpicsEvent {
+                            project_id: data.project_id.clone(),
+                            search_params: Some(search_params),
+                            epics: Vec::new(),
+                            error: Some(error),
+                            correlation_id: correlation_id(),
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, get_epics_for_project_event, req).await;
+                        });
+                        return Err(status);
+                    }
+                };
+                let req = Request::new(ProjectEpicsEvent {
+                    project_id: data.project_id.clone(),
+                    search_params: Some(search_params),
+                    epics: self.event_payload.apply_all(eps),
+                    error: None,
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+
+                let mut stream = tokio_stream::iter(proto_epics);
+                let (sender, receiver) = mpsc::channel(1);
+
+                spawn_tracked(async move {
+                    let _permit = permit;
+                    let mut delivered = true;
+                    while let Some(epic) = stream.next().await {
+                        if sender.send(Result::<ProtoEpic, Status>::Ok(epic)).await.is_err() {
+                            delivered = false;
+                            break;
+                        }
+                    }
+                    if delivered {
+                        send_event!(service, get_epics_for_project_event, req).await;
+                    }
+                });
+
+                let output_stream = ReceiverStream::new(receiver);
+
+                Ok(Response::new(
+                    Box::pin(output_stream) as Self::getEpicsForProjectStream
+                ))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(ProjectEpicsEvent {
+                    project_id: data.project_id.clone(),
+                    search_params: Some(search_params),
+                    epics: Vec::new(),
                     error: Some(error),
-                    search_params: Some(search_params)
+                    correlation_id: correlation_id(),
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.search_epics_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_epics_for_project_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             }
         }
     }
@@ -318,47 +733,220 @@ impl EpicsService for EpicsController {
         request: Request<CreateEpicRequest>,
     ) -> Result<Response<ProtoEpic>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
 
-        let col_id = match data.column_id.clone() {
-            Some(col_id) => col_id,
-            None => {
-                let result: Vec<Column> = columns
-                    .limit(1)
-                    .load::<Column>(&*db_connection)
-                    .expect("Create epic error");
+        if let Err(message) = self.text_limits.check_title("name", Some(&data.name))
+            .and_then(|_| self.text_limits.check_description("description", data.description.as_deref()))
+            .and_then(|_| self.user_validation.check("reporterId", Some(&data.reporter_id)))
+            .and_then(|_| self.user_validation.check("assigneeId", data.assignee_id.as_deref())) {
+            let epic = eventbus::Epic {
+                id: None,
+                column_id: data.column_id.clone(),
+                assignee_id: data.assignee_id.clone(),
+                reporter_id: Some(data.reporter_id.clone()),
+                name: Some(data.name.clone()),
+                description: data.description.clone(),
+                start_date: None,
+                due_date: None,
+                progress_percent: data.progress_percent,
+                metadata: data.metadata.clone(),
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(EpicEvent {
+                epic: Some(self.event_payload.apply(epic)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_epic_event, req).await;
+            });
+            return Err(status);
+        }
 
-                let column = result
-                    .first()
-                    .unwrap();
+        if let Some(progress) = data.progress_percent {
+            if !(0..=100).contains(&progress) {
+                let epic = eventbus::Epic {
+                    id: None,
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: Some(data.reporter_id.clone()),
+                    name: Some(data.name.clone()),
+                    description: data.description.clone(),
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: Some(progress),
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, "progressPercent must be between 0 and 100");
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        }
 
-                column.id.clone()
-            },
+        let (start, due) = match (
+            date_param("startDate", data.start_date.as_ref()),
+            date_param("dueDate", data.due_date.as_ref()),
+        ) {
+            (Ok(start), Ok(due)) => (start, due),
+            (Err(message), _) | (_, Err(message)) => {
+                let epic = eventbus::Epic {
+                    id: None,
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: Some(data.reporter_id.clone()),
+                    name: Some(data.name.clone()),
+                    description: data.description.clone(),
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
         };
 
-        let start = NaiveDateTime::from_timestamp(
-            data.start_date.as_ref().unwrap().seconds,
-            0,
-        );
+        if let (Some(start), Some(due)) = (start, due) {
+            if start > due {
+                let epic = eventbus::Epic {
+                    id: None,
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: Some(data.reporter_id.clone()),
+                    name: Some(data.name.clone()),
+                    description: data.description.clone(),
+                    start_date: Some(start.to_string()),
+                    due_date: Some(due.to_string()),
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, "startDate must not be later than dueDate");
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        }
 
-        let due = NaiveDateTime::from_timestamp(
-            data.due_date.as_ref().unwrap().seconds,
-            0,
-        );
+        let parsed_metadata = match parse_metadata(data.metadata.as_deref()) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                let epic = eventbus::Epic {
+                    id: None,
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: Some(data.reporter_id.clone()),
+                    name: Some(data.name.clone()),
+                    description: data.description.clone(),
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicEvent {
+                    epic: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let rejected_epic = || eventbus::Epic {
+            id: None,
+            column_id: None,
+            assignee_id: data.assignee_id.clone(),
+            reporter_id: Some(data.reporter_id.clone()),
+            name: Some(data.name.clone()),
+            description: data.description.clone(),
+            start_date: None,
+            due_date: None,
+            progress_percent: data.progress_percent,
+            metadata: data.metadata.clone(),
+        };
+
+        if data.column_id.is_none() && data.board_id.is_none() {
+            let (error, status) = rejection(Code::InvalidArgument, "Either column_id or board_id is required");
+            let req = Request::new(EpicEvent {
+                epic: Some(self.event_payload.apply(rejected_epic())),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_epic_event, req).await;
+            });
+            return Err(status);
+        }
 
         let new_epic = NewEpic {
-            id: &uuid::Uuid::new_v4().to_string(),
-            column_id: &col_id,
-            assignee_id: data.assignee_id.as_ref().map(|x| &**x),
+            id: &new_id(),
+            column_id: data.column_id.as_deref(),
+            assignee_id: data.assignee_id.as_deref(),
             reporter_id: &data.reporter_id,
             name: &data.name,
-            description: data.description.as_ref().map(|x| &**x),
-            start_date: Some(start),
-            due_date: Some(due),
+            description: data.description.as_deref(),
+            start_date: start,
+            due_date: due,
+            progress_percent: data.progress_percent,
+            metadata: parsed_metadata,
         };
 
-        match Epic::create(new_epic, db_connection).await {
+        match Epic::create(new_epic, data.board_id.as_deref(), db_connection).await {
             Ok(ep) => {
+                let proto_epic = to_proto_epic(&ep);
                 let epic = eventbus::Epic {
                     id: Some(ep.id.clone()),
                     column_id: Some(ep.column_id.clone()),
@@ -366,38 +954,24 @@ impl EpicsService for EpicsController {
                     reporter_id: Some(ep.reporter_id.clone()),
                     name: Some(ep.name.clone()),
                     description: ep.description.clone(),
-                    start_date: Some(ep.start_date.clone().to_string()),
-                    due_date: Some(ep.due_date.clone().to_string()),
+                    start_date: ep.start_date.map(|date| date.to_string()),
+                    due_date: ep.due_date.map(|date| date.to_string()),
+                    progress_percent: Some(ep.progress_percent),
+                    metadata: ep.metadata.as_ref().map(|value| value.to_string()),
                 };
                 let req = Request::new(EpicEvent {
-                    epic: Some(epic),
-                    error: None
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
                 
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_epic_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
                 });
 
-                let start_timestamp = Option::from(Timestamp {
-                    seconds: start.timestamp(),
-                    nanos: start.timestamp_subsec_nanos().try_into().unwrap(),
-                });
-                let due_timestamp = Option::from(Timestamp {
-                    seconds: due.timestamp(),
-                    nanos: due.timestamp_subsec_nanos().try_into().unwrap(),
-                });
-
-                Ok(Response::new(ProtoEpic {
-                    id: ep.id.clone(),
-                    column_id: ep.column_id.clone(),
-                    assignee_id: ep.assignee_id.clone(),
-                    reporter_id: ep.reporter_id.clone(),
-                    name: ep.name.clone(),
-                    description: ep.description.clone(),
-                    start_date: start_timestamp,
-                    due_date: due_timestamp,
-                }))
+                Ok(Response::new(proto_epic?))
             },
             Err(err) => {
                 let epic = eventbus::Epic {
@@ -407,22 +981,38 @@ impl EpicsService for EpicsController {
                     reporter_id: Some(data.reporter_id.clone()),
                     name: Some(data.name.clone()),
                     description: data.description.clone(),
-                    start_date: Some(start.to_string()),
-                    due_date: Some(due.to_string()),
+                    start_date: start.map(|date| date.to_string()),
+                    due_date: due.map(|date| date.to_string()),
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                // Only the column lookup for a board can come up empty.
+                let (code, message, status) = match err {
+                    NotFound if data.column_id.is_none() => (
+                        Code::FailedPrecondition,
+                        String::from("Board has no columns"),
+                        Status::failed_precondition("Board has no columns"),
+                    ),
+                    err => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
                 };
                 let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                    code: code.into(),
+                    message
                 };
                 let req = Request::new(EpicEvent {
-                    epic: Some(epic),
-                    error: Some(error)
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_epic_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             },
         }
     }
@@ -432,132 +1022,475 @@ impl EpicsService for EpicsController {
         request: Request<UpdateEpicRequest>,
     ) -> Result<Response<ProtoEpic>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
-
-        let start = NaiveDateTime::from_timestamp(
-            data.start_date.as_ref().unwrap().seconds,
-            0,
-        );
 
-        let due = NaiveDateTime::from_timestamp(
-            data.due_date.as_ref().unwrap().seconds,
-            0,
-        );
+        if let Err(message) = self.text_limits.check_title("name", data.name.as_deref())
+            .and_then(|_| self.text_limits.check_description("description", data.description.as_deref()))
+            .and_then(|_| self.user_validation.check("reporterId", data.reporter_id.as_deref()))
+            .and_then(|_| self.user_validation.check("assigneeId", data.assignee_id.as_deref())) {
+            let epic = eventbus::Epic {
+                id: Some(data.epic_id.clone()),
+                column_id: data.column_id.clone(),
+                assignee_id: data.assignee_id.clone(),
+                reporter_id: data.reporter_id.clone(),
+                name: data.name.clone(),
+                description: data.description.clone(),
+                start_date: None,
+                due_date: None,
+                progress_percent: data.progress_percent,
+                metadata: data.metadata.clone(),
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(EpicEvent {
+                epic: Some(self.event_payload.apply(epic)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, update_epic_event, req).await;
+            });
+            return Err(status);
+        }
 
-        let change_set = EpicChangeSet {
-            column_id: data.to_owned().column_id,
-            assignee_id: data.to_owned().assignee_id,
-            name: data.to_owned().name,
-            reporter_id: data.to_owned().reporter_id,
-            description: data.to_owned().description,
-            start_date: Option::from(start),
-            due_date: Option::from(due),
-        };
-        
-        match Epic::update(&data.epic_id, change_set, db_connection).await {
-            Ok(ep) => {
+        if let Some(progress) = data.progress_percent {
+            if !(0..=100).contains(&progress) {
                 let epic = eventbus::Epic {
-                    id: Some(ep.id.clone()),
-                    column_id: Some(ep.column_id.clone()),
-                    assignee_id: ep.assignee_id.clone(),
-                    reporter_id: Some(ep.reporter_id.clone()),
-                    name: Some(ep.name.clone()),
-                    description: ep.description.clone(),
-                    start_date: Some(ep.start_date.clone().to_string()),
-                    due_date: Some(ep.due_date.clone().to_string()),
-                };
-                let req = Request::new(EpicEvent {
-                    epic: Some(epic),
-                    error: None
-                });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.update_epic_event(req).await;
-                });
-
-                let start_timestamp = Option::from(Timestamp {
-                    seconds: start.timestamp(),
-                    nanos: start.timestamp_subsec_nanos().try_into().unwrap(),
+                    id: Some(data.epic_id.clone()),
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: data.reporter_id.clone(),
+                    name: data.name.clone(),
+                    description: data.description.clone(),
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: Some(progress),
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, "progressPercent must be between 0 and 100");
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        }
+
+        let (start, due) = match (
+            date_param("startDate", data.start_date.as_ref()),
+            date_param("dueDate", data.due_date.as_ref()),
+        ) {
+            (Ok(start), Ok(due)) => (start, due),
+            (Err(message), _) | (_, Err(message)) => {
+                let epic = eventbus::Epic {
+                    id: Some(data.epic_id.clone()),
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: data.reporter_id.clone(),
+                    name: data.name.clone(),
+                    description: data.description.clone(),
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        if let (Some(start), Some(due)) = (start, due) {
+            if start > due {
+                let epic = eventbus::Epic {
+                    id: Some(data.epic_id.clone()),
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: data.reporter_id.clone(),
+                    name: data.name.clone(),
+                    description: data.description.clone(),
+                    start_date: Some(start.to_string()),
+                    due_date: Some(due.to_string()),
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, "startDate must not be later than dueDate");
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        }
+
+        let parsed_metadata = match parse_metadata(data.metadata.as_deref()) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                let epic = eventbus::Epic {
+                    id: Some(data.epic_id.clone()),
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: data.reporter_id.clone(),
+                    name: data.name.clone(),
+                    description: data.description.clone(),
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let due_timestamp = Option::from(Timestamp {
-                    seconds: due.timestamp(),
-                    nanos: due.timestamp_subsec_nanos().try_into().unwrap(),
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
                 });
+                return Err(status);
+            }
+        };
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicEvent {
+                    epic: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let change_set = EpicChangeSet {
+            column_id: data.to_owned().column_id,
+            assignee_id: data.to_owned().assignee_id,
+            name: data.to_owned().name,
+            reporter_id: data.to_owned().reporter_id,
+            description: data.to_owned().description,
+            start_date: start,
+            due_date: due,
+            progress_percent: data.progress_percent,
+            metadata: parsed_metadata,
+        };
         
-                Ok(Response::new(ProtoEpic {
-                    id: ep.id.clone(),
-                    column_id: ep.column_id.clone(),
+        match Epic::update(&data.epic_id, change_set, db_connection).await {
+            Ok((ep, changed)) => {
+                let proto_epic = to_proto_epic(&ep);
+                let epic = eventbus::Epic {
+                    id: Some(ep.id.clone()),
+                    column_id: Some(ep.column_id.clone()),
                     assignee_id: ep.assignee_id.clone(),
-                    reporter_id: ep.reporter_id.clone(),
-                    name: ep.name.clone(),
+                    reporter_id: Some(ep.reporter_id.clone()),
+                    name: Some(ep.name.clone()),
                     description: ep.description.clone(),
-                    start_date: start_timestamp,
-                    due_date: due_timestamp,
-                }))
+                    start_date: ep.start_date.map(|date| date.to_string()),
+                    due_date: ep.due_date.map(|date| date.to_string()),
+                    progress_percent: Some(ep.progress_percent),
+                    metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+                };
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: None,
+                    changed_fields: changed.iter().map(|field| field.to_string()).collect(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+
+                Ok(Response::new(proto_epic?))
             },
+            Err(err) => {
+                let (code, message, status) = match err {
+                    UpdateEpicError::DueBeforeStart => {
+                        let message = "startDate must not be later than dueDate";
+                        (Code::InvalidArgument, message.to_string(), Status::invalid_argument(message))
+                    }
+                    UpdateEpicError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Epic not found"),
+                    ),
+                    UpdateEpicError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let epic = eventbus::Epic {
+                    id: Some(data.epic_id.clone()),
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: data.reporter_id.clone(),
+                    name: data.name.clone(),
+                    description: data.description.clone(),
+                    start_date: start.map(|date| date.to_string()),
+                    due_date: due.map(|date| date.to_string()),
+                    progress_percent: data.progress_percent,
+                    metadata: data.metadata.clone(),
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+                Err(status)
+            },
+        }
+    }
+
+    async fn delete_epic(
+        &self,
+        request: Request<EpicId>,
+    ) -> Result<Response<ProtoEpic>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicEvent {
+                    epic: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Epic::delete(&data.epic_id, db_connection).await {
+            Ok(ep) => {
+                let proto_epic = to_proto_epic(&ep);
+                let epic = eventbus::Epic {
+                    id: Some(ep.id.clone()),
+                    column_id: Some(ep.column_id.clone()),
+                    assignee_id: ep.assignee_id.clone(),
+                    reporter_id: Some(ep.reporter_id.clone()),
+                    name: Some(ep.name.clone()),
+                    description: ep.description.clone(),
+                    start_date: ep.start_date.map(|date| date.to_string()),
+                    due_date: ep.due_date.map(|date| date.to_string()),
+                    progress_percent: Some(ep.progress_percent),
+                    metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+                };
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_epic_event, req).await;
+                });
+
+                Ok(Response::new(proto_epic?))
+            }
             Err(err) => {
                 if err == NotFound {
                     let epic = eventbus::Epic {
                         id: Some(data.epic_id.clone()),
-                        column_id: data.column_id.clone(),
-                        assignee_id: data.assignee_id.clone(),
-                        reporter_id: data.reporter_id.clone(),
-                        name: data.name.clone(),
-                        description: data.description.clone(),
-                        start_date: Some(start.clone().to_string()),
-                        due_date: Some(due.clone().to_string()),
+                        column_id: None,
+                        assignee_id: None,
+                        reporter_id: None,
+                        name: None,
+                        description: None,
+                        start_date: None,
+                        due_date: None,
+                        progress_percent: None,
+                        metadata: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: err.to_string()
                     };
                     let req = Request::new(EpicEvent {
-                        epic: Some(epic),
-                        error: Some(error)
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.update_epic_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_epic_event, req).await;
                     });
                     Err(Status::not_found("Epic not found"))
                 } else {
                     let epic = eventbus::Epic {
                         id: Some(data.epic_id.clone()),
-                        column_id: data.column_id.clone(),
-                        assignee_id: data.assignee_id.clone(),
-                        reporter_id: data.reporter_id.clone(),
-                        name: data.name.clone(),
-                        description: data.description.clone(),
-                        start_date: Some(start.clone().to_string()),
-                        due_date: Some(due.clone().to_string()),
-                    };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
+                        column_id: None,
+                        assignee_id: None,
+                        reporter_id: None,
+                        name: None,
+                        description: None,
+                        start_date: None,
+                        due_date: None,
+                        progress_percent: None,
+                        metadata: None,
                     };
+                    let (error, status) = db_failure(&err);
                     let req = Request::new(EpicEvent {
-                        epic: Some(epic),
-                        error: Some(error)
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.update_epic_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_epic_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
+                    Err(status)
                 }
-            },
+            }
         }
     }
 
-    async fn delete_epic(
+    async fn can_delete_epic(
+        &self,
+        request: Request<EpicId>,
+    ) -> Result<Response<EpicDeletability>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicDeletabilityEvent {
+                    error: Some(error),
+                    epic_id: data.epic_id.clone(),
+                    deletable: None,
+                    reasons: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, can_delete_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Epic::count_references(&data.epic_id, db_connection).await {
+            Ok(references) => {
+                let mut reasons: Vec<String> = Vec::new();
+                if references.issues > 0 {
+                    reasons.push(format!("Epic has {} issue(s)", references.issues));
+                }
+                if references.dependencies > 0 {
+                    reasons.push(format!("Epic has {} dependency(ies)", references.dependencies));
+                }
+                let deletable = reasons.is_empty();
+
+                let req = Request::new(EpicDeletabilityEvent {
+                    error: None,
+                    epic_id: data.epic_id.clone(),
+                    deletable: Some(deletable),
+                    reasons: reasons.clone(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, can_delete_epic_event, req).await;
+                });
+
+                Ok(Response::new(EpicDeletability { deletable, reasons }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Epic not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let req = Request::new(EpicDeletabilityEvent {
+                    error: Some(error),
+                    epic_id: data.epic_id.clone(),
+                    deletable: None,
+                    reasons: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, can_delete_epic_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn touch_epic(
         &self,
         request: Request<EpicId>,
     ) -> Result<Response<ProtoEpic>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicEvent {
+                    epic: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
-        match Epic::delete(&data.epic_id, db_connection).await {
+        match Epic::touch(&data.epic_id, db_connection).await {
             Ok(ep) => {
+                let proto_epic = to_proto_epic(&ep);
                 let epic = eventbus::Epic {
                     id: Some(ep.id.clone()),
                     column_id: Some(ep.column_id.clone()),
@@ -565,37 +1498,23 @@ impl EpicsService for EpicsController {
                     reporter_id: Some(ep.reporter_id.clone()),
                     name: Some(ep.name.clone()),
                     description: ep.description.clone(),
-                    start_date: Some(ep.start_date.clone().to_string()),
-                    due_date: Some(ep.due_date.clone().to_string()),
+                    start_date: ep.start_date.map(|date| date.to_string()),
+                    due_date: ep.due_date.map(|date| date.to_string()),
+                    progress_percent: Some(ep.progress_percent),
+                    metadata: ep.metadata.as_ref().map(|value| value.to_string()),
                 };
                 let req = Request::new(EpicEvent {
-                    epic: Some(epic),
-                    error: None
-                });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.delete_epic_event(req).await;
-                });
-
-                let start_timestamp = Option::from(Timestamp {
-                    seconds: ep.start_date.timestamp(),
-                    nanos: ep.start_date.timestamp_subsec_nanos().try_into().unwrap(),
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let due_timestamp = Option::from(Timestamp {
-                    seconds: ep.due_date.timestamp(),
-                    nanos: ep.due_date.timestamp_subsec_nanos().try_into().unwrap(),
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_epic_event, req).await;
                 });
 
-                Ok(Response::new(ProtoEpic {
-                    id: ep.id.clone(),
-                    column_id: ep.column_id.clone(),
-                    assignee_id: ep.assignee_id.clone(),
-                    reporter_id: ep.reporter_id.clone(),
-                    name: ep.name.clone(),
-                    description: ep.description.clone(),
-                    start_date: start_timestamp,
-                    due_date: due_timestamp,
-                }))
+                Ok(Response::new(proto_epic?))
             }
             Err(err) => {
                 if err == NotFound {
@@ -608,18 +1527,22 @@ impl EpicsService for EpicsController {
                         description: None,
                         start_date: None,
                         due_date: None,
+                        progress_percent: None,
+                        metadata: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: err.to_string()
                     };
                     let req = Request::new(EpicEvent {
-                        epic: Some(epic),
-                        error: Some(error)
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_epic_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_epic_event, req).await;
                     });
                     Err(Status::not_found("Epic not found"))
                 } else {
@@ -632,22 +1555,758 @@ impl EpicsService for EpicsController {
                         description: None,
                         start_date: None,
                         due_date: None,
+                        progress_percent: None,
+                        metadata: None,
                     };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
-                    };
+                    let (error, status) = db_failure(&err);
                     let req = Request::new(EpicEvent {
-                        epic: Some(epic),
-                        error: Some(error)
+                        epic: Some(self.event_payload.apply(epic)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_epic_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_epic_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
+                    Err(status)
                 }
             }
         }
     }
+
+    async fn clone_epic(
+        &self,
+        request: Request<CloneEpicRequest>,
+    ) -> Result<Response<ClonedEpic>, Status> {
+        let data = request.get_ref();
+
+        if let Err(message) = self.text_limits.check_title("name", data.name.as_deref())
+            .and_then(|_| self.user_validation.check("assigneeId", data.assignee_id.as_deref())) {
+            let epic = eventbus::Epic {
+                id: None,
+                column_id: data.column_id.clone(),
+                assignee_id: data.assignee_id.clone(),
+                reporter_id: None,
+                name: data.name.clone(),
+                description: None,
+                start_date: None,
+                due_date: None,
+                progress_percent: None,
+                metadata: None,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(EpicEvent {
+                epic: Some(self.event_payload.apply(epic)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_epic_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let (start, due) = match (
+            date_param("startDate", data.start_date.as_ref()),
+            date_param("dueDate", data.due_date.as_ref()),
+        ) {
+            (Ok(start), Ok(due)) => (start, due),
+            (Err(message), _) | (_, Err(message)) => {
+                let epic = eventbus::Epic {
+                    id: None,
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: None,
+                    name: data.name.clone(),
+                    description: None,
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: None,
+                    metadata: None,
+                };
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(EpicEvent {
+                    epic: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let overrides = EpicOverrides {
+            column_id: data.column_id.clone(),
+            assignee_id: data.assignee_id.clone(),
+            name: data.name.clone(),
+            start_date: start,
+            due_date: due,
+        };
+
+        match Epic::clone_epic(&data.epic_id, overrides, data.include_issues, db_connection).await {
+            Ok((ep, cloned_issues)) => {
+                let proto_epic = to_proto_epic(&ep);
+                let epic = eventbus::Epic {
+                    id: Some(ep.id.clone()),
+                    column_id: Some(ep.column_id.clone()),
+                    assignee_id: ep.assignee_id.clone(),
+                    reporter_id: Some(ep.reporter_id.clone()),
+                    name: Some(ep.name.clone()),
+                    description: ep.description.clone(),
+                    start_date: ep.start_date.map(|date| date.to_string()),
+                    due_date: ep.due_date.map(|date| date.to_string()),
+                    progress_percent: Some(ep.progress_percent),
+                    metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+                };
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+
+                for iss in &cloned_issues {
+                    let issue = eventbus::Issue {
+                        id: Some(iss.id.clone()),
+                        column_id: Some(iss.column_id.clone()),
+                        epic_id: Some(iss.epic_id.clone()),
+                        title: Some(iss.title.clone()),
+                        description: Some(iss.description.clone()),
+                        status: Some(iss.status.into()),
+                    };
+                    let req = Request::new(IssueEvent {
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: None,
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.issues_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_issue_event, req).await;
+                    });
+                }
+
+                let proto_issues = cloned_issues
+                    .iter()
+                    .map(to_proto_issue)
+                    .collect::<Result<Vec<ProtoIssue>, _>>();
+
+                Ok(Response::new(ClonedEpic {
+                    epic: Some(proto_epic?),
+                    issues: proto_issues?,
+                }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Epic not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let epic = eventbus::Epic {
+                    id: None,
+                    column_id: data.column_id.clone(),
+                    assignee_id: data.assignee_id.clone(),
+                    reporter_id: None,
+                    name: data.name.clone(),
+                    description: None,
+                    start_date: None,
+                    due_date: None,
+                    progress_percent: None,
+                    metadata: None,
+                };
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_epic_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+    async fn shift_epic_dates(
+        &self,
+        request: Request<ShiftEpicDatesRequest>,
+    ) -> Result<Response<ShiftedEpics>, Status> {
+        let data = request.get_ref();
+
+        let requested_epics = || data.epics_ids
+            .iter()
+            .map(|epic_id| eventbus::Epic {
+                id: Some(epic_id.clone()),
+                column_id: None,
+                assignee_id: None,
+                reporter_id: None,
+                name: None,
+                description: None,
+                start_date: None,
+                due_date: None,
+                progress_percent: None,
+                metadata: None,
+            })
+            .collect::<Vec<eventbus::Epic>>();
+
+        if data.epics_ids.is_empty() {
+            let (error, status) = rejection(Code::InvalidArgument, "epicsIds must not be empty");
+            let req = Request::new(ShiftEpicDatesEvent {
+                error: Some(error),
+                delta_days: data.delta_days,
+                epics: Vec::new(),
+                correlation_id: correlation_id(),
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, shift_epic_dates_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ShiftEpicDatesEvent {
+                    error: Some(error),
+                    delta_days: data.delta_days,
+                    epics: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, shift_epic_dates_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Epic::shift_dates(&data.epics_ids, Duration::days(data.delta_days.into()), db_connection).await {
+            Ok(shifted) => {
+                let proto_epics = shifted
+                    .iter()
+                    .map(to_proto_epic)
+                    .collect::<Result<Vec<ProtoEpic>, _>>();
+                let eps = shifted
+                    .iter()
+                    .map(|ep| eventbus::Epic {
+                        id: Some(ep.id.clone()),
+                        column_id: Some(ep.column_id.clone()),
+                        assignee_id: ep.assignee_id.clone(),
+                        reporter_id: Some(ep.reporter_id.clone()),
+                        name: Some(ep.name.clone()),
+                        description: ep.description.clone(),
+                        start_date: ep.start_date.map(|date| date.to_string()),
+                        due_date: ep.due_date.map(|date| date.to_string()),
+                        progress_percent: Some(ep.progress_percent),
+                        metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+                    })
+                    .collect::<Vec<eventbus::Epic>>();
+                let req = Request::new(ShiftEpicDatesEvent {
+                    error: None,
+                    delta_days: data.delta_days,
+                    epics: self.event_payload.apply_all(eps),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, shift_epic_dates_event, req).await;
+                });
+
+                Ok(Response::new(ShiftedEpics { epics: proto_epics? }))
+            }
+            Err(err) => {
+                let (code, message, status) = match err {
+                    ShiftEpicDatesError::OutOfRange(epic_id) => {
+                        let message = format!("Shifted dates of epic {} are out of range", epic_id);
+                        (Code::InvalidArgument, message.clone(), Status::invalid_argument(message))
+                    }
+                    ShiftEpicDatesError::DueBeforeStart(epic_id) => {
+                        let message = format!("dueDate of epic {} would precede its startDate", epic_id);
+                        (Code::FailedPrecondition, message.clone(), Status::failed_precondition(message))
+                    }
+                    ShiftEpicDatesError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Epic not found"),
+                    ),
+                    ShiftEpicDatesError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(ShiftEpicDatesEvent {
+                    error: Some(error),
+                    delta_days: data.delta_days,
+                    epics: self.event_payload.apply_all(requested_epics()),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, shift_epic_dates_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn merge_epics(
+        &self,
+        request: Request<MergeEpicsRequest>,
+    ) -> Result<Response<MergedEpics>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(MergeEpicsEvent {
+                    error: Some(error),
+                    source_epic_id: data.source_epic_id.clone(),
+                    target_epic_id: data.target_epic_id.clone(),
+                    moved_issues_ids: Vec::new(),
+                    moved_dependencies: None,
+                    dropped_dependencies: None,
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, merge_epics_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Epic::merge(&data.source_epic_id, &data.target_epic_id, db_connection).await {
+            Ok(merged) => {
+                let proto_epic = to_proto_epic(&merged.target);
+
+                for iss in &merged.moved_issues {
+                    let issue = eventbus::Issue {
+                        id: Some(iss.id.clone()),
+                        column_id: Some(iss.column_id.clone()),
+                        epic_id: Some(iss.epic_id.clone()),
+                        title: Some(iss.title.clone()),
+                        description: Some(iss.description.clone()),
+                        status: Some(iss.status.into()),
+                    };
+                    let req = Request::new(IssueEvent {
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: None,
+                        changed_fields: vec![String::from("epic_id")],
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.issues_eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_issue_event, req).await;
+                    });
+                }
+
+                let ep = &merged.source;
+                let epic = eventbus::Epic {
+                    id: Some(ep.id.clone()),
+                    column_id: Some(ep.column_id.clone()),
+                    assignee_id: ep.assignee_id.clone(),
+                    reporter_id: Some(ep.reporter_id.clone()),
+                    name: Some(ep.name.clone()),
+                    description: ep.description.clone(),
+                    start_date: ep.start_date.map(|date| date.to_string()),
+                    due_date: ep.due_date.map(|date| date.to_string()),
+                    progress_percent: Some(ep.progress_percent),
+                    metadata: ep.metadata.as_ref().map(|value| value.to_string()),
+                };
+                let req = Request::new(EpicEvent {
+                    epic: Some(self.event_payload.apply(epic)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_epic_event, req).await;
+                });
+
+                let moved_issues = merged.moved_issues.len() as i32;
+                let moved_dependencies = merged.moved_dependencies as i32;
+                let dropped_dependencies = merged.dropped_dependencies as i32;
+
+                let req = Request::new(MergeEpicsEvent {
+                    error: None,
+                    source_epic_id: data.source_epic_id.clone(),
+                    target_epic_id: data.target_epic_id.clone(),
+                    moved_issues_ids: merged.moved_issues.iter().map(|iss| iss.id.clone()).collect(),
+                    moved_dependencies: Some(moved_dependencies),
+                    dropped_dependencies: Some(dropped_dependencies),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, merge_epics_event, req).await;
+                });
+
+                Ok(Response::new(MergedEpics {
+                    epic: Some(proto_epic?),
+                    moved_issues,
+                    moved_dependencies,
+                    dropped_dependencies,
+                }))
+            }
+            Err(err) => {
+                let (code, message, status) = match err {
+                    MergeEpicsError::SameEpic => {
+                        let message = String::from("An epic cannot be merged into itself");
+                        (Code::InvalidArgument, message.clone(), Status::invalid_argument(message))
+                    }
+                    MergeEpicsError::Cycle(cycle) => {
+                        let message = format!(
+                            "Merging would create a dependency cycle: {} -> {}",
+                            cycle.join(" -> "),
+                            cycle.first().map(String::as_str).unwrap_or_default(),
+                        );
+                        (Code::FailedPrecondition, message.clone(), Status::failed_precondition(message))
+                    }
+                    MergeEpicsError::Db(NotFound) => (
+                        Code::NotFound,
+                        NotFound.to_string(),
+                        Status::not_found("Epic not found"),
+                    ),
+                    MergeEpicsError::Db(err) => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(MergeEpicsEvent {
+                    error: Some(error),
+                    source_epic_id: data.source_epic_id.clone(),
+                    target_epic_id: data.target_epic_id.clone(),
+                    moved_issues_ids: Vec::new(),
+                    moved_dependencies: None,
+                    dropped_dependencies: None,
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, merge_epics_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+}
+
+/// Builds the filtered query. The date filters must have passed
+/// `check_filter_dates`.
+fn filter_epics(data: &SearchEpicsParams) -> BoxedEpicsQuery<'_, Pg> {
+    let mut query = epics.into_boxed();
+
+    let epics_ids = match (data.epics_ids.is_empty(), data.empty_ids_match_none.unwrap_or(false)) {
+        (true, false) => None,
+        _ => Some(&data.epics_ids),
+    };
+
+    if let Some(ep_ids) = epics_ids {
+        query = query.filter(id.eq_any(ep_ids));
+    }
+
+    if let Some(col_id) = &data.column_id {
+        query = query.filter(column_id.eq(col_id));
+    }
+    
+    if let Some(start) = data.min_start_date
+        .as_ref()
+        .and_then(from_timestamp) {
+        query = query.filter(start_date.ge(start));
+    }
+    
+    if let Some(due) = data.max_due_date
+        .as_ref()
+        .and_then(from_timestamp) {
+        query = query.filter(due_date.le(due));
+    }
+
+    if let Some(after) = data.created_after
+        .as_ref()
+        .and_then(from_timestamp) {
+        query = query.filter(created_at.ge(after));
+    }
+
+    if let Some(before) = data.created_before
+        .as_ref()
+        .and_then(from_timestamp) {
+        query = query.filter(created_at.lt(before));
+    }
+
+    if let Some(min_progress) = data.min_progress_percent {
+        query = query.filter(progress_percent.ge(min_progress));
+    }
+
+    if let Some(terms) = &data.text_query {
+        query = match TextQuery::new(terms) {
+            Some(text_query) => query.filter(text_query.matches(epic_document())),
+            None => query.filter(epic_document().ilike(format!("%{}%", escape_like(terms)))),
+        };
+    }
+
+    query
+}
+
+sql_function!(fn coalesce(value: Nullable<Text>, fallback: Text) -> Text);
+
+/// What text searches match epics against: the name and the description.
+type EpicDocument = Concat<Concat<name, Bound<Text, &'static str>>, coalesce::HelperType<description, &'static str>>;
+
+fn epic_document() -> EpicDocument {
+    name.concat(" ").concat(coalesce(description, ""))
+}
+
+fn parse_metadata(document: Option<&str>) -> Result<Option<Value>, String> {
+    document
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|err| format!("metadata must be valid JSON: {}", err))
+}
+
+fn epics_order(data: &SearchEpicsParams) -> EpicsOrder {
+    data.order_by
+        .and_then(EpicsOrder::from_i32)
+        .unwrap_or(EpicsOrder::Id)
+}
+
+fn order_epics<'a>(query: BoxedEpicsQuery<'a, Pg>, data: &SearchEpicsParams) -> BoxedEpicsQuery<'a, Pg> {
+    match (epics_order(data), data.descending.unwrap_or(false)) {
+        (EpicsOrder::Id, false) => query.order(id.asc()),
+        (EpicsOrder::Id, true) => query.order(id.desc()),
+        (EpicsOrder::StartDate, false) => query.order((start_date.asc(), id.asc())),
+        (EpicsOrder::StartDate, true) => query.order((start_date.desc(), id.desc())),
+        (EpicsOrder::DueDate, false) => query.order((due_date.asc(), id.asc())),
+        (EpicsOrder::DueDate, true) => query.order((due_date.desc(), id.desc())),
+        (EpicsOrder::Name, false) => query.order((name.asc(), id.asc())),
+        (EpicsOrder::Name, true) => query.order((name.desc(), id.desc())),
+    }
+}
+
+/// Converts a date from the API for storage, keeping its nanoseconds.
+/// `None` when the seconds are out of range or `nanos` is outside
+/// `0..1_000_000_000`.
+pub(crate) fn from_timestamp(date: &Timestamp) -> Option<NaiveDateTime> {
+    let nanos = u32::try_from(date.nanos)
+        .ok()
+        .filter(|nanos| *nanos < 1_000_000_000)?;
+    DateTime::from_timestamp(date.seconds, nanos).map(|date| date.naive_utc())
+}
+
+/// Converts an optional date parameter, naming the field when it is not a
+/// valid `Timestamp`.
+fn date_param(field: &str, date: Option<&Timestamp>) -> Result<Option<NaiveDateTime>, String> {
+    match date {
+        Some(date) => from_timestamp(date)
+            .map(Some)
+            .ok_or_else(|| format!("{} is not a valid timestamp", field)),
+        None => Ok(None),
+    }
+}
+
+/// Checks the date filters up front so `filter_epics` can rely on them.
+/// Shared by every epic listing.
+fn check_filter_dates(data: &SearchEpicsParams) -> Result<(), String> {
+    date_param("minStartDate", data.min_start_date.as_ref())?;
+    date_param("maxDueDate", data.max_due_date.as_ref())?;
+    let created_after = date_param("createdAfter", data.created_after.as_ref())?;
+    let created_before = date_param("createdBefore", data.created_before.as_ref())?;
+    match (created_after, created_before) {
+        (Some(after), Some(before)) if after > before => {
+            Err(String::from("createdAfter must not be later than createdBefore"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A stored date `Timestamp` cannot carry. Becomes `internal` through `?`.
+#[derive(Debug)]
+pub(crate) struct StoredDateOutOfRange;
+
+impl StoredDateOutOfRange {
+    /// The error for the event and the status to return, like `db_failure`
+    /// does for a failed query.
+    pub(crate) fn failure(self) -> (eventbus::Error, Status) {
+        let message = "stored timestamp out of range";
+        let error = eventbus::Error {
+            code: Code::Internal.into(),
+            message: message.to_string(),
+        };
+        (error, Status::internal(message))
+    }
+}
+
+impl From<StoredDateOutOfRange> for Status {
+    fn from(out_of_range: StoredDateOutOfRange) -> Self {
+        out_of_range.failure().1
+    }
+}
+
+/// Converts a stored date for the API. A date `Timestamp` cannot carry is
+/// logged and fails the call rather than panicking the handler.
+pub(crate) fn to_timestamp(date: NaiveDateTime) -> Result<Timestamp, StoredDateOutOfRange> {
+    let utc = date.and_utc();
+    match i32::try_from(utc.timestamp_subsec_nanos()) {
+        Ok(nanos) if nanos < 1_000_000_000 => Ok(Timestamp {
+            seconds: utc.timestamp(),
+            nanos,
+        }),
+        _ => {
+            eprintln!("error: stored timestamp {} is out of range", date);
+            Err(StoredDateOutOfRange)
+        }
+    }
+}
+
+pub(crate) fn to_proto_epic(epic: &Epic) -> Result<ProtoEpic, StoredDateOutOfRange> {
+    Ok(ProtoEpic {
+        id: epic.id.clone(),
+        column_id: epic.column_id.clone(),
+        assignee_id: epic.assignee_id.clone(),
+        reporter_id: epic.reporter_id.clone(),
+        name: epic.name.clone(),
+        description: epic.description.clone(),
+        start_date: epic.start_date.map(to_timestamp).transpose()?,
+        due_date: epic.due_date.map(to_timestamp).transpose()?,
+        updated_at: Some(to_timestamp(epic.updated_at)?),
+        created_at: Some(to_timestamp(epic.created_at)?),
+        progress_percent: epic.progress_percent,
+        metadata: epic.metadata.as_ref().map(|value| value.to_string()),
+        column_name: None,
+        board_id: None,
+        project_id: None,
+        highlight: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        connection::test_pool,
+        repos::{board::{NewBoard, CreateBoard, DeleteBoard}, column::{NewColumn, CreateColumn}},
+    };
+
+    #[test]
+    fn check_filter_dates_rejects_a_reversed_creation_window() {
+        let window = |after: i64, before: i64| SearchEpicsParams {
+            created_after: Some(Timestamp { seconds: after, nanos: 0 }),
+            created_before: Some(Timestamp { seconds: before, nanos: 0 }),
+            ..Default::default()
+        };
+
+        assert_eq!(check_filter_dates(&window(10, 10)), Ok(()));
+        assert_eq!(
+            check_filter_dates(&window(20, 10)),
+            Err(String::from("createdAfter must not be later than createdBefore")),
+        );
+        let invalid = SearchEpicsParams { max_due_date: Some(Timestamp { seconds: 0, nanos: -1 }), ..window(10, 20) };
+        assert!(check_filter_dates(&invalid).is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn max_due_date_filters_on_the_due_date() {
+        let pool = test_pool();
+        let board_id = new_id();
+        Board::create(NewBoard { id: &board_id, project_id: &new_id() }, pool.get().unwrap())
+            .await
+            .unwrap();
+        let column = Column::create(NewColumn { id: &new_id(), board_id: &board_id, name: "todo" }, pool.get().unwrap())
+            .await
+            .unwrap()
+            .id;
+        let day = DateTime::from_timestamp(1_657_000_000, 0).unwrap().naive_utc();
+
+        // Started in reverse order of their due dates, so filtering on the
+        // start date would pick the other end of the range.
+        let mut created = Vec::new();
+        for days in 0..4 {
+            let epic = Epic::create(NewEpic {
+                id: &new_id(),
+                column_id: Some(&column),
+                assignee_id: None,
+                reporter_id: "reporter",
+                name: "epic",
+                description: None,
+                start_date: Some(day - Duration::days(days)),
+                due_date: Some(day + Duration::days(days)),
+                progress_percent: None,
+                metadata: None,
+            }, None, pool.get().unwrap()).await.unwrap();
+            created.push(epic.id);
+        }
+
+        let params = SearchEpicsParams {
+            column_id: Some(column),
+            max_due_date: Some(to_timestamp(day + Duration::days(1)).unwrap()),
+            ..Default::default()
+        };
+        let mut found_due: Vec<String> = filter_epics(&params)
+            .select(id)
+            .load(&*pool.get().unwrap())
+            .unwrap();
+        found_due.sort();
+
+        let params = SearchEpicsParams {
+            min_start_date: Some(to_timestamp(day - Duration::days(1)).unwrap()),
+            ..params
+        };
+        let mut found_started: Vec<String> = filter_epics(&params)
+            .select(id)
+            .load(&*pool.get().unwrap())
+            .unwrap();
+        found_started.sort();
+        assert!(Board::delete(&board_id, pool.get().unwrap()).await.is_ok(), "board was not deleted");
+
+        let mut due_by_day_one = created[..2].to_vec();
+        due_by_day_one.sort();
+        assert_eq!(found_due, due_by_day_one);
+        assert_eq!(found_started, due_by_day_one);
+    }
 }
@@ -0,0 +1,82 @@
+use std::pin::Pin;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use futures::Stream;
+use proto::health::{
+    health_server::Health,
+    health_check_response::ServingStatus,
+    HealthCheckRequest,
+    HealthCheckResponse,
+};
+
+use crate::lifecycle::{Lifecycle, ServiceState};
+
+pub struct HealthController {
+    pub lifecycle: Lifecycle,
+    /// Fully qualified names of the services behind this server. An empty
+    /// name in a request asks about the server as a whole.
+    pub services: Vec<&'static str>,
+}
+
+fn serving_status(state: ServiceState) -> ServingStatus {
+    match state {
+        ServiceState::Ready => ServingStatus::Serving,
+        ServiceState::Starting | ServiceState::Draining | ServiceState::Stopped => ServingStatus::NotServing,
+    }
+}
+
+impl HealthController {
+    fn is_known(&self, service: &str) -> bool {
+        service.is_empty() || self.services.contains(&service)
+    }
+}
+
+#[tonic::async_trait]
+impl Health for HealthController {
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        if !self.is_known(&request.get_ref().service) {
+            return Err(Status::not_found("Unknown service"));
+        }
+
+        Ok(Response::new(HealthCheckResponse {
+            status: serving_status(self.lifecycle.state()).into(),
+        }))
+    }
+
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send>>;
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        if !self.is_known(&request.get_ref().service) {
+            return Err(Status::not_found("Unknown service"));
+        }
+
+        let mut states = self.lifecycle.subscribe();
+        let (sender, receiver) = mpsc::channel(1);
+
+        tokio::spawn(async move {
+            loop {
+                let state = *states.borrow();
+                let response = HealthCheckResponse {
+                    status: serving_status(state).into(),
+                };
+                if sender.send(Ok(response)).await.is_err() || state == ServiceState::Stopped {
+                    break;
+                }
+                if states.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(
+            Box::pin(ReceiverStream::new(receiver)) as Self::WatchStream
+        ))
+    }
+}
@@ -1,45 +1,137 @@
 use std::pin::Pin;
+use std::collections::HashSet;
+use prost::Message;
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, StreamExt};
-use diesel::{RunQueryDsl, QueryDsl, ExpressionMethods, QueryResult, result::Error::NotFound};
-use tonic::{Request, Response, Status, Code, transport::Channel};
+use diesel::{
+    RunQueryDsl,
+    QueryDsl,
+    ExpressionMethods,
+    BoolExpressionMethods,
+    PgTextExpressionMethods,
+    TextExpressionMethods,
+    QueryResult,
+    JoinOnDsl,
+    pg::Pg,
+    result::{Error::{NotFound, DatabaseError}, DatabaseErrorKind},
+};
+use tonic::{Request, Response, Status, Code, metadata::MetadataValue, transport::Channel};
 use futures::Stream;
 use proto::{
     issues::{
         issues_service_server::IssuesService,
         Issue as ProtoIssue,
         IssueId,
+        GetIssueRequest,
+        IssueExpand,
+        IssueLink as ProtoIssueLink,
+        IssueLinkId,
+        IssueLinks as ProtoIssueLinks,
+        AddIssueLinkRequest,
+        IssueLinkKind,
+        IssueLookup,
+        IssueAncestry,
+        IssueMoveHistory,
+        IssueMove as ProtoIssueMove,
+        ChurnRequest,
+        BoardChurn,
+        IssueChurn as ProtoIssueChurn,
+        BoardId,
+        IssueStatusCount,
+        IssuesCountByStatus,
+        Column as ProtoColumn,
+        Board as ProtoBoard,
         CreateIssueRequest,
         UpdateIssueRequest,
+        MoveIssueRequest,
+        IssueStatus,
+        TransitionIssuesRequest,
+        TransitionResult,
+        IssueTransition,
+        IssueTransitions,
+        CreateIssuesBatchRequest,
+        CreatedIssues,
         SearchIssuesParams,
+        ChunkedSearchIssuesParams,
+        IssuesChunk,
+        IssuesCount,
     }, 
     eventbus::{
         self,
-        issues_events_service_client::IssuesEventsServiceClient, IssueEvent, SearchIssuesEvent,
+        issues_events_service_client::IssuesEventsServiceClient, IssueEvent, SearchIssuesEvent, IssuesCountEvent, ChurnEvent,
+        IssuesCountByStatusEvent,
+        IssueLinkEvent, IssueLinksEvent,
     },
 };
 
 use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    controllers::epics::{to_proto_epic, from_timestamp, to_timestamp, StoredDateOutOfRange},
+    ids::new_id,
+    events::{EventPayload, db_failure, pooled_connection, rejection, send_event},
+    pagination::{Page, max_page_size, requested, chunk_size, chunked},
+    streams::StreamLimit,
+    limits::{TextLimits, check_not_blank, check_url},
     db::{
-        repos::issue::{NewIssue, Issue, CreateIssue, UpdateIssue, IssueChangeSet, DeleteIssue},
-        schema::issues::dsl::*,
+        repos::{
+            issue::{
+                NewIssue, Issue, CreateIssue, CreateIssues, UpdateIssue, MoveIssue, MoveIssueError, IssueChangeSet,
+                DeleteIssue, TouchIssue, TransitionIssues, TransitionOutcome, CountIssuesByStatus,
+            },
+            column::Column,
+            epic::Epic,
+            board::Board,
+            issue_move::{IssueMove, IssueMoveHistory as _, IssueChurn as _},
+            issue_link::{IssueLink, NewIssueLink, CreateIssueLink, DeleteIssueLink, IssueLinks},
+            outbox::NewOutboxEvent,
+        },
+        schema::{issues::{self as issues_schema, dsl::{issues, id, column_id, epic_id, title, description}}, columns, epics, boards},
+        full_text::TextQuery,
         connection::PgPool
     },
 };
 
+/// How many issues getChurn returns when the request sets no limit.
+const DEFAULT_CHURN_LIMIT: i64 = 20;
+
+/// Response header with the `afterId` of the next `searchIssues` page.
+const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
 pub struct IssuesController {
     pub pool: PgPool,
-    pub eventbus_service_client: IssuesEventsServiceClient<Channel>
+    /// Serves the get and search handlers; see `establish_read_connection`.
+    pub read_pool: PgPool,
+    pub eventbus_service_client: Option<IssuesEventsServiceClient<Channel>>,
+    pub event_payload: EventPayload,
+    pub stream_limit: StreamLimit,
+    pub text_limits: TextLimits,
 }
 
 #[tonic::async_trait]
 impl IssuesService for IssuesController {
     async fn get_issue_by_id(
         &self,
-        request: Request<IssueId>,
+        request: Request<GetIssueRequest>,
     ) -> Result<Response<ProtoIssue>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_by_id_event, req).await;
+                });
+                return Err(status);
+            }
+        };
         let result: QueryResult<Vec<Issue>> = issues
             .filter(id.eq(&request.get_ref().issue_id))
             .limit(1)
@@ -48,29 +140,64 @@ impl IssuesService for IssuesController {
         match result {
             Ok(vec) => {
                 if let Some(iss) = vec.first() {
+                    let links: Result<Vec<ProtoIssueLink>, _> = if data.expand == IssueExpand::Links as i32 {
+                        match IssueLink::for_issue(&iss.id, db_connection).await {
+                            Ok(lnks) => lnks.iter().map(to_proto_issue_link).collect(),
+                            Err(err) => {
+                                let issue = eventbus::Issue {
+                                    id: Some(data.issue_id.clone()),
+                                    column_id: None,
+                                    epic_id: None,
+                                    title: None,
+                                    description: None,
+                                    status: None,
+                                };
+                                let (error, status) = db_failure(&err);
+                                let req = Request::new(IssueEvent {
+                                    issue: Some(self.event_payload.apply(issue)),
+                                    error: Some(error),
+                                    changed_fields: Vec::new(),
+                                    correlation_id: correlation_id()
+                                });
+                                let service = self.eventbus_service_client.clone();
+                                spawn_tracked(async move {
+                                    send_event!(service, get_issue_by_id_event, req).await;
+                                });
+                                return Err(status);
+                            }
+                        }
+                    } else {
+                        Ok(Vec::new())
+                    };
+
                     let issue = eventbus::Issue {
                         id: Some(iss.id.clone()),
                         column_id: Some(iss.column_id.clone()),
                         epic_id: Some(iss.epic_id.clone()),
                         title: Some(iss.title.clone()),
                         description: Some(iss.description.clone()),
+                        status: Some(iss.status.into()),
+                    };
+                    let proto_issue = links.and_then(|links| Ok(ProtoIssue { links, ..to_proto_issue(iss)? }));
+                    let (error, result) = match proto_issue {
+                        Ok(proto_issue) => (None, Ok(Response::new(proto_issue))),
+                        Err(out_of_range) => {
+                            let (error, status) = out_of_range.failure();
+                            (Some(error), Err(status))
+                        }
                     };
                     let req = Request::new(IssueEvent {
-                        issue: Some(issue),
-                        error: None
+                        issue: Some(self.event_payload.apply(issue)),
+                        error,
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_issue_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_issue_by_id_event, req).await;
                     });
 
-                    Ok(Response::new(ProtoIssue {
-                        id: iss.id.clone(),
-                        column_id: iss.column_id.clone(),
-                        epic_id: iss.epic_id.clone(),
-                        title: iss.title.clone(),
-                        description: iss.description.clone(),
-                    }))
+                    result
                 } else {
                     let issue = eventbus::Issue {
                         id: Some(data.issue_id.clone()),
@@ -78,18 +205,21 @@ impl IssuesService for IssuesController {
                         epic_id: None,
                         title: None,
                         description: None,
+                        status: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: String::from("Issue not found")
                     };
                     let req = Request::new(IssueEvent {
-                        issue: Some(issue),
-                        error: Some(error)
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.get_issue_by_id_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, get_issue_by_id_event, req).await;
                     });
                     Err(Status::not_found("Issue not found"))
                 }
@@ -101,20 +231,415 @@ impl IssuesService for IssuesController {
                     epic_id: None,
                     title: None,
                     description: None,
+                    status: None,
                 };
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                let (error, status) = db_failure(&err);
+                let req = Request::new(IssueEvent {
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_by_id_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn find_issue_by_id(
+        &self,
+        request: Request<IssueId>,
+    ) -> Result<Response<IssueLookup>, Status> {
+        let request = Request::new(GetIssueRequest {
+            issue_id: request.into_inner().issue_id,
+            expand: IssueExpand::None as i32,
+        });
+        match self.get_issue_by_id(request).await {
+            Ok(response) => Ok(Response::new(IssueLookup {
+                found: true,
+                issue: Some(response.into_inner()),
+            })),
+            Err(status) if status.code() == Code::NotFound => Ok(Response::new(IssueLookup {
+                found: false,
+                issue: Some(ProtoIssue::default()),
+            })),
+            Err(status) => Err(status),
+        }
+    }
+
+    async fn get_issue_ancestry(
+        &self,
+        request: Request<IssueId>,
+    ) -> Result<Response<IssueAncestry>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_ancestry_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<(Issue, Option<Epic>, Option<Column>, Option<Board>)> = issues
+            .left_join(epics::table.on(epics::id.eq(epic_id)))
+            .left_join(columns::table.on(columns::id.eq(column_id)))
+            .left_join(boards::table.on(boards::id.eq(columns::board_id)))
+            .filter(id.eq(&data.issue_id))
+            .first(&*db_connection);
+
+        match result {
+            Ok((iss, ep, clmn, brd)) => {
+                let issue = eventbus::Issue {
+                    id: Some(iss.id.clone()),
+                    column_id: Some(iss.column_id.clone()),
+                    epic_id: Some(iss.epic_id.clone()),
+                    title: Some(iss.title.clone()),
+                    description: Some(iss.description.clone()),
+                    status: Some(iss.status.into()),
+                };
+                let converted = to_proto_issue(&iss)
+                    .and_then(|proto_issue| Ok((proto_issue, ep.as_ref().map(to_proto_epic).transpose()?)));
+                let (proto_issue, proto_epic) = match converted {
+                    Ok(converted) => converted,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(IssueEvent {
+                            issue: Some(self.event_payload.apply(issue)),
+                            error: Some(error),
+                            changed_fields: Vec::new(),
+                            correlation_id: correlation_id()
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, get_issue_ancestry_event, req).await;
+                        });
+                        return Err(status);
+                    }
+                };
+                let req = Request::new(IssueEvent {
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_ancestry_event, req).await;
+                });
+
+                Ok(Response::new(IssueAncestry {
+                    issue: Some(proto_issue),
+                    epic: proto_epic,
+                    column: clmn.map(|clmn| ProtoColumn {
+                        id: clmn.id,
+                        board_id: clmn.board_id,
+                        name: clmn.name,
+                        position: clmn.position,
+                        archived: clmn.archived,
+                    }),
+                    project_id: brd.as_ref().map(|brd| brd.project_id.clone()),
+                    board: brd.map(|brd| ProtoBoard {
+                        id: brd.id,
+                        project_id: brd.project_id,
+                        columns: Vec::new(),
+                        deletion_protected: brd.deletion_protected,
+                    }),
+                }))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Issue not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let issue = eventbus::Issue {
+                    id: Some(data.issue_id.clone()),
+                    column_id: None,
+                    epic_id: None,
+                    title: None,
+                    description: None,
+                    status: None,
+                };
+                let req = Request::new(IssueEvent {
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_ancestry_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn get_issue_move_history(
+        &self,
+        request: Request<IssueId>,
+    ) -> Result<Response<IssueMoveHistory>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_move_history_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let existing: QueryResult<String> = issues
+            .filter(id.eq(&data.issue_id))
+            .select(id)
+            .first::<String>(&*db_connection);
+
+        let result = match existing {
+            Ok(_) => IssueMove::history(&data.issue_id, db_connection).await,
+            Err(err) => Err(err),
+        };
+
+        let issue = eventbus::Issue {
+            id: Some(data.issue_id.clone()),
+            column_id: None,
+            epic_id: None,
+            title: None,
+            description: None,
+            status: None,
+        };
+
+        match result {
+            Ok(moves) => {
+                let proto_moves = moves
+                    .into_iter()
+                    .map(|mv| Ok(ProtoIssueMove {
+                        id: mv.id,
+                        issue_id: mv.issue_id,
+                        from_column_id: mv.from_column,
+                        to_column_id: mv.to_column,
+                        moved_at: Some(to_timestamp(mv.moved_at)?),
+                    }))
+                    .collect::<Result<Vec<_>, StoredDateOutOfRange>>();
+                let (error, result) = match proto_moves {
+                    Ok(moves) => (None, Ok(Response::new(IssueMoveHistory { moves }))),
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        (Some(error), Err(status))
+                    }
+                };
+                let req = Request::new(IssueEvent {
+                    issue: Some(self.event_payload.apply(issue)),
+                    error,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_move_history_event, req).await;
+                });
+                result
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Issue not found"),
+                    ),
+                    _ => db_failure(&err),
                 };
                 let req = Request::new(IssueEvent {
-                    issue: Some(issue),
-                    error: Some(error)
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_issue_move_history_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn get_churn(
+        &self,
+        request: Request<ChurnRequest>,
+    ) -> Result<Response<BoardChurn>, Status> {
+        let data = request.get_ref();
+
+        let validated = match &data.since {
+            None => Err(String::from("since is required")),
+            Some(since) => from_timestamp(since)
+                .ok_or_else(|| String::from("since is not a valid timestamp"))
+                .and_then(|since| Page::with_default(data.limit, None, DEFAULT_CHURN_LIMIT)
+                    .map(|page| (since, page.limit))),
+        };
+
+        let (since, churn_limit) = match validated {
+            Ok(validated) => validated,
+            Err(message) => {
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(ChurnEvent {
+                    error: Some(error),
+                    board_id: data.board_id.clone(),
+                    since: data.since.clone(),
+                    limit: data.limit,
+                    issues_ids: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_churn_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(ChurnEvent {
+                    error: Some(error),
+                    board_id: data.board_id.clone(),
+                    since: data.since.clone(),
+                    limit: data.limit,
+                    issues_ids: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_churn_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match IssueMove::churn(&data.board_id, since, churn_limit, db_connection).await {
+            Ok(churn) => {
+                let req = Request::new(ChurnEvent {
+                    error: None,
+                    board_id: data.board_id.clone(),
+                    since: data.since.clone(),
+                    limit: data.limit,
+                    issues_ids: churn.iter().map(|(issue_id, _)| issue_id.clone()).collect(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_churn_event, req).await;
+                });
+                Ok(Response::new(BoardChurn {
+                    issues: churn
+                        .into_iter()
+                        .map(|(issue_id, moves)| ProtoIssueChurn { issue_id, moves })
+                        .collect(),
+                }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(ChurnEvent {
+                    error: Some(error),
+                    board_id: data.board_id.clone(),
+                    since: data.since.clone(),
+                    limit: data.limit,
+                    issues_ids: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, get_churn_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn count_issues_by_status(
+        &self,
+        request: Request<BoardId>,
+    ) -> Result<Response<IssuesCountByStatus>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssuesCountByStatusEvent {
+                    error: Some(error),
+                    board_id: data.board_id.clone(),
+                    counts: Vec::new(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_issues_by_status_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Issue::count_by_status(&data.board_id, db_connection).await {
+            Ok(counted) => {
+                let counts = status_counts(&counted);
+                let req = Request::new(IssuesCountByStatusEvent {
+                    error: None,
+                    board_id: data.board_id.clone(),
+                    counts: counts
+                        .iter()
+                        .map(|count| eventbus::IssueStatusCount { status: count.status, count: count.count })
+                        .collect(),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_issues_by_status_event, req).await;
+                });
+                Ok(Response::new(IssuesCountByStatus { counts }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(IssuesCountByStatusEvent {
+                    error: Some(error),
+                    board_id: data.board_id.clone(),
+                    counts: Vec::new(),
+                    correlation_id: correlation_id(),
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.get_issue_by_id_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_issues_by_status_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             }
         }
     }
@@ -126,40 +651,119 @@ impl IssuesService for IssuesController {
         request: Request<SearchIssuesParams>,
     ) -> Result<Response<Self::searchIssuesStream>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
-
-        let mut query = issues.into_boxed();
 
-        let issues_ids = match data.issues_ids.is_empty() {
-            false => Some(&data.issues_ids),
-            true => None,
+        let (limit, offset) = requested(data.page.as_ref(), data.limit, data.offset);
+        let mut search_params = eventbus::SearchIssuesParams {
+            issues_ids: data.issues_ids.clone(),
+            column_id: data.column_id.clone(),
+            epic_id: data.epic_id.clone(),
+            limit,
+            offset,
+            quick_query: data.quick_query.clone(),
+            epics_ids: data.epics_ids.clone(),
+            strict: data.strict,
+            empty_ids_match_none: data.empty_ids_match_none,
+            text_query: data.text_query.clone(),
+            after_id: data.after_id.clone(),
+            statuses: data.statuses.clone(),
         };
 
-        if let Some(is_ids) = issues_ids {
-            query = query.filter(id.eq_any(is_ids));
+        if let Err(message) = check_search_params(data, offset) {
+            let (error, status) = rejection(Code::InvalidArgument, message);
+            let req = Request::new(SearchIssuesEvent {
+                issues: Vec::new(),
+                error: Some(error),
+                search_params: Some(search_params),
+                correlation_id: correlation_id(),
+                next_cursor: None
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, search_issues_event, req).await;
+            });
+            return Err(status);
         }
 
-        if let Some(col_id) = &data.column_id {
-            query = query.filter(column_id.eq(col_id));
-        }
+        let page = match Page::from_params(limit, offset) {
+            Ok(page) => page,
+            Err(message) => {
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(SearchIssuesEvent {
+                    issues: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id(),
+                    next_cursor: None
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_issues_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        search_params.limit = page.effective_limit();
 
-        if let Some(col_id) = &data.epic_id {
-            query = query.filter(column_id.eq(col_id));
-        }
+        let permit = self.stream_limit.acquire()?;
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(SearchIssuesEvent {
+                    issues: Vec::new(),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id(),
+                    next_cursor: None
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_issues_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
-        if let Some(limit) = data.limit.clone() {
-            query = query.limit(limit.try_into().unwrap());
+        let mut query = filtered_issues(data);
+        let text_query = data.text_query.as_deref().and_then(TextQuery::new);
+
+        query = query.limit(page.limit);
+
+        if let Some(page_offset) = page.offset {
+            query = query.offset(page_offset);
         }
 
-        if let Some(offset) = data.offset.clone() {
-            query = query.offset(offset.try_into().unwrap());
+        if let Some(after) = &data.after_id {
+            query = query.filter(id.gt(after));
         }
 
-        let result: QueryResult<Vec<Issue>> = query
-            .load::<Issue>(&*db_connection);
-            
+        let result: QueryResult<Vec<(Issue, Option<String>)>> = match text_query {
+            Some(text_query) => {
+                let document = title.concat(" ").concat(description);
+                query
+                    .select((
+                        issues_schema::all_columns,
+                        text_query.rank(document),
+                        text_query.headline(document),
+                    ))
+                    .order((text_query.rank(document).desc(), id.asc()))
+                    .load::<(Issue, f32, String)>(&*db_connection)
+                    .map(|rows| rows.into_iter().map(|(iss, _, highlight)| (iss, Some(highlight))).collect())
+            }
+            _ => query
+                .order(id.asc())
+                .load::<Issue>(&*db_connection)
+                .map(|rows| rows.into_iter().map(|iss| (iss, None)).collect()),
+        };
+
         match result {
-            Ok(vec) => {
+            Ok(rows) => {
+                let (vec, highlights): (Vec<Issue>, Vec<Option<String>>) = rows.into_iter().unzip();
+                // A short page is the last one.
+                let next_cursor = match vec.len() as i64 == page.limit {
+                    true => vec.last().map(|issue| issue.id.clone()),
+                    false => None,
+                };
                 let iss = vec
                     .iter()
                     .map(|issue| eventbus::Issue {
@@ -168,49 +772,69 @@ impl IssuesService for IssuesController {
                         epic_id: Some(issue.epic_id.clone()),
                         title: Some(issue.title.clone()),
                         description: Some(issue.description.clone()),
+                        status: Some(issue.status.into()),
                     })
                     .collect::<Vec<eventbus::Issue>>();
-                let search_params = eventbus::SearchIssuesParams {
-                    issues_ids: data.issues_ids.clone(),
-                    column_id: data.column_id.clone(),
-                    epic_id: data.epic_id.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
+
+                let converted = vec
+                    .iter()
+                    .zip(highlights)
+                    .map(|(issue, highlight)| Ok(ProtoIssue { highlight, ..to_proto_issue(issue)? }))
+                    .collect::<Result<Vec<ProtoIssue>, StoredDateOutOfRange>>();
+                let proto_issues = match converted {
+                    Ok(proto_issues) => proto_issues,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(SearchIssuesEvent {
+                            issues: self.event_payload.apply_all(iss),
+                            error: Some(error),
+                            search_params: Some(search_params),
+                            correlation_id: correlation_id(),
+                            next_cursor: None
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, search_issues_event, req).await;
+                        });
+                        return Err(status);
+                    }
                 };
         
                 let req = Request::new(SearchIssuesEvent {
-                    issues: iss,
+                    issues: self.event_payload.apply_all(iss),
                     error: None,
-                    search_params: Some(search_params)
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id(),
+                    next_cursor: next_cursor.clone()
                 });
-                let mut service = self.eventbus_service_client.clone();
-        
-                let proto_issues: Vec<ProtoIssue> = vec.iter().map(|issue| ProtoIssue {
-                    id: issue.id.clone(),
-                    column_id: issue.column_id.clone(),
-                    epic_id: issue.epic_id.clone(),
-                    title: issue.title.clone(),
-                    description: issue.description.clone(),
-                }).collect();
+                let service = self.eventbus_service_client.clone();
         
                 let mut stream = tokio_stream::iter(proto_issues);
                 let (sender, receiver) = mpsc::channel(1);
         
-                tokio::spawn(async move {
+                spawn_tracked(async move {
+                    let _permit = permit;
+                    let mut delivered = true;
                     while let Some(issue) = stream.next().await {
-                        match sender.send(Result::<ProtoIssue, Status>::Ok(issue)).await {
-                            Ok(_) => {},
-                            Err(_err) => break
+                        if sender.send(Result::<ProtoIssue, Status>::Ok(issue)).await.is_err() {
+                            delivered = false;
+                            break;
                         }
                     }
-                    service.search_issues_event(req).await;
+                    if delivered {
+                        send_event!(service, search_issues_event, req).await;
+                    }
                 });
         
                 let output_stream = ReceiverStream::new(receiver);
         
-                Ok(Response::new(
+                let mut response = Response::new(
                     Box::pin(output_stream) as Self::searchIssuesStream
-                ))
+                );
+                if let Some(cursor) = next_cursor.and_then(|cursor| MetadataValue::try_from(cursor).ok()) {
+                    response.metadata_mut().insert(NEXT_CURSOR_HEADER, cursor);
+                }
+                Ok(response)
             }
             Err(err) => {
                 let iss = data.issues_ids
@@ -221,30 +845,130 @@ impl IssuesService for IssuesController {
                         epic_id: None,
                         title: None,
                         description: None,
+                        status: None,
                     })
                     .collect::<Vec<eventbus::Issue>>();
-                let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
-                };
-                let search_params = eventbus::SearchIssuesParams {
-                    issues_ids: data.issues_ids.clone(),
-                    column_id: data.column_id.clone(),
-                    epic_id: data.epic_id.clone(),
-                    limit: data.limit.clone(),
-                    offset: data.offset.clone(),
-                };
+                let (error, status) = db_failure(&err);
         
                 let req = Request::new(SearchIssuesEvent {
-                    issues: iss,
+                    issues: self.event_payload.apply_all(iss),
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    correlation_id: correlation_id(),
+                    next_cursor: None
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, search_issues_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    type searchIssuesInChunksStream = Pin<Box<dyn Stream<Item = Result<IssuesChunk, Status>> + Send>>;
+
+    async fn search_issues_in_chunks(
+        &self,
+        request: Request<ChunkedSearchIssuesParams>,
+    ) -> Result<Response<Self::searchIssuesInChunksStream>, Status> {
+        let data = request.into_inner();
+        let size = chunk_size(data.chunk_size).map_err(Status::invalid_argument)?;
+        let rows = self.search_issues(Request::new(data.params.unwrap_or_default())).await?;
+        let metadata = rows.metadata().clone();
+
+        let mut response = Response::new(
+            Box::pin(chunked(rows.into_inner(), size, |rows| IssuesChunk { issues: rows })) as Self::searchIssuesInChunksStream
+        );
+        *response.metadata_mut() = metadata;
+        Ok(response)
+    }
+
+    async fn count_issues(
+        &self,
+        request: Request<SearchIssuesParams>,
+    ) -> Result<Response<IssuesCount>, Status> {
+        let data = request.get_ref();
+        let search_params = eventbus::SearchIssuesParams {
+            issues_ids: data.issues_ids.clone(),
+            column_id: data.column_id.clone(),
+            epic_id: data.epic_id.clone(),
+            limit: None,
+            offset: None,
+            quick_query: data.quick_query.clone(),
+            epics_ids: data.epics_ids.clone(),
+            strict: data.strict,
+            empty_ids_match_none: data.empty_ids_match_none,
+            text_query: data.text_query.clone(),
+            after_id: None,
+            statuses: data.statuses.clone(),
+        };
+
+        let (_, offset) = requested(data.page.as_ref(), data.limit, data.offset);
+        if let Err(message) = check_search_params(data, offset) {
+            let (error, status) = rejection(Code::InvalidArgument, message);
+            let req = Request::new(IssuesCountEvent {
+                error: Some(error),
+                search_params: Some(search_params),
+                count: None,
+                correlation_id: correlation_id(),
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, count_issues_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssuesCountEvent {
                     error: Some(error),
-                    search_params: Some(search_params)
+                    search_params: Some(search_params),
+                    count: None,
+                    correlation_id: correlation_id(),
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.search_issues_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_issues_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                return Err(status);
+            }
+        };
+
+        let result: QueryResult<i64> = filtered_issues(data)
+            .count()
+            .get_result(&*db_connection);
+
+        match result {
+            Ok(total) => {
+                let req = Request::new(IssuesCountEvent {
+                    error: None,
+                    search_params: Some(search_params),
+                    count: Some(total),
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_issues_event, req).await;
+                });
+                Ok(Response::new(IssuesCount { count: total }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(IssuesCountEvent {
+                    error: Some(error),
+                    search_params: Some(search_params),
+                    count: None,
+                    correlation_id: correlation_id(),
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, count_issues_event, req).await;
+                });
+                Err(status)
             }
         }
     }
@@ -254,17 +978,82 @@ impl IssuesService for IssuesController {
         request: Request<CreateIssueRequest>,
     ) -> Result<Response<ProtoIssue>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+
+        if let Err(message) = check_not_blank(Some(&data.title), "issue title required")
+            .and_then(|_| self.text_limits.check_title("title", Some(data.title.trim())))
+            .and_then(|_| self.text_limits.check_description("description", Some(&data.description))) {
+            let issue = eventbus::Issue {
+                id: None,
+                column_id: data.column_id.clone(),
+                epic_id: Some(data.epic_id.clone()),
+                title: Some(data.title.clone()),
+                description: Some(data.description.clone()),
+                status: None,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(IssueEvent {
+                issue: Some(self.event_payload.apply(issue)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_issue_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        if data.column_id.is_none() && data.board_id.is_none() {
+            let issue = eventbus::Issue {
+                id: None,
+                column_id: None,
+                epic_id: Some(data.epic_id.clone()),
+                title: Some(data.title.clone()),
+                description: Some(data.description.clone()),
+                status: None,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, "Either column_id or board_id is required");
+            let req = Request::new(IssueEvent {
+                issue: Some(self.event_payload.apply(issue)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_issue_event, req).await;
+            });
+            return Err(status);
+        }
 
         let new_issue = NewIssue {
-            id: &uuid::Uuid::new_v4().to_string(),
-            column_id: &data.column_id,
+            id: &new_id(),
+            column_id: data.column_id.as_deref(),
             epic_id: &data.epic_id,
-            title: &data.title,
+            title: data.title.trim(),
             description: &data.description,
         };
 
-        match Issue::create(new_issue, db_connection).await {
+        match Issue::create(new_issue, data.board_id.as_deref(), db_connection).await {
             Ok(iss) => {
                 let issue = eventbus::Issue {
                     id: Some(iss.id.clone()),
@@ -272,90 +1061,256 @@ impl IssuesService for IssuesController {
                     epic_id: Some(iss.epic_id.clone()),
                     title: Some(iss.title.clone()),
                     description: Some(iss.description.clone()),
+                    status: Some(iss.status.into()),
                 };
                 let req = Request::new(IssueEvent {
-                    issue: Some(issue),
-                    error: None
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
                 
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_issue_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_issue_event, req).await;
                 });
 
-                Ok(Response::new(ProtoIssue {
-                    id: iss.id.clone(),
-                    column_id: iss.column_id.clone(),
-                    epic_id: iss.epic_id.clone(),
-                    title: iss.title.clone(),
-                    description: iss.description.clone(),
-                }))
+                Ok(Response::new(to_proto_issue(&iss)?))
             },
             Err(err) => {
                 let issue = eventbus::Issue {
                     id: None,
-                    column_id: Some(data.column_id.clone()),
+                    column_id: data.column_id.clone(),
                     epic_id: Some(data.epic_id.clone()),
                     title: Some(data.title.clone()),
                     description: Some(data.description.clone()),
+                    status: None,
+                };
+                // Only the column lookup for a board can come up empty.
+                let (code, message, status) = match err {
+                    NotFound if data.column_id.is_none() => (
+                        Code::FailedPrecondition,
+                        String::from("Board has no columns"),
+                        Status::failed_precondition("Board has no columns"),
+                    ),
+                    err => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
                 };
                 let error = eventbus::Error {
-                    code: Code::Unavailable.into(),
-                    message: err.to_string()
+                    code: code.into(),
+                    message
                 };
                 let req = Request::new(IssueEvent {
-                    issue: Some(issue),
-                    error: Some(error)
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.create_issue_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_issue_event, req).await;
                 });
-                Err(Status::unavailable("Database is unavailable"))
+                Err(status)
             },
         }
     }
 
-    async fn update_issue(
+    async fn create_issues_batch(
         &self,
-        request: Request<UpdateIssueRequest>,
-    ) -> Result<Response<ProtoIssue>, Status> {
+        request: Request<CreateIssuesBatchRequest>,
+    ) -> Result<Response<CreatedIssues>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
 
-        let change_set = IssueChangeSet {
-            column_id: data.column_id.clone(),
-            epic_id: data.epic_id.clone(),
-            title: data.title.clone(),
-            description: data.description.clone(),
+        let invalid = if data.issues.is_empty() {
+            Some(String::from("issues required"))
+        } else if data.issues.len() as i64 > max_page_size() {
+            Some(format!("At most {} issues per batch", max_page_size()))
+        } else {
+            data.issues.iter().enumerate().find_map(|(index, item)| {
+                self.check_new_issue(item).err().map(|message| format!("issues[{}]: {}", index, message))
+            })
         };
-        
-        match Issue::update(&data.issue_id, change_set, db_connection).await {
-            Ok(iss) => {
-                let issue = eventbus::Issue {
-                    id: Some(iss.id.clone()),
-                    column_id: Some(iss.column_id.clone()),
-                    epic_id: Some(iss.epic_id.clone()),
-                    title: Some(iss.title.clone()),
-                    description: Some(iss.description.clone()),
-                };
-                let req = Request::new(IssueEvent {
-                    issue: Some(issue),
-                    error: None
+        if let Some(message) = invalid {
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(IssueEvent {
+                issue: None,
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, create_issue_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.update_issue_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_issue_event, req).await;
                 });
-        
-                Ok(Response::new(ProtoIssue {
-                    id: iss.id.clone(),
-                    column_id: iss.column_id.clone(),
-                    epic_id: iss.epic_id.clone(),
-                    title: iss.title.clone(),
-                    description: iss.description.clone(),
+                return Err(status);
+            }
+        };
+
+        let ids: Vec<String> = data.issues.iter().map(|_| new_id()).collect();
+        let new_issues = data.issues
+            .iter()
+            .zip(&ids)
+            .map(|(item, issue_id)| (
+                NewIssue {
+                    id: issue_id,
+                    column_id: item.column_id.as_deref(),
+                    epic_id: &item.epic_id,
+                    title: item.title.trim(),
+                    description: &item.description,
+                },
+                item.board_id.as_deref(),
+            ))
+            .collect();
+
+        match Issue::create_all(new_issues, db_connection).await {
+            Ok(created) => {
+                // One event per issue, like the same calls to createIssue.
+                for iss in &created {
+                    let issue = eventbus::Issue {
+                        id: Some(iss.id.clone()),
+                        column_id: Some(iss.column_id.clone()),
+                        epic_id: Some(iss.epic_id.clone()),
+                        title: Some(iss.title.clone()),
+                        description: Some(iss.description.clone()),
+                        status: Some(iss.status.into()),
+                    };
+                    let req = Request::new(IssueEvent {
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: None,
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, create_issue_event, req).await;
+                    });
+                }
+
+                Ok(Response::new(CreatedIssues {
+                    issues: created.iter().map(to_proto_issue).collect::<Result<_, _>>()?,
                 }))
             },
+            Err(err) => {
+                // Only the column lookup for a board can come up empty.
+                let (code, message, status) = match err {
+                    NotFound => (
+                        Code::FailedPrecondition,
+                        String::from("Board has no columns"),
+                        Status::failed_precondition("Board has no columns"),
+                    ),
+                    err => {
+                        let (error, status) = db_failure(&err);
+                        (Code::from_i32(error.code), error.message, status)
+                    }
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, create_issue_event, req).await;
+                });
+                Err(status)
+            },
+        }
+    }
+
+    async fn update_issue(
+        &self,
+        request: Request<UpdateIssueRequest>,
+    ) -> Result<Response<ProtoIssue>, Status> {
+        let data = request.get_ref();
+
+        if let Err(message) = check_not_blank(data.title.as_deref(), "issue title required")
+            .and_then(|_| self.text_limits.check_title("title", data.title.as_deref().map(str::trim)))
+            .and_then(|_| self.text_limits.check_description("description", data.description.as_deref()))
+            .and_then(|_| match data.status.is_some_and(|value| IssueStatus::from_i32(value).is_none()) {
+                true => Err(String::from("Unknown issue status")),
+                false => Ok(()),
+            }) {
+            let issue = eventbus::Issue {
+                id: Some(data.issue_id.clone()),
+                column_id: data.column_id.clone(),
+                epic_id: data.epic_id.clone(),
+                title: data.title.clone(),
+                description: data.description.clone(),
+                status: data.status,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(IssueEvent {
+                issue: Some(self.event_payload.apply(issue)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, update_issue_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let change_set = IssueChangeSet {
+            column_id: data.column_id.clone(),
+            epic_id: data.epic_id.clone(),
+            title: data.title.as_deref().map(|text| text.trim().to_string()),
+            description: data.description.clone(),
+            status: data.status.and_then(|value| i16::try_from(value).ok()),
+        };
+        
+        // The success event goes through the outbox, committed with the
+        // update, so a crash right after the commit cannot lose it.
+        let outbox_event = self.eventbus_service_client.as_ref()
+            .map(|_| update_outbox_event(self.event_payload));
+        
+        match Issue::update(&data.issue_id, change_set, outbox_event, db_connection).await {
+            Ok((iss, _)) => {
+                Ok(Response::new(to_proto_issue(&iss)?))
+            },
             Err(err) => {
                 if err == NotFound {
                     let issue = eventbus::Issue {
@@ -364,18 +1319,21 @@ impl IssuesService for IssuesController {
                         epic_id: data.epic_id.clone(),
                         title: data.title.clone(),
                         description: data.description.clone(),
+                        status: data.status,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: err.to_string()
                     };
                     let req = Request::new(IssueEvent {
-                        issue: Some(issue),
-                        error: Some(error)
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.update_issue_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_issue_event, req).await;
                     });
                     Err(Status::not_found("Issue not found"))
                 } else {
@@ -385,31 +1343,228 @@ impl IssuesService for IssuesController {
                         epic_id: data.epic_id.clone(),
                         title: data.title.clone(),
                         description: data.description.clone(),
+                        status: data.status,
                     };
-                    let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
-                        message: err.to_string()
-                    };
+                    let (error, status) = db_failure(&err);
                     let req = Request::new(IssueEvent {
-                        issue: Some(issue),
-                        error: Some(error)
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.update_issue_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_issue_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
+                    Err(status)
                 }
             },
         }
     }
 
+    async fn move_issue(
+        &self,
+        request: Request<MoveIssueRequest>,
+    ) -> Result<Response<ProtoIssue>, Status> {
+        let data = request.get_ref();
+
+        if data.target_column_id.is_empty() {
+            let issue = eventbus::Issue {
+                id: Some(data.issue_id.clone()),
+                column_id: None,
+                epic_id: None,
+                title: None,
+                description: None,
+                status: None,
+            };
+            let (error, status) = rejection(Code::InvalidArgument, "targetColumnId required");
+            let req = Request::new(IssueEvent {
+                issue: Some(self.event_payload.apply(issue)),
+                error: Some(error),
+                changed_fields: Vec::new(),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, update_issue_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let outbox_event = self.eventbus_service_client.as_ref()
+            .map(|_| update_outbox_event(self.event_payload));
+        let result = Issue::move_to(&data.issue_id, &data.target_column_id, outbox_event, db_connection).await
+            .map_err(|err| match err {
+                MoveIssueError::ColumnNotFound => (
+                    Code::NotFound,
+                    String::from("Column not found"),
+                    Status::not_found("Column not found"),
+                ),
+                MoveIssueError::Db(NotFound) => (Code::NotFound, NotFound.to_string(), Status::not_found("Issue not found")),
+                MoveIssueError::Db(err) => {
+                    let (error, status) = db_failure(&err);
+                    (Code::from_i32(error.code), error.message, status)
+                }
+            });
+
+        match result {
+            Ok((iss, _)) => Ok(Response::new(to_proto_issue(&iss)?)),
+            Err((code, message, status)) => {
+                let issue = eventbus::Issue {
+                    id: Some(data.issue_id.clone()),
+                    column_id: Some(data.target_column_id.clone()),
+                    epic_id: None,
+                    title: None,
+                    description: None,
+                    status: None,
+                };
+                let error = eventbus::Error {
+                    code: code.into(),
+                    message
+                };
+                let req = Request::new(IssueEvent {
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn transition_issues(
+        &self,
+        request: Request<TransitionIssuesRequest>,
+    ) -> Result<Response<IssueTransitions>, Status> {
+        let data = request.get_ref();
+
+        let target = match check_transition(data) {
+            Ok(target) => target,
+            Err(message) => {
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let force = data.force;
+        let allowed = move |current: i16| match IssueStatus::from_i32(current.into()) {
+            Some(from) => transition_allowed(from, target, force),
+            None => true,
+        };
+        // Like updateIssue, the success events are committed with the
+        // transitions through the outbox.
+        let outbox_event = self.eventbus_service_client.as_ref()
+            .map(|_| update_outbox_event(self.event_payload));
+
+        match Issue::transition_all(&data.issues_ids, target as i16, allowed, outbox_event, db_connection).await {
+            Ok(outcomes) => {
+                let mut transitions = Vec::with_capacity(outcomes.len());
+                for (issue_id, outcome) in data.issues_ids.iter().zip(outcomes) {
+                    let (result, issue) = match outcome {
+                        TransitionOutcome::Transitioned(iss) => (TransitionResult::Transitioned, Some(iss)),
+                        TransitionOutcome::Unchanged(iss) => (TransitionResult::Unchanged, Some(iss)),
+                        TransitionOutcome::NotAllowed(iss) => (TransitionResult::NotAllowed, Some(iss)),
+                        TransitionOutcome::NotFound => (TransitionResult::NotFound, None),
+                    };
+                    transitions.push(IssueTransition {
+                        issue_id: issue_id.clone(),
+                        result: result.into(),
+                        issue: issue.as_ref().map(to_proto_issue).transpose()?,
+                    });
+                }
+
+                Ok(Response::new(IssueTransitions { transitions }))
+            },
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                Err(status)
+            },
+        }
+    }
+
     async fn delete_issue(
         &self,
         request: Request<IssueId>,
     ) -> Result<Response<ProtoIssue>, Status> {
         let data = request.get_ref();
-        let db_connection = self.pool.get().expect("Db error");
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
 
         match Issue::delete(&data.issue_id, db_connection).await {
             Ok(iss) => {
@@ -419,23 +1574,20 @@ impl IssuesService for IssuesController {
                     epic_id: Some(iss.epic_id.clone()),
                     title: Some(iss.title.clone()),
                     description: Some(iss.description.clone()),
+                    status: Some(iss.status.into()),
                 };
                 let req = Request::new(IssueEvent {
-                    issue: Some(issue),
-                    error: None
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
                 });
-                let mut service = self.eventbus_service_client.clone();
-                tokio::spawn(async move {
-                    service.delete_issue_event(req).await;
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, delete_issue_event, req).await;
                 });
         
-                Ok(Response::new(ProtoIssue {
-                    id: iss.id.clone(),
-                    column_id: iss.column_id.clone(),
-                    epic_id: iss.epic_id.clone(),
-                    title: iss.title.clone(),
-                    description: iss.description.clone(),
-                }))
+                Ok(Response::new(to_proto_issue(&iss)?))
             }
             Err(err) => {
                 if err == NotFound {
@@ -445,18 +1597,21 @@ impl IssuesService for IssuesController {
                         epic_id: None,
                         title: None,
                         description: None,
+                        status: None,
                     };
                     let error = eventbus::Error {
                         code: Code::NotFound.into(),
                         message: err.to_string()
                     };
                     let req = Request::new(IssueEvent {
-                        issue: Some(issue),
-                        error: Some(error)
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_issue_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_issue_event, req).await;
                     });
                     Err(Status::not_found("Issue not found"))
                 } else {
@@ -466,22 +1621,633 @@ impl IssuesService for IssuesController {
                         epic_id: None,
                         title: None,
                         description: None,
+                        status: None,
+                    };
+                    let (error, status) = db_failure(&err);
+                    let req = Request::new(IssueEvent {
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, delete_issue_event, req).await;
+                    });
+                    Err(status)
+                }
+            }
+        }
+    }
+
+    async fn touch_issue(
+        &self,
+        request: Request<IssueId>,
+    ) -> Result<Response<ProtoIssue>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueEvent {
+                    issue: None,
+                    error: Some(error),
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match Issue::touch(&data.issue_id, db_connection).await {
+            Ok(iss) => {
+                let issue = eventbus::Issue {
+                    id: Some(iss.id.clone()),
+                    column_id: Some(iss.column_id.clone()),
+                    epic_id: Some(iss.epic_id.clone()),
+                    title: Some(iss.title.clone()),
+                    description: Some(iss.description.clone()),
+                    status: Some(iss.status.into()),
+                };
+                let req = Request::new(IssueEvent {
+                    issue: Some(self.event_payload.apply(issue)),
+                    error: None,
+                    changed_fields: Vec::new(),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, update_issue_event, req).await;
+                });
+        
+                Ok(Response::new(to_proto_issue(&iss)?))
+            }
+            Err(err) => {
+                if err == NotFound {
+                    let issue = eventbus::Issue {
+                        id: Some(data.issue_id.clone()),
+                        column_id: None,
+                        epic_id: None,
+                        title: None,
+                        description: None,
+                        status: None,
                     };
                     let error = eventbus::Error {
-                        code: Code::Unavailable.into(),
+                        code: Code::NotFound.into(),
                         message: err.to_string()
                     };
                     let req = Request::new(IssueEvent {
-                        issue: Some(issue),
-                        error: Some(error)
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
                     });
-                    let mut service = self.eventbus_service_client.clone();
-                    tokio::spawn(async move {
-                        service.delete_issue_event(req).await;
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_issue_event, req).await;
                     });
-                    Err(Status::unavailable("Database is unavailable"))
+                    Err(Status::not_found("Issue not found"))
+                } else {
+                    let issue = eventbus::Issue {
+                        id: Some(data.issue_id.clone()),
+                        column_id: None,
+                        epic_id: None,
+                        title: None,
+                        description: None,
+                        status: None,
+                    };
+                    let (error, status) = db_failure(&err);
+                    let req = Request::new(IssueEvent {
+                        issue: Some(self.event_payload.apply(issue)),
+                        error: Some(error),
+                        changed_fields: Vec::new(),
+                        correlation_id: correlation_id()
+                    });
+                    let service = self.eventbus_service_client.clone();
+                    spawn_tracked(async move {
+                        send_event!(service, update_issue_event, req).await;
+                    });
+                    Err(status)
                 }
             }
         }
     }
+    async fn add_issue_link(
+        &self,
+        request: Request<AddIssueLinkRequest>,
+    ) -> Result<Response<ProtoIssueLink>, Status> {
+        let data = request.get_ref();
+
+        let requested_link = || eventbus::IssueLink {
+            id: None,
+            issue_id: Some(data.issue_id.clone()),
+            url: Some(data.url.clone()),
+            title: data.title.clone(),
+            kind: Some(data.kind),
+        };
+
+        let invalid = check_url("url", &data.url)
+            .and_then(|_| self.text_limits.check_title("title", data.title.as_deref()))
+            .and_then(|_| match IssueLinkKind::from_i32(data.kind) {
+                Some(_) => Ok(()),
+                None => Err(String::from("Unknown link kind")),
+            });
+        if let Err(message) = invalid {
+            let (error, status) = rejection(Code::InvalidArgument, &message);
+            let req = Request::new(IssueLinkEvent {
+                link: Some(self.event_payload.apply(requested_link())),
+                error: Some(error),
+                correlation_id: correlation_id()
+            });
+            let service = self.eventbus_service_client.clone();
+            spawn_tracked(async move {
+                send_event!(service, add_issue_link_event, req).await;
+            });
+            return Err(status);
+        }
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueLinkEvent {
+                    link: Some(self.event_payload.apply(requested_link())),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, add_issue_link_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let new_link = NewIssueLink {
+            id: &new_id(),
+            issue_id: &data.issue_id,
+            url: &data.url,
+            title: data.title.as_deref(),
+            kind: data.kind,
+        };
+
+        match IssueLink::create(new_link, db_connection).await {
+            Ok(lnk) => {
+                let req = Request::new(IssueLinkEvent {
+                    link: Some(self.event_payload.apply(to_event_issue_link(&lnk))),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, add_issue_link_event, req).await;
+                });
+
+                Ok(Response::new(to_proto_issue_link(&lnk)?))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Issue not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let req = Request::new(IssueLinkEvent {
+                    link: Some(self.event_payload.apply(requested_link())),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, add_issue_link_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn remove_issue_link(
+        &self,
+        request: Request<IssueLinkId>,
+    ) -> Result<Response<ProtoIssueLink>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueLinkEvent {
+                    link: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, remove_issue_link_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match IssueLink::delete(&data.link_id, db_connection).await {
+            Ok(lnk) => {
+                let req = Request::new(IssueLinkEvent {
+                    link: Some(self.event_payload.apply(to_event_issue_link(&lnk))),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, remove_issue_link_event, req).await;
+                });
+
+                Ok(Response::new(to_proto_issue_link(&lnk)?))
+            }
+            Err(err) => {
+                let (error, status) = match err {
+                    NotFound => (
+                        eventbus::Error {
+                            code: Code::NotFound.into(),
+                            message: err.to_string(),
+                        },
+                        Status::not_found("Issue link not found"),
+                    ),
+                    _ => db_failure(&err),
+                };
+                let link = eventbus::IssueLink {
+                    id: Some(data.link_id.clone()),
+                    issue_id: None,
+                    url: None,
+                    title: None,
+                    kind: None,
+                };
+                let req = Request::new(IssueLinkEvent {
+                    link: Some(self.event_payload.apply(link)),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, remove_issue_link_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+
+    async fn list_issue_links(
+        &self,
+        request: Request<IssueId>,
+    ) -> Result<Response<ProtoIssueLinks>, Status> {
+        let data = request.get_ref();
+        let db_connection = match pooled_connection(&self.read_pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(IssueLinksEvent {
+                    issue_id: data.issue_id.clone(),
+                    links: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, list_issue_links_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        match IssueLink::for_issue(&data.issue_id, db_connection).await {
+            Ok(lnks) => {
+                let (error, result) = match lnks.iter().map(to_proto_issue_link).collect() {
+                    Ok(links) => (None, Ok(Response::new(ProtoIssueLinks { links }))),
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        (Some(error), Err(status))
+                    }
+                };
+                let req = Request::new(IssueLinksEvent {
+                    issue_id: data.issue_id.clone(),
+                    links: self.event_payload.apply_all(lnks.iter().map(to_event_issue_link).collect()),
+                    error,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, list_issue_links_event, req).await;
+                });
+
+                result
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(IssueLinksEvent {
+                    issue_id: data.issue_id.clone(),
+                    links: Vec::new(),
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, list_issue_links_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+}
+
+impl IssuesController {
+    /// The checks createIssue runs before touching the database.
+    fn check_new_issue(&self, data: &CreateIssueRequest) -> Result<(), String> {
+        check_not_blank(Some(&data.title), "issue title required")
+            .and_then(|_| self.text_limits.check_title("title", Some(data.title.trim())))
+            .and_then(|_| self.text_limits.check_description("description", Some(&data.description)))?;
+        match data.column_id.is_none() && data.board_id.is_none() {
+            true => Err(String::from("Either column_id or board_id is required")),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Builds the `update_issue_event` outbox row for an updated issue.
+fn update_outbox_event(event_payload: EventPayload) -> impl Fn(&Issue, &[&'static str]) -> NewOutboxEvent + Send {
+    move |iss, changed| {
+        let issue = eventbus::Issue {
+            id: Some(iss.id.clone()),
+            column_id: Some(iss.column_id.clone()),
+            epic_id: Some(iss.epic_id.clone()),
+            title: Some(iss.title.clone()),
+            description: Some(iss.description.clone()),
+            status: Some(iss.status.into()),
+        };
+        let event = IssueEvent {
+            issue: Some(event_payload.apply(issue)),
+            error: None,
+            changed_fields: changed.iter().map(|field| field.to_string()).collect(),
+            correlation_id: correlation_id()
+        };
+        NewOutboxEvent {
+            method: String::from("update_issue_event"),
+            payload: event.encode_to_vec(),
+        }
+    }
+}
+
+/// Checks a transitionIssues request and returns its target status.
+fn check_transition(data: &TransitionIssuesRequest) -> Result<IssueStatus, String> {
+    if data.issues_ids.is_empty() {
+        return Err(String::from("issuesIds required"));
+    }
+    if data.issues_ids.len() as i64 > max_page_size() {
+        return Err(format!("At most {} issues per transition", max_page_size()));
+    }
+    let distinct: HashSet<&String> = data.issues_ids.iter().collect();
+    if distinct.len() != data.issues_ids.len() {
+        return Err(String::from("issuesIds must be distinct"));
+    }
+    IssueStatus::from_i32(data.target_status).ok_or_else(|| String::from("Unknown issue status"))
+}
+
+/// Closed issues stay closed unless the transition is forced; every other
+/// move between statuses is allowed.
+fn transition_allowed(from: IssueStatus, to: IssueStatus, force: bool) -> bool {
+    force || from != IssueStatus::Closed || to == IssueStatus::Closed
+}
+
+/// Shared by searchIssues and countIssues so the count always matches what
+/// a search with the same filters returns. The statuses must have passed
+/// `check_statuses`.
+fn filtered_issues(data: &SearchIssuesParams) -> issues_schema::BoxedQuery<'_, Pg> {
+    let mut query = issues.into_boxed();
+
+    let issues_ids = match (data.issues_ids.is_empty(), data.empty_ids_match_none.unwrap_or(false)) {
+        (true, false) => None,
+        _ => Some(&data.issues_ids),
+    };
+
+    if let Some(is_ids) = issues_ids {
+        query = query.filter(id.eq_any(is_ids));
+    }
+
+    if let Some(col_id) = &data.column_id {
+        query = query.filter(column_id.eq(col_id));
+    }
+
+    if let Some(ep_id) = &data.epic_id {
+        query = query.filter(epic_id.eq(ep_id));
+    }
+
+    let epics_ids = match data.epics_ids.is_empty() {
+        false => Some(&data.epics_ids),
+        true => None,
+    };
+
+    if let Some(ep_ids) = epics_ids {
+        query = query.filter(epic_id.eq_any(ep_ids));
+    }
+
+    if let Some(term) = &data.quick_query {
+        let pattern = format!("%{}%", escape_like(term));
+        query = query.filter(
+            id.eq(term)
+                .or(title.ilike(pattern.clone()))
+                .or(description.ilike(pattern))
+        );
+    }
+
+    if !data.statuses.is_empty() {
+        let statuses: Vec<i16> = data.statuses
+            .iter()
+            .filter_map(|value| IssueStatus::from_i32(*value))
+            .map(|value| value as i16)
+            .collect();
+        query = query.filter(issues_schema::status.eq_any(statuses));
+    }
+
+    if let Some(terms) = &data.text_query {
+        query = match TextQuery::new(terms) {
+            Some(text_query) => query.filter(text_query.matches(title.concat(" ").concat(description))),
+            None => {
+                let pattern = format!("%{}%", escape_like(terms));
+                query.filter(title.ilike(pattern.clone()).or(description.ilike(pattern)))
+            }
+        };
+    }
+
+    query
+}
+
+/// Every status, in enum order.
+const ALL_STATUSES: [IssueStatus; 4] = [
+    IssueStatus::Open,
+    IssueStatus::InProgress,
+    IssueStatus::Done,
+    IssueStatus::Closed,
+];
+
+/// One count per status from the per-status tallies of
+/// `CountIssuesByStatus`, zero for the statuses that had none.
+fn status_counts(counted: &[(i16, i64)]) -> Vec<IssueStatusCount> {
+    ALL_STATUSES
+        .iter()
+        .map(|status| IssueStatusCount {
+            status: *status as i32,
+            count: counted
+                .iter()
+                .find(|(counted_status, _)| i32::from(*counted_status) == *status as i32)
+                .map_or(0, |(_, count)| *count),
+        })
+        .collect()
+}
+
+/// The checks searchIssues and countIssues make before querying, so that
+/// a count is only returned for a search that would be accepted. `offset`
+/// is the one `requested` resolved.
+fn check_search_params(data: &SearchIssuesParams, offset: Option<i32>) -> Result<(), &'static str> {
+    let combines_ids = !data.issues_ids.is_empty() && (
+        data.column_id.is_some()
+            || data.epic_id.is_some()
+            || !data.epics_ids.is_empty()
+            || data.quick_query.is_some()
+            || data.text_query.is_some()
+            || !data.statuses.is_empty()
+    );
+    match (&data.after_id, offset, &data.text_query) {
+        (Some(_), Some(_), _) => return Err("afterId cannot be combined with offset"),
+        (Some(_), None, Some(_)) => return Err("afterId cannot be combined with textQuery"),
+        _ => {}
+    }
+    if data.strict.unwrap_or(false) && combines_ids {
+        return Err("issuesIds cannot be combined with other filters in strict mode");
+    }
+    check_statuses(&data.statuses)
+}
+
+/// Rejects a status filter with a value `IssueStatus` does not have,
+/// rather than letting it match nothing.
+fn check_statuses(statuses: &[i32]) -> Result<(), &'static str> {
+    match statuses.iter().all(|value| IssueStatus::from_i32(*value).is_some()) {
+        true => Ok(()),
+        false => Err("Unknown issue status"),
+    }
+}
+
+fn to_proto_issue_link(link: &IssueLink) -> Result<ProtoIssueLink, StoredDateOutOfRange> {
+    Ok(ProtoIssueLink {
+        id: link.id.clone(),
+        issue_id: link.issue_id.clone(),
+        url: link.url.clone(),
+        title: link.title.clone(),
+        kind: link.kind,
+        created_at: Some(to_timestamp(link.created_at)?),
+    })
+}
+
+/// The API issue without its links, with the dates checked like
+/// `to_proto_epic` does.
+pub(crate) fn to_proto_issue(issue: &Issue) -> Result<ProtoIssue, StoredDateOutOfRange> {
+    Ok(ProtoIssue {
+        id: issue.id.clone(),
+        column_id: issue.column_id.clone(),
+        epic_id: issue.epic_id.clone(),
+        title: issue.title.clone(),
+        description: issue.description.clone(),
+        updated_at: Some(to_timestamp(issue.updated_at)?),
+        created_at: Some(to_timestamp(issue.created_at)?),
+        links: Vec::new(),
+        highlight: None,
+        status: issue.status.into(),
+    })
+}
+
+fn to_event_issue_link(link: &IssueLink) -> eventbus::IssueLink {
+    eventbus::IssueLink {
+        id: Some(link.id.clone()),
+        issue_id: Some(link.issue_id.clone()),
+        url: Some(link.url.clone()),
+        title: link.title.clone(),
+        kind: Some(link.kind),
+    }
+}
+
+/// Escapes LIKE wildcards so a search term is matched literally.
+pub fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition_request(issues_ids: &[&str], target_status: i32) -> TransitionIssuesRequest {
+        TransitionIssuesRequest {
+            issues_ids: issues_ids.iter().map(|issue_id| issue_id.to_string()).collect(),
+            target_status,
+            force: false,
+        }
+    }
+
+    #[test]
+    fn closed_issues_reopen_only_when_forced() {
+        assert!(!transition_allowed(IssueStatus::Closed, IssueStatus::InProgress, false));
+        assert!(transition_allowed(IssueStatus::Closed, IssueStatus::InProgress, true));
+        assert!(transition_allowed(IssueStatus::Closed, IssueStatus::Closed, false));
+        assert!(transition_allowed(IssueStatus::Done, IssueStatus::Open, false));
+        assert!(transition_allowed(IssueStatus::InProgress, IssueStatus::Closed, false));
+    }
+
+    #[test]
+    fn check_search_params_rejects_conflicting_filters() {
+        let strict_ids = SearchIssuesParams {
+            issues_ids: vec![String::from("a")],
+            column_id: Some(String::from("column")),
+            strict: Some(true),
+            ..Default::default()
+        };
+        assert!(check_search_params(&strict_ids, None).is_err());
+        assert_eq!(check_search_params(&SearchIssuesParams { strict: None, ..strict_ids }, None), Ok(()));
+
+        let cursor = SearchIssuesParams { after_id: Some(String::from("a")), ..Default::default() };
+        assert_eq!(check_search_params(&cursor, None), Ok(()));
+        assert!(check_search_params(&cursor, Some(10)).is_err());
+        let text_cursor = SearchIssuesParams { text_query: Some(String::from("bug")), ..cursor };
+        assert!(check_search_params(&text_cursor, None).is_err());
+
+        let unknown_status = SearchIssuesParams { statuses: vec![42], ..Default::default() };
+        assert!(check_search_params(&unknown_status, None).is_err());
+    }
+
+    #[test]
+    fn check_transition_rejects_bad_requests() {
+        assert!(check_transition(&transition_request(&[], IssueStatus::Done as i32)).is_err());
+        assert!(check_transition(&transition_request(&["a", "a"], IssueStatus::Done as i32)).is_err());
+        assert!(check_transition(&transition_request(&["a"], 42)).is_err());
+        assert_eq!(
+            check_transition(&transition_request(&["a", "b"], IssueStatus::Done as i32)),
+            Ok(IssueStatus::Done)
+        );
+    }
+
+    #[test]
+    fn status_counts_include_every_status() {
+        let counts = status_counts(&[(IssueStatus::InProgress as i16, 4), (IssueStatus::Closed as i16, 1)]);
+        let counts: Vec<(i32, i64)> = counts.iter().map(|count| (count.status, count.count)).collect();
+        assert_eq!(counts, vec![
+            (IssueStatus::Open as i32, 0),
+            (IssueStatus::InProgress as i32, 4),
+            (IssueStatus::Done as i32, 0),
+            (IssueStatus::Closed as i32, 1),
+        ]);
+    }
 }
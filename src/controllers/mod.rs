@@ -2,4 +2,7 @@ pub mod boards;
 pub mod columns;
 pub mod epics;
 pub mod issues;
-pub mod dependencies;
\ No newline at end of file
+pub mod dependencies;
+pub mod admin;
+pub mod search;
+pub mod health;
\ No newline at end of file
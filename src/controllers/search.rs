@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+use diesel::{
+    RunQueryDsl,
+    QueryDsl,
+    ExpressionMethods, PgTextExpressionMethods, BoolExpressionMethods, QueryResult,
+};
+use tonic::{Request, Response, Status, Code, transport::Channel};
+use proto::{
+    issues::{
+        search_service_server::SearchService,
+        GlobalSearchRequest,
+        GlobalSearchResults,
+        GlobalSearchHit,
+        global_search_hit::Entity,
+        Board as ProtoBoard,
+    },
+    eventbus::{
+        search_events_service_client::SearchEventsServiceClient,
+        GlobalSearchEvent,
+    },
+};
+
+use crate::{
+    correlation::correlation_id,
+    lifecycle::spawn_tracked,
+    controllers::epics::{to_proto_epic, StoredDateOutOfRange},
+    controllers::issues::{escape_like, to_proto_issue},
+    events::{db_failure, pooled_connection, rejection, send_event},
+    pagination::Page,
+    db::{
+        repos::{board::Board, epic::Epic, issue::Issue},
+        schema::{boards, epics, issues},
+        connection::PgPool,
+    },
+};
+
+const DEFAULT_LIMIT: i64 = 20;
+
+pub struct SearchController {
+    pub pool: PgPool,
+    pub eventbus_service_client: Option<SearchEventsServiceClient<Channel>>,
+}
+
+#[tonic::async_trait]
+impl SearchService for SearchController {
+    async fn global_search(
+        &self,
+        request: Request<GlobalSearchRequest>,
+    ) -> Result<Response<GlobalSearchResults>, Status> {
+        let data = request.get_ref();
+
+        let validated = match data.query.trim().is_empty() {
+            true => Err(String::from("query required")),
+            false => Page::with_default(data.limit, None, DEFAULT_LIMIT),
+        };
+
+        let limit = match validated {
+            Ok(page) => page.limit,
+            Err(message) => {
+                let (error, status) = rejection(Code::InvalidArgument, &message);
+                let req = Request::new(GlobalSearchEvent {
+                    query: data.query.clone(),
+                    limit: data.limit,
+                    hits: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, global_search_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+
+        let db_connection = match pooled_connection(&self.pool) {
+            Ok(db_connection) => db_connection,
+            Err(rejected) => {
+                let (error, status) = *rejected;
+                let req = Request::new(GlobalSearchEvent {
+                    query: data.query.clone(),
+                    limit: data.limit,
+                    hits: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, global_search_event, req).await;
+                });
+                return Err(status);
+            }
+        };
+        let exact = escape_like(&data.query);
+        let prefix = format!("{}%", exact);
+        let pattern = format!("%{}%", exact);
+
+        // Each kind is ranked the same way as match_score before its limit,
+        // so the best matches are not cut off in favour of lower ids.
+
+        let result: QueryResult<(Vec<Issue>, Vec<Epic>, Vec<Board>)> = (|| {
+            let matched_issues: Vec<Issue> = issues::table
+                .filter(issues::title.ilike(&pattern))
+                .order((issues::title.ilike(&exact).desc(), issues::title.ilike(&prefix).desc(), issues::id.asc()))
+                .limit(limit)
+                .load::<Issue>(&*db_connection)?;
+
+            let matched_epics: Vec<Epic> = epics::table
+                .filter(epics::name.ilike(&pattern))
+                .order((epics::name.ilike(&exact).desc(), epics::name.ilike(&prefix).desc(), epics::id.asc()))
+                .limit(limit)
+                .load::<Epic>(&*db_connection)?;
+
+            let matched_boards: Vec<Board> = match data.include_boards {
+                true => boards::table
+                    .filter(boards::id.eq(&data.query).or(boards::project_id.eq(&data.query)))
+                    .order(boards::id.asc())
+                    .limit(limit)
+                    .load::<Board>(&*db_connection)?,
+                false => Vec::new(),
+            };
+
+            Ok((matched_issues, matched_epics, matched_boards))
+        })();
+
+        match result {
+            Ok((matched_issues, matched_epics, matched_boards)) => {
+                let issue_hits = matched_issues.iter().map(|iss| Ok(GlobalSearchHit {
+                    score: match_score(&iss.title, &data.query),
+                    entity: Some(Entity::Issue(to_proto_issue(iss)?)),
+                }));
+                let epic_hits = matched_epics.iter().map(|ep| Ok(GlobalSearchHit {
+                    score: match_score(&ep.name, &data.query),
+                    entity: Some(Entity::Epic(to_proto_epic(ep)?)),
+                }));
+                let mut hits = match issue_hits.chain(epic_hits)
+                    .collect::<Result<Vec<GlobalSearchHit>, StoredDateOutOfRange>>()
+                {
+                    Ok(hits) => hits,
+                    Err(out_of_range) => {
+                        let (error, status) = out_of_range.failure();
+                        let req = Request::new(GlobalSearchEvent {
+                            query: data.query.clone(),
+                            limit: data.limit,
+                            hits: None,
+                            error: Some(error),
+                            correlation_id: correlation_id()
+                        });
+                        let service = self.eventbus_service_client.clone();
+                        spawn_tracked(async move {
+                            send_event!(service, global_search_event, req).await;
+                        });
+                        return Err(status);
+                    }
+                };
+
+                hits.extend(matched_boards.into_iter().map(|brd| GlobalSearchHit {
+                    score: EXACT_MATCH,
+                    entity: Some(Entity::Board(ProtoBoard {
+                        id: brd.id,
+                        project_id: brd.project_id,
+                        columns: Vec::new(),
+                        deletion_protected: brd.deletion_protected,
+                    })),
+                }));
+
+                // Stable sort keeps issues, epics, boards in that order within a score.
+                hits.sort_by_key(|hit| Reverse(hit.score));
+                hits.truncate(limit as usize);
+
+                let req = Request::new(GlobalSearchEvent {
+                    query: data.query.clone(),
+                    limit: data.limit,
+                    hits: Some(hits.len() as i32),
+                    error: None,
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, global_search_event, req).await;
+                });
+
+                Ok(Response::new(GlobalSearchResults { hits }))
+            }
+            Err(err) => {
+                let (error, status) = db_failure(&err);
+                let req = Request::new(GlobalSearchEvent {
+                    query: data.query.clone(),
+                    limit: data.limit,
+                    hits: None,
+                    error: Some(error),
+                    correlation_id: correlation_id()
+                });
+                let service = self.eventbus_service_client.clone();
+                spawn_tracked(async move {
+                    send_event!(service, global_search_event, req).await;
+                });
+                Err(status)
+            }
+        }
+    }
+}
+
+const EXACT_MATCH: i32 = 3;
+const PREFIX_MATCH: i32 = 2;
+const SUBSTRING_MATCH: i32 = 1;
+
+/// Ranks a case-insensitive match: whole text, then prefix, then substring.
+fn match_score(text: &str, query: &str) -> i32 {
+    let text = text.to_lowercase();
+    let query = query.to_lowercase();
+    if text == query {
+        EXACT_MATCH
+    } else if text.starts_with(&query) {
+        PREFIX_MATCH
+    } else {
+        SUBSTRING_MATCH
+    }
+}
\ No newline at end of file
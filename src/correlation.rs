@@ -0,0 +1,102 @@
+use std::{future::Future, task::{Context, Poll}};
+use tonic::{
+    body::BoxBody,
+    codegen::{http::{HeaderValue, Request, Response}, BoxFuture, Service},
+    transport::Body,
+};
+use tower::Layer;
+
+/// Metadata key carrying the id that ties an RPC to the events it emits.
+pub const CORRELATION_HEADER: &str = "x-correlation-id";
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+/// The correlation id of the RPC being handled, for the events it emits.
+/// Empty outside of a call; tasks started with `spawn_tracked` keep the id
+/// of the call that started them.
+pub fn correlation_id() -> String {
+    CORRELATION_ID.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Runs `task` with `id` as its correlation id, for work done on behalf of
+/// a call outside of it.
+pub fn with_correlation_id<F: Future>(id: String, task: F) -> impl Future<Output = F::Output> {
+    CORRELATION_ID.scope(id, task)
+}
+
+/// ` (correlation id <id>)` to end log lines written on behalf of a call
+/// with, so they can be matched with its events; empty outside of a call.
+pub fn log_context() -> String {
+    match correlation_id() {
+        id if id.is_empty() => String::new(),
+        id => format!(" (correlation id {})", id),
+    }
+}
+
+/// Gives every call a correlation id: the caller's `x-correlation-id` when it
+/// sent a usable one, a fresh UUID otherwise. The id is visible to handlers
+/// through `correlation_id` and echoed in the response headers.
+#[derive(Clone, Copy)]
+pub struct CorrelationLayer;
+
+impl<S> Layer<S> for CorrelationLayer {
+    type Service = Correlated<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Correlated { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct Correlated<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for Correlated<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let id = request.headers()
+            .get(CORRELATION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty() && value.len() <= 128)
+            .map(str::to_owned)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        // Ids from the header are visible ASCII and generated ones are UUIDs,
+        // so this cannot fail.
+        let header = HeaderValue::from_str(&id).expect("valid correlation id");
+        request.headers_mut().insert(CORRELATION_HEADER, header.clone());
+
+        let call = self.inner.call(request);
+        Box::pin(CORRELATION_ID.scope(id, async move {
+            let mut response = call.await?;
+            response.headers_mut().insert(CORRELATION_HEADER, header);
+            Ok(response)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn log_context_names_the_call() {
+        assert_eq!(log_context(), "");
+        let context = with_correlation_id(String::from("abc"), async { log_context() }).await;
+        assert_eq!(context, " (correlation id abc)");
+    }
+}
@@ -1,19 +1,168 @@
-use diesel::{r2d2::{ConnectionManager, PoolError}, PgConnection};
+use diesel::{
+    r2d2::{ConnectionManager, CustomizeConnection, Error, PoolError},
+    connection::SimpleConnection,
+    PgConnection,
+};
 use dotenv::dotenv;
 use r2d2::Pool;
 use std::env;
 
+use super::pool_metrics::PoolWaitMetrics;
+
 pub type PgPool = Pool<ConnectionManager<PgConnection>>;
 
-fn init_pool(database_url: &str) -> Result<PgPool, PoolError> {
+/// Session settings applied to every new connection in the pool.
+/// Configured with `DATABASE_SEARCH_PATH` (unset keeps the server default)
+/// and `DATABASE_TIME_ZONE` (defaults to UTC).
+#[derive(Debug)]
+struct SessionSettings {
+    search_path: Option<String>,
+    time_zone: String,
+}
+
+impl SessionSettings {
+    fn from_env() -> SessionSettings {
+        SessionSettings {
+            search_path: env::var("DATABASE_SEARCH_PATH").ok(),
+            time_zone: env::var("DATABASE_TIME_ZONE").unwrap_or_else(|_| String::from("UTC")),
+        }
+    }
+}
+
+impl CustomizeConnection<PgConnection, Error> for SessionSettings {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), Error> {
+        if let Some(search_path) = &self.search_path {
+            conn.batch_execute(&format!("SET search_path TO {}", quote_search_path(search_path)))
+                .map_err(Error::QueryError)?;
+        }
+        conn.batch_execute(&format!("SET TIME ZONE '{}'", self.time_zone.replace('\'', "''")))
+            .map_err(Error::QueryError)
+    }
+}
+
+/// Quotes each comma-separated schema in `search_path` as an identifier, so
+/// the setting can only ever name schemas and not inject SQL. Names are
+/// then matched case-sensitively, as Postgres does for quoted identifiers.
+fn quote_search_path(search_path: &str) -> String {
+    search_path
+        .split(',')
+        .map(|schema| format!("\"{}\"", schema.trim().replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether the pool runs a validation query (`SELECT 1`) on a connection
+/// before lending it, so one the server has dropped is replaced instead of
+/// failing the request. Set with `DB_TEST_ON_CHECKOUT`, on by default.
+fn test_on_check_out_from_env() -> Result<bool, String> {
+    match env::var("DB_TEST_ON_CHECKOUT") {
+        Err(_) => Ok(true),
+        Ok(value) => match value.as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            other => Err(format!("DB_TEST_ON_CHECKOUT must be true or false, got {}", other)),
+        },
+    }
+}
+
+fn init_pool(
+    database_url: &str,
+    test_on_check_out: bool,
+    wait_metrics: PoolWaitMetrics,
+) -> Result<PgPool, PoolError> {
     let manager = ConnectionManager::<PgConnection>::new(database_url);
-    Pool::builder().build(manager)
+    Pool::builder()
+        .test_on_check_out(test_on_check_out)
+        .connection_customizer(Box::new(SessionSettings::from_env()))
+        .event_handler(Box::new(wait_metrics))
+        .build(manager)
 }
 
-pub fn establish_connection() -> PgPool {
+pub fn establish_connection(wait_metrics: PoolWaitMetrics) -> Result<PgPool, String> {
+    dotenv().ok();
+
+    let database_url = env::var("DATABASE_URL")
+        .map_err(|_| String::from("DATABASE_URL env variable must be set"))?;
+    let test_on_check_out = test_on_check_out_from_env()?;
+    init_pool(&database_url, test_on_check_out, wait_metrics)
+        .map_err(|err| format!("Failed to create pool: {}", err))
+}
+
+/// Pool for the get and search handlers, built from `DATABASE_READ_URL`
+/// (typically a streaming replica). Returns `None` when the variable is
+/// unset, in which case reads share the primary pool.
+///
+/// A replica lags the primary, so a read right after a write may not see
+/// it yet, and a get-by-id can put that stale row back into the read cache
+/// until its TTL runs out. Writes and the validation/audit handlers always
+/// go to the primary.
+pub fn establish_read_connection(wait_metrics: PoolWaitMetrics) -> Result<Option<PgPool>, String> {
+    dotenv().ok();
+
+    let database_url = match env::var("DATABASE_READ_URL") {
+        Ok(url) => url,
+        Err(_) => return Ok(None),
+    };
+    let test_on_check_out = test_on_check_out_from_env()?;
+    init_pool(&database_url, test_on_check_out, wait_metrics)
+        .map(Some)
+        .map_err(|err| format!("Failed to create read pool: {}", err))
+}
+
+/// Pool for the database tests, against the migrated database in
+/// `DATABASE_URL`.
+#[cfg(test)]
+pub fn test_pool() -> PgPool {
     dotenv().ok();
 
     let database_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL env variable must be set");
-    init_pool(&database_url).expect("Failed to create pool")
-}
\ No newline at end of file
+    let wait_metrics = PoolWaitMetrics::from_env().expect("Invalid POOL_WAIT_WARN_MS");
+    init_pool(&database_url, true, wait_metrics).expect("Failed to create test pool")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::{sql_query, sql_types::Integer, RunQueryDsl};
+
+    #[test]
+    fn search_path_schemas_are_quoted() {
+        assert_eq!(quote_search_path("issues"), "\"issues\"");
+        assert_eq!(quote_search_path("issues, public"), "\"issues\", \"public\"");
+        assert_eq!(
+            quote_search_path("x; DROP TABLE boards; --"),
+            "\"x; DROP TABLE boards; --\""
+        );
+        assert_eq!(quote_search_path("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[derive(QueryableByName)]
+    struct BackendPid {
+        #[sql_type = "Integer"]
+        pid: i32,
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn checkout_replaces_a_killed_connection() {
+        let pool = test_pool();
+        let victim = pool.get().unwrap();
+        let victim_pid: BackendPid = sql_query("SELECT pg_backend_pid() AS pid")
+            .get_result(&*victim)
+            .unwrap();
+        sql_query(format!("SELECT pg_terminate_backend({})", victim_pid.pid))
+            .execute(&*pool.get().unwrap())
+            .unwrap();
+        // Back in the pool as an idle connection the server has dropped.
+        drop(victim);
+
+        let connections: Vec<_> = (0..pool.max_size()).map(|_| pool.get().unwrap()).collect();
+        for connection in &connections {
+            let current: BackendPid = sql_query("SELECT pg_backend_pid() AS pid")
+                .get_result(&**connection)
+                .unwrap();
+            assert_ne!(current.pid, victim_pid.pid);
+        }
+    }
+}
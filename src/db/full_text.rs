@@ -0,0 +1,95 @@
+use std::{env, sync::OnceLock};
+use diesel::{
+    dsl::sql,
+    expression::{bound::Bound, Expression, NonAggregate, SqlLiteral},
+    sql_types::{Text, Float},
+};
+
+#[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+#[postgres(oid = "3614", array_oid = "3643")]
+pub struct TsVector;
+
+#[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+#[postgres(oid = "3615", array_oid = "3645")]
+pub struct TsQuery;
+
+#[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+#[postgres(oid = "3734", array_oid = "3735")]
+pub struct Regconfig;
+
+sql_function!(fn to_tsvector(config: Regconfig, document: Text) -> TsVector);
+sql_function!(fn websearch_to_tsquery(config: Regconfig, query: Text) -> TsQuery);
+sql_function!(fn ts_rank(vector: TsVector, query: TsQuery) -> Float);
+sql_function!(fn ts_headline(config: Regconfig, document: Text, query: TsQuery) -> Text);
+diesel_infix_operator!(Matches, " @@ ", backend: diesel::pg::Pg);
+
+/// The Postgres text search configuration from `FULL_TEXT_SEARCH_CONFIG`.
+static CONFIG: OnceLock<String> = OnceLock::new();
+
+/// Reads `FULL_TEXT_SEARCH_CONFIG`, the name of a Postgres text search
+/// configuration such as `english` or `simple`. Text searches fall back to
+/// substring matching when it is unset.
+pub fn configure_from_env() -> Result<(), String> {
+    if let Ok(value) = env::var("FULL_TEXT_SEARCH_CONFIG") {
+        // The name goes into the SQL as a literal, so keep it to what a
+        // (possibly schema-qualified) configuration name can look like.
+        let valid = !value.is_empty()
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        if !valid {
+            return Err(format!("FULL_TEXT_SEARCH_CONFIG must be a text search configuration name, got {}", value));
+        }
+        let _ = CONFIG.set(value);
+    }
+    Ok(())
+}
+
+pub type Document<D> = to_tsvector::HelperType<SqlLiteral<Regconfig>, D>;
+pub type Query = websearch_to_tsquery::HelperType<SqlLiteral<Regconfig>, Bound<Text, String>>;
+
+/// Search terms in web search syntax (quoted phrases, `or`, `-word`) matched
+/// against a text document with the configured text search configuration.
+pub struct TextQuery {
+    config: &'static str,
+    terms: String,
+}
+
+impl TextQuery {
+    /// `None` when full-text search is not configured.
+    pub fn new(terms: &str) -> Option<TextQuery> {
+        CONFIG.get().map(|config| TextQuery {
+            config,
+            terms: terms.to_owned(),
+        })
+    }
+
+    fn config(&self) -> SqlLiteral<Regconfig> {
+        sql(&format!("'{}'", self.config))
+    }
+
+    fn query(&self) -> Query {
+        websearch_to_tsquery(self.config(), self.terms.clone())
+    }
+
+    pub fn matches<D>(&self, document: D) -> Matches<Document<D>, Query>
+    where
+        D: Expression<SqlType = Text> + NonAggregate,
+    {
+        Matches::new(to_tsvector(self.config(), document), self.query())
+    }
+
+    pub fn rank<D>(&self, document: D) -> ts_rank::HelperType<Document<D>, Query>
+    where
+        D: Expression<SqlType = Text> + NonAggregate,
+    {
+        ts_rank(to_tsvector(self.config(), document), self.query())
+    }
+
+    /// The document with the matched words wrapped in `<b>` and `</b>`,
+    /// shortened around them.
+    pub fn headline<D>(&self, document: D) -> ts_headline::HelperType<SqlLiteral<Regconfig>, D, Query>
+    where
+        D: Expression<SqlType = Text> + NonAggregate,
+    {
+        ts_headline(self.config(), document, self.query())
+    }
+}
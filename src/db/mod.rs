@@ -1,3 +1,6 @@
 pub mod schema;
 pub mod repos;
-pub mod connection;
\ No newline at end of file
+pub mod connection;
+pub mod pool_metrics;
+pub mod slow_queries;
+pub mod full_text;
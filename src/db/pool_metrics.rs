@@ -0,0 +1,109 @@
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use r2d2::{HandleEvent, event::{CheckoutEvent, TimeoutEvent}};
+
+use crate::correlation::log_context;
+
+/// Upper bounds (inclusive, in milliseconds) of the wait histogram buckets.
+/// Waits above the last bound land in a final overflow bucket.
+pub const WAIT_BUCKETS_MS: [u64; 8] = [1, 5, 10, 50, 100, 500, 1000, 5000];
+
+/// Histogram of how long requests waited in `pool.get()` for a connection.
+/// Waits longer than `POOL_WAIT_WARN_MS` (default 100) are logged.
+#[derive(Clone, Debug)]
+pub struct PoolWaitMetrics {
+    inner: Arc<Counters>,
+    warn_threshold: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    buckets: [AtomicU64; WAIT_BUCKETS_MS.len() + 1],
+    count: AtomicU64,
+    total_wait_micros: AtomicU64,
+    max_wait_micros: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+pub struct PoolWaitSnapshot {
+    /// One count per `WAIT_BUCKETS_MS` bound, followed by the overflow bucket.
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub total_wait_micros: u64,
+    pub max_wait_micros: u64,
+    pub timeouts: u64,
+}
+
+impl PoolWaitMetrics {
+    pub fn from_env() -> Result<PoolWaitMetrics, String> {
+        let warn_ms = match env::var("POOL_WAIT_WARN_MS") {
+            Err(_) => 100,
+            Ok(value) => value.parse().map_err(|_| format!(
+                "POOL_WAIT_WARN_MS must be a non-negative integer, got {}", value
+            ))?,
+        };
+
+        Ok(PoolWaitMetrics {
+            inner: Arc::new(Counters::default()),
+            warn_threshold: Duration::from_millis(warn_ms),
+        })
+    }
+
+    pub fn snapshot(&self) -> PoolWaitSnapshot {
+        PoolWaitSnapshot {
+            buckets: self.inner.buckets
+                .iter()
+                .map(|bucket| bucket.load(Ordering::Relaxed))
+                .collect(),
+            count: self.inner.count.load(Ordering::Relaxed),
+            total_wait_micros: self.inner.total_wait_micros.load(Ordering::Relaxed),
+            max_wait_micros: self.inner.max_wait_micros.load(Ordering::Relaxed),
+            timeouts: self.inner.timeouts.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record(&self, wait: Duration) {
+        let wait_ms = wait.as_millis() as u64;
+        let wait_micros = wait.as_micros() as u64;
+        let bucket = WAIT_BUCKETS_MS
+            .iter()
+            .position(|bound| wait_ms <= *bound)
+            .unwrap_or(WAIT_BUCKETS_MS.len());
+
+        self.inner.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.inner.count.fetch_add(1, Ordering::Relaxed);
+        self.inner.total_wait_micros.fetch_add(wait_micros, Ordering::Relaxed);
+        self.inner.max_wait_micros.fetch_max(wait_micros, Ordering::Relaxed);
+    }
+}
+
+impl HandleEvent for PoolWaitMetrics {
+    fn handle_checkout(&self, event: CheckoutEvent) {
+        let wait = event.duration();
+        self.record(wait);
+        if wait > self.warn_threshold {
+            eprintln!(
+                "Waited {} ms for a database connection (threshold {} ms){}",
+                wait.as_millis(),
+                self.warn_threshold.as_millis(),
+                log_context(),
+            );
+        }
+    }
+
+    fn handle_timeout(&self, event: TimeoutEvent) {
+        self.inner.timeouts.fetch_add(1, Ordering::Relaxed);
+        eprintln!(
+            "Timed out after {} ms waiting for a database connection{}",
+            event.timeout().as_millis(),
+            log_context(),
+        );
+    }
+}
\ No newline at end of file
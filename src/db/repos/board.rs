@@ -1,15 +1,25 @@
 use diesel::result::Error;
 
 use crate::db;
-use db::schema::boards;
+use db::slow_queries::timed;
+use db::schema::{boards, columns, epics, issues, dependencies};
+use db::repos::{column::Column, epic::Epic, issue::Issue, dependency::Dependency};
+use crate::ids::new_id;
 
 use diesel::{
     RunQueryDsl,
+    QueryDsl,
+    OptionalExtension,
     r2d2::ConnectionManager,
+    Connection,
     PgConnection,
     ExpressionMethods,
+    BoolExpressionMethods,
     insert_into,
-    delete
+    update,
+    delete,
+    sql_query,
+    sql_types::Text,
 };
 use r2d2::PooledConnection;
 
@@ -17,6 +27,7 @@ use r2d2::PooledConnection;
 pub struct Board {
     pub id: String,
     pub project_id: String,
+    pub deletion_protected: bool,
 }
 
 #[derive(Insertable)]
@@ -26,6 +37,12 @@ pub struct NewBoard<'a> {
     pub project_id: &'a str,
 }
 
+#[derive(AsChangeset)]
+#[table_name="boards"]
+pub struct BoardChangeSet {
+    pub deletion_protected: Option<bool>,
+}
+
 #[tonic::async_trait]
 pub trait CreateBoard {
     async fn create<'a>(
@@ -40,21 +57,146 @@ impl CreateBoard for Board {
         new_board: NewBoard<'a>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Board, Error> {
-        let result: Vec<Board> = match insert_into(boards::dsl::boards)
+        timed("board", "create", || insert_into(boards::dsl::boards)
             .values(new_board)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
-            };
+            .returning(boards::all_columns)
+            .get_result(&*db_connection))
+    }
+}
 
-        let board: &Board = result
-            .first()
-            .unwrap();
+pub enum CreatedBoard {
+    Created(Board),
+    /// The project already had a board, so nothing was inserted.
+    Existing(Board),
+}
 
-        Ok(Board {
-            id: board.id.clone(),
-            project_id: board.project_id.clone(),
-        })
+#[tonic::async_trait]
+pub trait CreateOnlyBoard {
+    async fn create_only<'a>(
+        new_board: NewBoard<'a>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<CreatedBoard, Error>;
+}
+
+#[tonic::async_trait]
+impl CreateOnlyBoard for Board {
+    /// Inserts the board unless its project already has one. Concurrent calls
+    /// for the same project are serialized on a transaction-scoped advisory
+    /// lock, so only one of them can insert.
+    async fn create_only<'a>(
+        new_board: NewBoard<'a>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<CreatedBoard, Error> {
+        timed("board", "create_only", || create_only_with_columns(new_board, &[], &db_connection)
+            .map(|(created, _)| created))
+    }
+}
+
+#[tonic::async_trait]
+pub trait GetOrCreateBoard {
+    async fn get_or_create<'a>(
+        new_board: NewBoard<'a>,
+        column_names: &'a [String],
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(CreatedBoard, Vec<Column>), Error>;
+}
+
+#[tonic::async_trait]
+impl GetOrCreateBoard for Board {
+    /// Like `create_only`, but a board it inserts also gets `column_names`
+    /// as its columns, positioned in the given order. Also returns the
+    /// columns it inserted, none for an existing board.
+    async fn get_or_create<'a>(
+        new_board: NewBoard<'a>,
+        column_names: &'a [String],
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(CreatedBoard, Vec<Column>), Error> {
+        timed("board", "get_or_create", || create_only_with_columns(new_board, column_names, &db_connection))
+    }
+}
+
+fn create_only_with_columns(
+    new_board: NewBoard<'_>,
+    column_names: &[String],
+    db_connection: &PgConnection,
+) -> Result<(CreatedBoard, Vec<Column>), Error> {
+    db_connection.transaction::<_, Error, _>(|| {
+        sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind::<Text, _>(new_board.project_id)
+            .execute(db_connection)?;
+
+        let existing: Option<Board> = boards::dsl::boards
+            .filter(boards::dsl::project_id.eq(new_board.project_id))
+            .order(boards::dsl::id.asc())
+            .first(db_connection)
+            .optional()?;
+
+        if let Some(board) = existing {
+            return Ok((CreatedBoard::Existing(board), Vec::new()));
+        }
+
+        let board: Board = insert_into(boards::dsl::boards)
+            .values(new_board)
+            .get_result(db_connection)?;
+
+        let mut board_columns = Vec::with_capacity(column_names.len());
+        for (position, name) in column_names.iter().enumerate() {
+            let column: Column = insert_into(columns::dsl::columns)
+                .values((
+                    columns::dsl::id.eq(new_id()),
+                    columns::dsl::board_id.eq(&board.id),
+                    columns::dsl::name.eq(name),
+                    columns::dsl::position.eq(position as i32),
+                ))
+                .get_result(db_connection)?;
+            board_columns.push(column);
+        }
+
+        Ok((CreatedBoard::Created(board), board_columns))
+    })
+}
+
+#[tonic::async_trait]
+pub trait UpdateBoard {
+    async fn update<'a>(
+        board_id: &'a str,
+        change_set: BoardChangeSet,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Board, Error>;
+}
+
+#[tonic::async_trait]
+impl UpdateBoard for Board {
+    async fn update<'a>(
+        board_id: &'a str,
+        change_set: BoardChangeSet,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Board, Error> {
+        timed("board", "update", || update(boards::dsl::boards)
+            .filter(boards::dsl::id.eq(board_id))
+            .set(change_set)
+            .get_result(&*db_connection))
+    }
+}
+
+/// What a board deletion removed along with the board.
+pub struct DeletedBoard {
+    pub board: Board,
+    pub columns: Vec<Column>,
+    pub epics: Vec<Epic>,
+    pub issues: Vec<Issue>,
+    pub dependencies: Vec<Dependency>,
+}
+
+pub enum DeleteBoardError {
+    /// The board has deletion protection turned on.
+    Protected,
+    Db(Error),
+}
+
+impl From<Error> for DeleteBoardError {
+    fn from(err: Error) -> Self {
+        DeleteBoardError::Db(err)
     }
 }
 
@@ -63,30 +205,276 @@ pub trait DeleteBoard {
     async fn delete<'a>(
         board_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Board, Error>;
+    ) -> Result<DeletedBoard, DeleteBoardError>;
 }
 
 #[tonic::async_trait]
 impl DeleteBoard for Board {
+    /// Deletes the board with its columns, the epics and issues on those
+    /// columns and the dependencies of those epics, all or nothing. Issue
+    /// moves and links go with their issues. The protection flag is read
+    /// with the row locked, so a concurrent update cannot slip in between.
+    ///
+    /// Issues on other boards that belong to a deleted epic keep their
+    /// `epic_id`, the same as when that epic is deleted on its own.
     async fn delete<'a>(
         board_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Board, Error> {
-        let result: Vec<Board> = match delete(boards::dsl::boards)
-            .filter(boards::dsl::id.eq(board_id))
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
-            };
-
-        let board: &Board = match result.first() {
-            Some(brd) => brd,
-            None => return Err(Error::NotFound),
+    ) -> Result<DeletedBoard, DeleteBoardError> {
+        timed("board", "delete", || db_connection.transaction(|| {
+            let protected: bool = boards::dsl::boards
+                .filter(boards::dsl::id.eq(board_id))
+                .select(boards::dsl::deletion_protected)
+                .for_update()
+                .first(&*db_connection)?;
+            if protected {
+                return Err(DeleteBoardError::Protected);
+            }
+
+            let board: Board = delete(boards::dsl::boards)
+                .filter(boards::dsl::id.eq(board_id))
+                .get_result(&*db_connection)?;
+
+            let deleted_columns: Vec<Column> = delete(columns::dsl::columns)
+                .filter(columns::dsl::board_id.eq(board_id))
+                .get_results(&*db_connection)?;
+            let columns_ids: Vec<&String> = deleted_columns
+                .iter()
+                .map(|column| &column.id)
+                .collect();
+
+            let deleted_epics: Vec<Epic> = delete(epics::dsl::epics)
+                .filter(epics::dsl::column_id.eq_any(&columns_ids))
+                .get_results(&*db_connection)?;
+            let epics_ids: Vec<&String> = deleted_epics
+                .iter()
+                .map(|epic| &epic.id)
+                .collect();
+
+            let deleted_issues: Vec<Issue> = delete(issues::dsl::issues)
+                .filter(issues::dsl::column_id.eq_any(&columns_ids))
+                .get_results(&*db_connection)?;
+
+            let deleted_dependencies: Vec<Dependency> = delete(dependencies::dsl::dependencies)
+                .filter(dependencies::dsl::blocking_epic_id.eq_any(&epics_ids)
+                    .or(dependencies::dsl::blocked_epic_id.eq_any(&epics_ids)))
+                .get_results(&*db_connection)?;
+
+            Ok(DeletedBoard {
+                board,
+                columns: deleted_columns,
+                epics: deleted_epics,
+                issues: deleted_issues,
+                dependencies: deleted_dependencies,
+            })
+        }))
+    }
+}
+
+/// A board with everything on it, read at one point in time.
+pub struct BoardSnapshot {
+    pub board: Board,
+    pub columns: Vec<Column>,
+    pub epics: Vec<Epic>,
+    pub issues: Vec<Issue>,
+    /// Dependencies with at least one end on the board.
+    pub dependencies: Vec<Dependency>,
+}
+
+#[tonic::async_trait]
+pub trait SnapshotBoard {
+    async fn snapshot<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<BoardSnapshot, Error>;
+}
+
+#[tonic::async_trait]
+impl SnapshotBoard for Board {
+    /// Reads the board, its columns, the epics and issues on them and their
+    /// dependencies in one REPEATABLE READ transaction, so that a concurrent
+    /// change cannot leave, say, an issue on a column that was not read.
+    /// Everything is ordered by id.
+    async fn snapshot<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<BoardSnapshot, Error> {
+        timed("board", "snapshot", || db_connection.build_transaction().repeatable_read().read_only().run(|| {
+            let board: Board = boards::dsl::boards
+                .filter(boards::dsl::id.eq(board_id))
+                .first(&*db_connection)?;
+
+            let board_columns: Vec<Column> = columns::dsl::columns
+                .filter(columns::dsl::board_id.eq(board_id))
+                .order(columns::dsl::id.asc())
+                .load(&*db_connection)?;
+            let columns_ids: Vec<&String> = board_columns
+                .iter()
+                .map(|column| &column.id)
+                .collect();
+
+            let board_epics: Vec<Epic> = epics::dsl::epics
+                .filter(epics::dsl::column_id.eq_any(&columns_ids))
+                .order(epics::dsl::id.asc())
+                .load(&*db_connection)?;
+
+            let board_issues: Vec<Issue> = issues::dsl::issues
+                .filter(issues::dsl::column_id.eq_any(&columns_ids))
+                .order(issues::dsl::id.asc())
+                .load(&*db_connection)?;
+
+            let epics_ids: Vec<&String> = board_epics
+                .iter()
+                .map(|epic| &epic.id)
+                .collect();
+            let deps: Vec<Dependency> = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq_any(&epics_ids)
+                    .or(dependencies::dsl::blocked_epic_id.eq_any(&epics_ids)))
+                .order(dependencies::dsl::id.asc())
+                .load(&*db_connection)?;
+
+            Ok(BoardSnapshot {
+                board,
+                columns: board_columns,
+                epics: board_epics,
+                issues: board_issues,
+                dependencies: deps,
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::connection::test_pool;
+    use db::repos::{
+        column::{NewColumn, CreateColumn},
+        epic::{NewEpic, CreateEpic},
+        issue::{NewIssue, CreateIssue},
+        dependency::{NewDependency, CreateDependency},
+    };
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn delete_removes_columns_epics_and_issues() {
+        let pool = test_pool();
+        let board_id = new_id();
+        Board::create(NewBoard { id: &board_id, project_id: &new_id() }, pool.get().unwrap())
+            .await
+            .unwrap();
+
+        let mut columns_ids = Vec::new();
+        for column_name in ["todo", "done"] {
+            let column = Column::create(NewColumn { id: &new_id(), board_id: &board_id, name: column_name }, pool.get().unwrap())
+                .await
+                .unwrap();
+            columns_ids.push(column.id);
+        }
+
+        let epic = Epic::create(NewEpic {
+            id: &new_id(),
+            column_id: Some(&columns_ids[0]),
+            assignee_id: None,
+            reporter_id: "reporter",
+            name: "epic",
+            description: None,
+            start_date: None,
+            due_date: None,
+            progress_percent: None,
+            metadata: None,
+        }, None, pool.get().unwrap()).await.unwrap();
+
+        for col_id in columns_ids.iter().chain(columns_ids.iter()) {
+            Issue::create(NewIssue {
+                id: &new_id(),
+                column_id: Some(col_id),
+                epic_id: &epic.id,
+                title: "issue",
+                description: "",
+            }, None, pool.get().unwrap()).await.unwrap();
+        }
+
+        let deleted = match Board::delete(&board_id, pool.get().unwrap()).await {
+            Ok(deleted) => deleted,
+            Err(_) => panic!("board was not deleted"),
         };
+        assert_eq!(deleted.columns.len(), 2);
+        assert_eq!(deleted.epics.len(), 1);
+        assert_eq!(deleted.issues.len(), 4);
+
+        let conn = pool.get().unwrap();
+        let columns_left: i64 = columns::dsl::columns
+            .filter(columns::dsl::board_id.eq(&board_id))
+            .count()
+            .get_result(&*conn)
+            .unwrap();
+        let epics_left: i64 = epics::dsl::epics
+            .filter(epics::dsl::column_id.eq_any(&columns_ids))
+            .count()
+            .get_result(&*conn)
+            .unwrap();
+        let issues_left: i64 = issues::dsl::issues
+            .filter(issues::dsl::column_id.eq_any(&columns_ids))
+            .count()
+            .get_result(&*conn)
+            .unwrap();
+        assert_eq!((columns_left, epics_left, issues_left), (0, 0, 0));
+    }
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn snapshot_reads_everything_on_the_board() {
+        let pool = test_pool();
+        let board_id = new_id();
+        Board::create(NewBoard { id: &board_id, project_id: &new_id() }, pool.get().unwrap())
+            .await
+            .unwrap();
+        let column = Column::create(NewColumn { id: &new_id(), board_id: &board_id, name: "todo" }, pool.get().unwrap())
+            .await
+            .unwrap();
 
-        Ok(Board {
-            id: board.id.clone(),
-            project_id: board.project_id.clone(),
-        })
+        let mut epics_ids = Vec::new();
+        for epic_name in ["first", "second"] {
+            let epic = Epic::create(NewEpic {
+                id: &new_id(),
+                column_id: Some(&column.id),
+                assignee_id: None,
+                reporter_id: "reporter",
+                name: epic_name,
+                description: None,
+                start_date: None,
+                due_date: None,
+                progress_percent: None,
+                metadata: None,
+            }, None, pool.get().unwrap()).await.unwrap();
+            epics_ids.push(epic.id);
+        }
+        epics_ids.sort();
+        Issue::create(NewIssue {
+            id: &new_id(),
+            column_id: Some(&column.id),
+            epic_id: &epics_ids[0],
+            title: "issue",
+            description: "",
+        }, None, pool.get().unwrap()).await.unwrap();
+        let created = Dependency::create(NewDependency {
+            id: &new_id(),
+            blocking_epic_id: &epics_ids[0],
+            blocked_epic_id: &epics_ids[1],
+            dependency_type: None,
+        }, pool.get().unwrap()).await;
+        assert!(created.is_ok(), "dependency was not created");
+
+        let snapshot = Board::snapshot(&board_id, pool.get().unwrap()).await;
+        let deleted = Board::delete(&board_id, pool.get().unwrap()).await;
+        assert!(deleted.is_ok(), "board was not deleted");
+
+        let snapshot = snapshot.unwrap();
+        assert_eq!(snapshot.board.id, board_id);
+        assert_eq!(snapshot.columns.len(), 1);
+        let snapshot_epics_ids: Vec<&String> = snapshot.epics.iter().map(|epic| &epic.id).collect();
+        assert_eq!(snapshot_epics_ids, vec![&epics_ids[0], &epics_ids[1]]);
+        assert_eq!(snapshot.issues.len(), 1);
+        assert_eq!(snapshot.dependencies.len(), 1);
     }
-}
\ No newline at end of file
+}
@@ -1,16 +1,23 @@
+use std::collections::HashSet;
 use diesel::result::Error;
 
 use crate::db;
-use db::schema::columns;
+use db::slow_queries::timed;
+use db::schema::{columns, issues};
 
 use diesel::{
     RunQueryDsl,
     r2d2::ConnectionManager,
     PgConnection,
     ExpressionMethods,
+    QueryDsl,
+    Connection,
     insert_into,
     update,
-    delete
+    delete,
+    sql_query,
+    expression::dsl::max,
+    sql_types::Text,
 };
 use r2d2::PooledConnection;
 
@@ -20,6 +27,19 @@ pub struct Column {
     pub id: String,
     pub board_id: String,
     pub name: String,
+    pub position: i32,
+    pub archived: bool,
+}
+
+/// The id of the board's first column by position, `Error::NotFound` when
+/// the board has none. Where new epics and issues go when only a board is
+/// given.
+pub(crate) fn first_column_id(board_id: &str, db_connection: &PgConnection) -> Result<String, Error> {
+    columns::dsl::columns
+        .filter(columns::dsl::board_id.eq(board_id))
+        .order((columns::dsl::position.asc(), columns::dsl::id.asc()))
+        .select(columns::dsl::id)
+        .first(db_connection)
 }
 
 #[derive(Insertable)]
@@ -34,6 +54,7 @@ pub struct NewColumn<'a> {
 #[table_name="columns"]
 pub struct ColumnChangeSet {
     pub name: Option<String>,
+    pub archived: Option<bool>,
 }
 
 #[tonic::async_trait]
@@ -46,26 +67,42 @@ pub trait CreateColumn {
 
 #[tonic::async_trait]
 impl CreateColumn for Column {
+    /// Puts the column after the board's last one. Creates on the same
+    /// board are serialized so that they cannot take the same position.
     async fn create<'a>(
         new_column: NewColumn<'a>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Column, Error> {
-        let result: Vec<Column> = match insert_into(columns::dsl::columns)
-            .values(new_column)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
-            };
+        timed("column", "create", || db_connection.transaction::<_, Error, _>(|| {
+            sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                .bind::<Text, _>(new_column.board_id)
+                .execute(&*db_connection)?;
 
-        let column: &Column = result
-            .first()
-            .unwrap();
+            let last: Option<i32> = columns::dsl::columns
+                .filter(columns::dsl::board_id.eq(new_column.board_id))
+                .select(max(columns::dsl::position))
+                .first(&*db_connection)?;
 
-        Ok(Column {
-            id: column.id.clone(),
-            board_id: column.board_id.clone(),
-            name: column.name.clone(),
-        })
+            insert_into(columns::dsl::columns)
+                .values((new_column, columns::dsl::position.eq(last.map_or(0, |position| position + 1))))
+                .returning(columns::all_columns)
+                .get_result(&*db_connection)
+        }))
+    }
+}
+
+pub enum UpdateColumnError {
+    /// The column, on the board with this id, is archived and the update
+    /// does not restore it.
+    Archived(String),
+    /// Another column on the board, with this id, already has the new name.
+    NameTaken(String),
+    Db(Error),
+}
+
+impl From<Error> for UpdateColumnError {
+    fn from(err: Error) -> Self {
+        UpdateColumnError::Db(err)
     }
 }
 
@@ -74,35 +111,55 @@ pub trait UpdateColumn {
     async fn update<'a>(
         column_id: &'a str,
         change_set: ColumnChangeSet,
+        enforce_unique_name: bool,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Column, Error>;
+    ) -> Result<Column, UpdateColumnError>;
 }
 
 #[tonic::async_trait]
 impl UpdateColumn for Column {
+    /// An archived column only takes an update that restores it. The check
+    /// is made on the row locked for the update. With `enforce_unique_name`,
+    /// renames on the same board are serialized like creates, so two of them
+    /// cannot both take a free name.
     async fn update<'a>(
         column_id: &'a str,
         change_set: ColumnChangeSet,
+        enforce_unique_name: bool,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Column, Error> {
-        let result: Vec<Column> = match update(columns::dsl::columns)
-            .filter(columns::dsl::id.eq(column_id))
-            .set(change_set)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
-            };
+    ) -> Result<Column, UpdateColumnError> {
+        timed("column", "update", || db_connection.transaction(|| {
+            let current: Column = columns::dsl::columns
+                .filter(columns::dsl::id.eq(column_id))
+                .for_update()
+                .first(&*db_connection)?;
+            if current.archived && change_set.archived != Some(false) {
+                return Err(UpdateColumnError::Archived(current.board_id));
+            }
 
-        let column: &Column = match result.first() {
-            Some(col) => col,
-            None => return Err(Error::NotFound),
-        };
+            if let (true, Some(new_name)) = (enforce_unique_name, &change_set.name) {
+                sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                    .bind::<Text, _>(&current.board_id)
+                    .execute(&*db_connection)?;
 
-        Ok(Column {
-            id: column.id.clone(),
-            board_id: column.board_id.clone(),
-            name: column.name.clone(),
-        })
+                let taken: i64 = columns::dsl::columns
+                    .filter(columns::dsl::board_id.eq(&current.board_id))
+                    .filter(columns::dsl::name.eq(new_name))
+                    .filter(columns::dsl::id.ne(column_id))
+                    .count()
+                    .get_result(&*db_connection)?;
+                if taken > 0 {
+                    return Err(UpdateColumnError::NameTaken(current.board_id));
+                }
+            }
+
+            let column: Column = update(columns::dsl::columns)
+                .filter(columns::dsl::id.eq(column_id))
+                .set(change_set)
+                .get_result(&*db_connection)?;
+
+            Ok(column)
+        }))
     }
 }
 
@@ -120,9 +177,9 @@ impl DeleteColumn for Column {
         column_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Column, Error> {
-        let result: Vec<Column> = match delete(columns::dsl::columns)
+        let result: Vec<Column> = match timed("column", "delete", || delete(columns::dsl::columns)
             .filter(columns::dsl::id.eq(column_id))
-            .get_results(&*db_connection) {
+            .get_results(&*db_connection)) {
                 Ok(res) => res,
                 Err(err) => return Err(err),
             };
@@ -136,6 +193,298 @@ impl DeleteColumn for Column {
             id: column.id.clone(),
             board_id: column.board_id.clone(),
             name: column.name.clone(),
+            position: column.position,
+            archived: column.archived,
         })
     }
-}
\ No newline at end of file
+}
+
+pub enum SwapColumnsError {
+    DifferentBoards,
+    Db(Error),
+}
+
+impl From<Error> for SwapColumnsError {
+    fn from(err: Error) -> Self {
+        SwapColumnsError::Db(err)
+    }
+}
+
+#[tonic::async_trait]
+pub trait SwapColumns {
+    async fn swap<'a>(
+        first_column_id: &'a str,
+        second_column_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Column, Column), SwapColumnsError>;
+}
+
+#[tonic::async_trait]
+impl SwapColumns for Column {
+    async fn swap<'a>(
+        first_column_id: &'a str,
+        second_column_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Column, Column), SwapColumnsError> {
+        timed("column", "swap", || db_connection.transaction::<_, SwapColumnsError, _>(|| {
+            // Both rows in one statement and in id order, so that swaps of
+            // the same pair given the other way round cannot deadlock.
+            let locked: Vec<Column> = columns::dsl::columns
+                .filter(columns::dsl::id.eq_any(&[first_column_id, second_column_id]))
+                .order(columns::dsl::id.asc())
+                .for_update()
+                .load(&*db_connection)?;
+            let locked_column = |column_id: &str| locked
+                .iter()
+                .find(|column| column.id == column_id)
+                .ok_or(Error::NotFound);
+            let first = locked_column(first_column_id)?;
+            let second = locked_column(second_column_id)?;
+
+            if first.board_id != second.board_id {
+                return Err(SwapColumnsError::DifferentBoards);
+            }
+
+            let swapped_first: Column = update(columns::dsl::columns)
+                .filter(columns::dsl::id.eq(first_column_id))
+                .set(columns::dsl::position.eq(second.position))
+                .get_result(&*db_connection)?;
+
+            let swapped_second: Column = update(columns::dsl::columns)
+                .filter(columns::dsl::id.eq(second_column_id))
+                .set(columns::dsl::position.eq(first.position))
+                .get_result(&*db_connection)?;
+
+            Ok((swapped_first, swapped_second))
+        }))
+    }
+}
+
+#[tonic::async_trait]
+pub trait NormalizePositions {
+    async fn normalize_positions<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<NormalizedCounts, Error>;
+}
+
+/// How many rows `normalize_positions` gave a new position or rank.
+pub struct NormalizedCounts {
+    pub columns_adjusted: i32,
+    pub issues_adjusted: i32,
+}
+
+#[tonic::async_trait]
+impl NormalizePositions for Column {
+    /// Rewrites the board's column positions, and the issue ranks within each
+    /// of its columns, to dense 0..N sequences keeping their relative order.
+    /// Ties are broken by id.
+    async fn normalize_positions<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<NormalizedCounts, Error> {
+        timed("column", "normalize_positions", || db_connection.transaction::<_, Error, _>(|| {
+            let board_columns: Vec<Column> = columns::dsl::columns
+                .filter(columns::dsl::board_id.eq(board_id))
+                .order((columns::dsl::position.asc(), columns::dsl::id.asc()))
+                .for_update()
+                .load(&*db_connection)?;
+
+            let mut columns_adjusted = 0;
+            for (index, column) in board_columns.iter().enumerate() {
+                let new_position = index as i32;
+                if column.position == new_position {
+                    continue;
+                }
+                update(columns::dsl::columns)
+                    .filter(columns::dsl::id.eq(&column.id))
+                    .set(columns::dsl::position.eq(new_position))
+                    .execute(&*db_connection)?;
+                columns_adjusted += 1;
+            }
+
+            let columns_ids: Vec<&str> = board_columns.iter().map(|column| column.id.as_str()).collect();
+            let board_issues: Vec<(String, String, i32)> = issues::dsl::issues
+                .filter(issues::dsl::column_id.eq_any(&columns_ids))
+                .select((issues::dsl::id, issues::dsl::column_id, issues::dsl::rank))
+                .order((issues::dsl::column_id.asc(), issues::dsl::rank.asc(), issues::dsl::id.asc()))
+                .for_update()
+                .load(&*db_connection)?;
+
+            let mut issues_adjusted = 0;
+            let mut new_rank = 0;
+            for (index, (issue_id, column_id, rank)) in board_issues.iter().enumerate() {
+                if index > 0 && board_issues[index - 1].1 != *column_id {
+                    new_rank = 0;
+                }
+                if *rank != new_rank {
+                    update(issues::dsl::issues)
+                        .filter(issues::dsl::id.eq(issue_id))
+                        .set(issues::dsl::rank.eq(new_rank))
+                        .execute(&*db_connection)?;
+                    issues_adjusted += 1;
+                }
+                new_rank += 1;
+            }
+
+            Ok(NormalizedCounts { columns_adjusted, issues_adjusted })
+        }))
+    }
+}
+
+pub enum ReorderColumnsError {
+    DuplicateColumn(String),
+    /// Listed ids that are not columns of the board.
+    UnknownColumns(Vec<String>),
+    Db(Error),
+}
+
+impl From<Error> for ReorderColumnsError {
+    fn from(err: Error) -> Self {
+        ReorderColumnsError::Db(err)
+    }
+}
+
+#[tonic::async_trait]
+pub trait ReorderColumns {
+    async fn reorder<'a>(
+        board_id: &'a str,
+        column_ids: &'a [String],
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Column>, ReorderColumnsError>;
+}
+
+#[tonic::async_trait]
+impl ReorderColumns for Column {
+    /// Gives the listed columns positions 0..N in the given order. Columns
+    /// of the board that are not listed follow them in their current order.
+    /// Returns all the board's columns in their new order.
+    async fn reorder<'a>(
+        board_id: &'a str,
+        column_ids: &'a [String],
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Column>, ReorderColumnsError> {
+        timed("column", "reorder", || db_connection.transaction::<_, ReorderColumnsError, _>(|| {
+            let mut board_columns: Vec<Column> = columns::dsl::columns
+                .filter(columns::dsl::board_id.eq(board_id))
+                .order((columns::dsl::position.asc(), columns::dsl::id.asc()))
+                .for_update()
+                .load(&*db_connection)?;
+
+            let mut listed = HashSet::new();
+            for column_id in column_ids {
+                if !listed.insert(column_id.as_str()) {
+                    return Err(ReorderColumnsError::DuplicateColumn(column_id.clone()));
+                }
+            }
+            let unknown: Vec<String> = column_ids
+                .iter()
+                .filter(|column_id| !board_columns.iter().any(|column| &column.id == *column_id))
+                .cloned()
+                .collect();
+            if !unknown.is_empty() {
+                return Err(ReorderColumnsError::UnknownColumns(unknown));
+            }
+
+            // A stable sort keeps the current order among unlisted columns.
+            board_columns.sort_by_key(|column| column_ids
+                .iter()
+                .position(|column_id| *column_id == column.id)
+                .unwrap_or(column_ids.len()));
+
+            for (index, column) in board_columns.iter_mut().enumerate() {
+                let new_position = index as i32;
+                if column.position == new_position {
+                    continue;
+                }
+                update(columns::dsl::columns)
+                    .filter(columns::dsl::id.eq(&column.id))
+                    .set(columns::dsl::position.eq(new_position))
+                    .execute(&*db_connection)?;
+                column.position = new_position;
+            }
+
+            Ok(board_columns)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::new_id;
+    use db::connection::test_pool;
+    use db::repos::issue::{Issue, NewIssue, CreateIssue};
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn normalize_positions_compacts_issue_ranks_per_column() {
+        let pool = test_pool();
+        let board_id = new_id();
+        let column = Column::create(NewColumn { id: &new_id(), board_id: &board_id, name: "todo" }, pool.get().unwrap())
+            .await
+            .unwrap();
+
+        let mut issues_ids = Vec::new();
+        for _ in 0..3 {
+            let issue = Issue::create(NewIssue {
+                id: &new_id(),
+                column_id: Some(&column.id),
+                epic_id: &new_id(),
+                title: "issue",
+                description: "",
+            }, None, pool.get().unwrap()).await.unwrap();
+            issues_ids.push(issue.id);
+        }
+        update(issues::dsl::issues)
+            .filter(issues::dsl::id.eq(&issues_ids[0]))
+            .set(issues::dsl::rank.eq(7))
+            .execute(&*pool.get().unwrap())
+            .unwrap();
+
+        let counts = match Column::normalize_positions(&board_id, pool.get().unwrap()).await {
+            Ok(counts) => counts,
+            Err(err) => panic!("positions were not normalized: {}", err),
+        };
+        assert_eq!((counts.columns_adjusted, counts.issues_adjusted), (0, 3));
+
+        let ranked: Vec<(String, i32)> = issues::dsl::issues
+            .filter(issues::dsl::column_id.eq(&column.id))
+            .select((issues::dsl::id, issues::dsl::rank))
+            .order(issues::dsl::rank.asc())
+            .load(&*pool.get().unwrap())
+            .unwrap();
+        let expected = vec![
+            (issues_ids[1].clone(), 0),
+            (issues_ids[2].clone(), 1),
+            (issues_ids[0].clone(), 2),
+        ];
+        assert_eq!(ranked, expected);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn swap_exchanges_positions_in_either_order() {
+        let pool = test_pool();
+        let board_id = new_id();
+        let mut columns_ids = Vec::new();
+        for column_name in ["todo", "done"] {
+            let column = Column::create(NewColumn { id: &new_id(), board_id: &board_id, name: column_name }, pool.get().unwrap())
+                .await
+                .unwrap();
+            columns_ids.push(column.id);
+        }
+
+        let swapped = Column::swap(&columns_ids[1], &columns_ids[0], pool.get().unwrap()).await;
+        let positions = swapped.ok().map(|(first, second)| (first.position, second.position));
+        let missing = Column::swap(&columns_ids[0], &new_id(), pool.get().unwrap()).await;
+
+        delete(columns::dsl::columns)
+            .filter(columns::dsl::board_id.eq(&board_id))
+            .execute(&*pool.get().unwrap())
+            .unwrap();
+
+        assert_eq!(positions, Some((0, 1)));
+        assert!(matches!(missing, Err(SwapColumnsError::Db(Error::NotFound))));
+    }
+}
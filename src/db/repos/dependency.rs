@@ -1,24 +1,34 @@
-use diesel::result::Error;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::NaiveDateTime;
+
+use diesel::result::{Error, DatabaseErrorKind};
 
 use crate::db;
-use db::schema::dependencies;
+use db::slow_queries::timed;
+use db::schema::{dependencies, epics, columns};
+use db::repos::epic::Epic;
 
 use diesel::{
     RunQueryDsl,
+    QueryDsl,
     r2d2::ConnectionManager,
+    Connection,
     PgConnection,
     ExpressionMethods,
+    BoolExpressionMethods,
     insert_into,
     update,
     delete
 };
 use r2d2::PooledConnection;
 
-#[derive(Queryable)]
+#[derive(Queryable, Clone)]
 pub struct Dependency {
     pub id: String,
     pub blocking_epic_id: String,
     pub blocked_epic_id: String,
+    pub dependency_type: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -27,6 +37,7 @@ pub struct NewDependency<'a> {
     pub id: &'a str,
     pub blocking_epic_id: &'a str,
     pub blocked_epic_id: &'a str,
+    pub dependency_type: Option<i32>,
 }
 
 #[derive(AsChangeset)]
@@ -36,72 +47,198 @@ pub struct DependencyChangeSet {
     pub blocked_epic_id: Option<String>,
 }
 
+/// How often a create is retried when its serializable transaction loses
+/// to a concurrent one.
+const SERIALIZATION_RETRIES: usize = 3;
+
+pub enum CreateDependencyError {
+    /// The new blocking dependency would close a cycle through these epics,
+    /// starting with its blocking epic; each blocks the next one and the last
+    /// blocks the first.
+    Cycle(Vec<String>),
+    Db(Error),
+}
+
+impl From<Error> for CreateDependencyError {
+    fn from(err: Error) -> Self {
+        CreateDependencyError::Db(err)
+    }
+}
+
 #[tonic::async_trait]
 pub trait CreateDependency {
     async fn create<'a>(
         new_dependency: NewDependency<'a>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Dependency, Error>;
+    ) -> Result<Dependency, CreateDependencyError>;
 }
 
 #[tonic::async_trait]
 impl CreateDependency for Dependency {
+    /// A blocking dependency is only inserted if it does not close a cycle.
+    /// The check and the insert share a SERIALIZABLE transaction, so two
+    /// concurrent creates cannot both pass the check and form a cycle
+    /// together; the one that loses is retried.
     async fn create<'a>(
         new_dependency: NewDependency<'a>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Dependency, Error> {
-        let result: Vec<Dependency> = match insert_into(dependencies::dsl::dependencies)
-            .values(new_dependency)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
-            };
+    ) -> Result<Dependency, CreateDependencyError> {
+        timed("dependency", "create", || {
+            let mut attempt = 0;
+            loop {
+                let result = db_connection.build_transaction().serializable().run(|| {
+                    if new_dependency.dependency_type.unwrap_or_default() == 0 {
+                        let path = blocking_path(
+                            new_dependency.blocked_epic_id,
+                            new_dependency.blocking_epic_id,
+                            &db_connection,
+                        )?;
+                        if let Some(mut path) = path {
+                            path.pop();
+                            path.insert(0, new_dependency.blocking_epic_id.to_string());
+                            return Err(CreateDependencyError::Cycle(path));
+                        }
+                    }
 
-        let dependency: &Dependency = result
-            .first()
-            .unwrap();
+                    let dependency: Dependency = insert_into(dependencies::dsl::dependencies)
+                        .values(&new_dependency)
+                        .returning(dependencies::all_columns)
+                        .get_result(&*db_connection)?;
+                    Ok(dependency)
+                });
 
-        Ok(Dependency {
-            id: dependency.id.clone(),
-            blocked_epic_id: dependency.blocked_epic_id.clone(),
-            blocking_epic_id: dependency.blocking_epic_id.clone(),
+                match result {
+                    Err(CreateDependencyError::Db(Error::DatabaseError(DatabaseErrorKind::SerializationFailure, _)))
+                        if attempt < SERIALIZATION_RETRIES => attempt += 1,
+                    result => return result,
+                }
+            }
         })
     }
 }
 
+/// The epics along a chain of blocking dependencies from `from` to `to`,
+/// both included, or `None` when `to` is not reachable. Walks breadth-first,
+/// so the chain found is a shortest one.
+pub(crate) fn blocking_path(from: &str, to: &str, db_connection: &PgConnection) -> Result<Option<Vec<String>>, Error> {
+    shortest_path(from, to, |frontier| {
+        dependencies::dsl::dependencies
+            .select((dependencies::dsl::blocking_epic_id, dependencies::dsl::blocked_epic_id))
+            .filter(dependencies::dsl::blocking_epic_id.eq_any(frontier))
+            .filter(dependencies::dsl::dependency_type.eq(0).or(dependencies::dsl::dependency_type.is_null()))
+            .order((dependencies::dsl::blocking_epic_id.asc(), dependencies::dsl::blocked_epic_id.asc()))
+            .load(db_connection)
+    })
+}
+
+/// The breadth-first walk behind `blocking_path`. `edges_from` returns the
+/// `(blocking, blocked)` edges leaving any epic of the frontier, ordered by
+/// blocking and then blocked epic id.
+fn shortest_path<E>(
+    from: &str,
+    to: &str,
+    mut edges_from: impl FnMut(&[String]) -> Result<Vec<(String, String)>, E>,
+) -> Result<Option<Vec<String>>, E> {
+    let mut parents: HashMap<String, String> = HashMap::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    seen.insert(from.to_string());
+    let mut frontier: Vec<String> = vec![from.to_string()];
+
+    while !frontier.is_empty() {
+        let edges = edges_from(&frontier)?;
+
+        let mut next: Vec<String> = Vec::new();
+        for (blocking, blocked) in edges {
+            if !seen.insert(blocked.clone()) {
+                continue;
+            }
+            parents.insert(blocked.clone(), blocking);
+            if blocked == to {
+                let mut path = vec![blocked];
+                while let Some(parent) = parents.get(path.last().map(String::as_str).unwrap_or_default()) {
+                    path.push(parent.clone());
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            next.push(blocked);
+        }
+        frontier = next;
+    }
+
+    Ok(None)
+}
+
+pub enum UpdateDependencyError {
+    /// The change would make an epic depend on itself.
+    SelfReference,
+    /// The re-pointed blocking dependency would close a cycle through these
+    /// epics, starting with its blocking epic.
+    Cycle(Vec<String>),
+    Db(Error),
+}
+
+impl From<Error> for UpdateDependencyError {
+    fn from(err: Error) -> Self {
+        UpdateDependencyError::Db(err)
+    }
+}
+
 #[tonic::async_trait]
 pub trait UpdateDependency {
     async fn update<'a>(
         dependency_id: &'a str,
         change_set: DependencyChangeSet,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Dependency, Error>;
+    ) -> Result<Dependency, UpdateDependencyError>;
 }
 
 #[tonic::async_trait]
 impl UpdateDependency for Dependency {
+    /// Re-points a dependency in place, keeping its id. Like a create, the
+    /// cycle check runs in the same SERIALIZABLE transaction as the change:
+    /// the row is updated first and the change rolled back if the new
+    /// blocked epic now reaches the new blocking one.
     async fn update<'a>(
         dependency_id: &'a str,
         change_set: DependencyChangeSet,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Dependency, Error> {
-        let result: Vec<Dependency> = match update(dependencies::dsl::dependencies)
-            .filter(dependencies::dsl::id.eq(dependency_id))
-            .set(change_set)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
-            };
+    ) -> Result<Dependency, UpdateDependencyError> {
+        timed("dependency", "update", || {
+            let mut attempt = 0;
+            loop {
+                let result = db_connection.build_transaction().serializable().run(|| {
+                    let dependency: Dependency = update(dependencies::dsl::dependencies)
+                        .filter(dependencies::dsl::id.eq(dependency_id))
+                        .set(&change_set)
+                        .get_result(&*db_connection)?;
 
-        let dependency: &Dependency = match result.first() {
-            Some(dep) => dep,
-            None => return Err(Error::NotFound),
-        };
+                    if dependency.blocking_epic_id == dependency.blocked_epic_id {
+                        return Err(UpdateDependencyError::SelfReference);
+                    }
 
-        Ok(Dependency {
-            id: dependency.id.clone(),
-            blocked_epic_id: dependency.blocked_epic_id.clone(),
-            blocking_epic_id: dependency.blocking_epic_id.clone(),
+                    if dependency.dependency_type.unwrap_or_default() == 0 {
+                        let path = blocking_path(
+                            &dependency.blocked_epic_id,
+                            &dependency.blocking_epic_id,
+                            &db_connection,
+                        )?;
+                        if let Some(mut path) = path {
+                            path.pop();
+                            path.insert(0, dependency.blocking_epic_id.clone());
+                            return Err(UpdateDependencyError::Cycle(path));
+                        }
+                    }
+
+                    Ok(dependency)
+                });
+
+                match result {
+                    Err(UpdateDependencyError::Db(Error::DatabaseError(DatabaseErrorKind::SerializationFailure, _)))
+                        if attempt < SERIALIZATION_RETRIES => attempt += 1,
+                    result => return result,
+                }
+            }
         })
     }
 }
@@ -120,9 +257,9 @@ impl DeleteDependency for Dependency {
         dependency_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Dependency, Error> {
-        let result: Vec<Dependency> = match delete(dependencies::dsl::dependencies)
+        let result: Vec<Dependency> = match timed("dependency", "delete", || delete(dependencies::dsl::dependencies)
             .filter(dependencies::dsl::id.eq(dependency_id))
-            .get_results(&*db_connection) {
+            .get_results(&*db_connection)) {
                 Ok(res) => res,
                 Err(err) => return Err(err),
             };
@@ -136,6 +273,517 @@ impl DeleteDependency for Dependency {
             id: dependency.id.clone(),
             blocked_epic_id: dependency.blocked_epic_id.clone(),
             blocking_epic_id: dependency.blocking_epic_id.clone(),
+            dependency_type: dependency.dependency_type,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Integrity problems found among a set of dependencies.
+pub struct DependencyAudit {
+    pub self_references: Vec<Dependency>,
+    /// Every copy of an edge except the one with the lowest id.
+    pub duplicates: Vec<Dependency>,
+    /// Cycles of blocking dependencies, each ending with the edge that closes it.
+    pub cycles: Vec<Vec<Dependency>>,
+}
+
+impl DependencyAudit {
+    pub fn of(mut deps: Vec<Dependency>) -> DependencyAudit {
+        deps.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut self_references = Vec::new();
+        let mut duplicates = Vec::new();
+        let mut edges: Vec<Dependency> = Vec::new();
+        let mut seen: HashSet<(String, String, i32)> = HashSet::new();
+
+        for dep in deps {
+            if dep.blocking_epic_id == dep.blocked_epic_id {
+                self_references.push(dep);
+                continue;
+            }
+            let key = (
+                dep.blocking_epic_id.clone(),
+                dep.blocked_epic_id.clone(),
+                dep.dependency_type.unwrap_or_default(),
+            );
+            if seen.insert(key) {
+                edges.push(dep);
+            } else {
+                duplicates.push(dep);
+            }
+        }
+
+        // Only blocking edges order epics, so only they can form a cycle
+        let mut graph: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (index, dep) in edges.iter().enumerate() {
+            if dep.dependency_type.unwrap_or_default() == 0 {
+                graph.entry(&dep.blocking_epic_id).or_default().push(index);
+            }
+        }
+
+        let mut visited: HashMap<&str, bool> = HashMap::new();
+        let mut path: Vec<usize> = Vec::new();
+        let mut cycle_edges: Vec<Vec<usize>> = Vec::new();
+        for epic_id in graph.keys() {
+            if !visited.contains_key(epic_id) {
+                find_cycles(epic_id, &graph, &edges, &mut visited, &mut path, &mut cycle_edges);
+            }
+        }
+
+        let cycles = cycle_edges
+            .into_iter()
+            .map(|cycle| cycle.into_iter().map(|index| edges[index].clone()).collect())
+            .collect();
+
+        DependencyAudit { self_references, duplicates, cycles }
+    }
+
+    /// Ids of the edges whose removal leaves no self-references, duplicates or cycles.
+    pub fn offending_ids(&self) -> Vec<String> {
+        self.self_references
+            .iter()
+            .chain(self.duplicates.iter())
+            .chain(self.cycles.iter().filter_map(|cycle| cycle.last()))
+            .map(|dep| dep.id.clone())
+            .collect()
+    }
+
+    /// Each cycle as the epics it passes through, starting with the blocking
+    /// epic of its first edge.
+    pub fn cycle_epic_ids(&self) -> Vec<Vec<String>> {
+        self.cycles
+            .iter()
+            .map(|cycle| cycle.iter().map(|dep| dep.blocking_epic_id.clone()).collect())
+            .collect()
+    }
+}
+
+/// Depth-first walk that records a cycle for every edge pointing back into
+/// the current path. `visited` maps an epic to whether it is still on the path.
+fn find_cycles<'a>(
+    epic_id: &'a str,
+    graph: &BTreeMap<&'a str, Vec<usize>>,
+    edges: &'a [Dependency],
+    visited: &mut HashMap<&'a str, bool>,
+    path: &mut Vec<usize>,
+    cycles: &mut Vec<Vec<usize>>,
+) {
+    visited.insert(epic_id, true);
+    for &index in graph.get(epic_id).map(|out| out.as_slice()).unwrap_or_default() {
+        let target = edges[index].blocked_epic_id.as_str();
+        match visited.get(target) {
+            None => {
+                path.push(index);
+                find_cycles(target, graph, edges, visited, path, cycles);
+                path.pop();
+            }
+            Some(true) => {
+                let start = path
+                    .iter()
+                    .position(|&step| edges[step].blocking_epic_id == target)
+                    .unwrap_or(path.len());
+                let mut cycle = path[start..].to_vec();
+                cycle.push(index);
+                cycles.push(cycle);
+            }
+            Some(false) => {}
+        }
+    }
+    visited.insert(epic_id, false);
+}
+
+#[tonic::async_trait]
+pub trait AuditDependencies {
+    async fn audit<'a>(
+        board_id: Option<&'a str>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<DependencyAudit, Error>;
+
+    async fn fix<'a>(
+        board_id: Option<&'a str>,
+        dry_run: bool,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(DependencyAudit, Vec<String>), Error>;
+}
+
+fn load_for_audit(board_id: Option<&str>, db_connection: &PgConnection) -> Result<Vec<Dependency>, Error> {
+    match board_id {
+        Some(brd_id) => {
+            let board_epics = epics::dsl::epics
+                .select(epics::dsl::id)
+                .filter(epics::dsl::column_id.eq_any(
+                    columns::dsl::columns
+                        .select(columns::dsl::id)
+                        .filter(columns::dsl::board_id.eq(brd_id))
+                ));
+            let epics_ids: Vec<String> = board_epics.load(db_connection)?;
+
+            // Only edges with both ends on the board, so that fixing one
+            // board never deletes an edge another board shares.
+            dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq_any(&epics_ids)
+                    .and(dependencies::dsl::blocked_epic_id.eq_any(&epics_ids)))
+                .load::<Dependency>(db_connection)
+        }
+        None => dependencies::dsl::dependencies.load::<Dependency>(db_connection),
+    }
+}
+
+#[tonic::async_trait]
+impl AuditDependencies for Dependency {
+    async fn audit<'a>(
+        board_id: Option<&'a str>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<DependencyAudit, Error> {
+        let deps = timed("dependency", "audit", || load_for_audit(board_id, &db_connection))?;
+
+        Ok(DependencyAudit::of(deps))
+    }
+
+    async fn fix<'a>(
+        board_id: Option<&'a str>,
+        dry_run: bool,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(DependencyAudit, Vec<String>), Error> {
+        timed("dependency", "fix", || db_connection.transaction::<_, Error, _>(|| {
+            let audit = DependencyAudit::of(load_for_audit(board_id, &db_connection)?);
+            let offending = audit.offending_ids();
+
+            if !dry_run && !offending.is_empty() {
+                delete(dependencies::dsl::dependencies)
+                    .filter(dependencies::dsl::id.eq_any(&offending))
+                    .execute(&*db_connection)?;
+            }
+
+            Ok((audit, offending))
+        }))
+    }
+}
+
+pub enum EpicScheduleError {
+    /// Epics whose blocking dependencies form a cycle, each blocking the
+    /// next one and the last blocking the first.
+    Cycle(Vec<String>),
+    Db(Error),
+}
+
+impl From<Error> for EpicScheduleError {
+    fn from(err: Error) -> Self {
+        EpicScheduleError::Db(err)
+    }
+}
+
+#[tonic::async_trait]
+pub trait ScheduleEpics {
+    async fn schedule<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Epic>, EpicScheduleError>;
+}
+
+#[tonic::async_trait]
+impl ScheduleEpics for Dependency {
+    /// The board's epics ordered so that each comes after every epic
+    /// blocking it. Epics are released in rounds, each ordered by id: first
+    /// the ones nothing blocks, then the ones blocked only by the first
+    /// round, and so on. Non-blocking dependencies and dependencies on epics
+    /// of other boards are ignored.
+    async fn schedule<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Epic>, EpicScheduleError> {
+        let (board_epics, deps) = timed("dependency", "schedule", || -> Result<_, Error> {
+            let board_epics = load_board_epics(board_id, &db_connection)?;
+            let epics_ids: Vec<&String> = board_epics.iter().map(|epic| &epic.id).collect();
+
+            let deps: Vec<Dependency> = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq_any(&epics_ids))
+                .filter(dependencies::dsl::blocked_epic_id.eq_any(&epics_ids))
+                .load(&*db_connection)?;
+
+            Ok((board_epics, deps))
+        })?;
+
+        let order = schedule_order(&board_epics, &deps).map_err(EpicScheduleError::Cycle)?;
+
+        let mut slots: Vec<Option<Epic>> = board_epics.into_iter().map(Some).collect();
+        Ok(order.into_iter().filter_map(|index| slots[index].take()).collect())
+    }
+}
+
+/// Layered topological sort of `board_epics`, which must be sorted by id.
+/// Returns indices into `board_epics`, or the ids of a cycle when the
+/// blocking dependencies do not form a DAG.
+fn schedule_order(board_epics: &[Epic], deps: &[Dependency]) -> Result<Vec<usize>, Vec<String>> {
+    let positions: HashMap<&str, usize> = board_epics
+        .iter()
+        .enumerate()
+        .map(|(index, epic)| (epic.id.as_str(), index))
+        .collect();
+
+    let mut blockers: Vec<Vec<usize>> = vec![Vec::new(); board_epics.len()];
+    let mut blocked: Vec<Vec<usize>> = vec![Vec::new(); board_epics.len()];
+    for dep in deps.iter().filter(|dep| dep.dependency_type.unwrap_or_default() == 0) {
+        if let (Some(&from), Some(&to)) = (
+            positions.get(dep.blocking_epic_id.as_str()),
+            positions.get(dep.blocked_epic_id.as_str()),
+        ) {
+            blockers[to].push(from);
+            blocked[from].push(to);
+        }
+    }
+
+    let mut pending: Vec<usize> = blockers.iter().map(|from| from.len()).collect();
+    let mut round: Vec<usize> = (0..board_epics.len()).filter(|&index| pending[index] == 0).collect();
+    let mut order: Vec<usize> = Vec::with_capacity(board_epics.len());
+    while !round.is_empty() {
+        let mut next: Vec<usize> = Vec::new();
+        for &index in &round {
+            for &to in &blocked[index] {
+                pending[to] -= 1;
+                if pending[to] == 0 {
+                    next.push(to);
+                }
+            }
+        }
+        order.append(&mut round);
+        next.sort_unstable();
+        round = next;
+    }
+
+    if order.len() == board_epics.len() {
+        return Ok(order);
+    }
+
+    // Every epic left over still has a blocker that is left over too, so
+    // following blockers from the lowest id must come back to an epic on
+    // the walk.
+    let mut walk: Vec<usize> = Vec::new();
+    let mut current = (0..board_epics.len())
+        .find(|&index| pending[index] > 0)
+        .unwrap_or_default();
+    loop {
+        if let Some(start) = walk.iter().position(|&index| index == current) {
+            let mut cycle: Vec<String> = walk[start..]
+                .iter()
+                .map(|&index| board_epics[index].id.clone())
+                .collect();
+            cycle.reverse();
+            return Err(cycle);
+        }
+        walk.push(current);
+        current = blockers[current]
+            .iter()
+            .copied()
+            .filter(|&from| pending[from] > 0)
+            .min()
+            .unwrap_or(current);
+    }
+}
+
+/// The board's epics, ordered by id.
+fn load_board_epics(board_id: &str, db_connection: &PgConnection) -> Result<Vec<Epic>, Error> {
+    epics::dsl::epics
+        .filter(epics::dsl::column_id.eq_any(
+            columns::dsl::columns
+                .select(columns::dsl::id)
+                .filter(columns::dsl::board_id.eq(board_id))
+        ))
+        .order(epics::dsl::id.asc())
+        .load(db_connection)
+}
+
+/// A blocking dependency whose blocking epic is due after its blocked epic
+/// starts.
+pub struct DateConflict {
+    pub dependency: Dependency,
+    pub blocking_due: NaiveDateTime,
+    pub blocked_start: NaiveDateTime,
+}
+
+#[tonic::async_trait]
+pub trait ValidateSchedule {
+    async fn date_conflicts<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<DateConflict>, Error>;
+}
+
+#[tonic::async_trait]
+impl ValidateSchedule for Dependency {
+    /// Checks every blocking dependency with at least one end on the board,
+    /// so an epic of another board blocking one of this board's epics is
+    /// included. Epics without the dates to compare never conflict.
+    async fn date_conflicts<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<DateConflict>, Error> {
+        let (deps, related_epics) = timed("dependency", "date_conflicts", || -> Result<_, Error> {
+            let board_epics = load_board_epics(board_id, &db_connection)?;
+            let epics_ids: Vec<&String> = board_epics.iter().map(|epic| &epic.id).collect();
+
+            // Only hard blockers constrain the schedule.
+            let deps: Vec<Dependency> = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq_any(&epics_ids)
+                    .or(dependencies::dsl::blocked_epic_id.eq_any(&epics_ids)))
+                .filter(dependencies::dsl::dependency_type.eq(0)
+                    .or(dependencies::dsl::dependency_type.is_null()))
+                .load(&*db_connection)?;
+
+            let related_epics_ids: Vec<&String> = deps
+                .iter()
+                .flat_map(|dep| [&dep.blocking_epic_id, &dep.blocked_epic_id])
+                .collect();
+            let related_epics: Vec<Epic> = epics::dsl::epics
+                .filter(epics::dsl::id.eq_any(related_epics_ids))
+                .load(&*db_connection)?;
+
+            Ok((deps, related_epics))
+        })?;
+
+        let epics_by_id: HashMap<&str, &Epic> = related_epics
+            .iter()
+            .map(|epic| (epic.id.as_str(), epic))
+            .collect();
+
+        Ok(deps
+            .into_iter()
+            .filter_map(|dep| {
+                let blocking_due = epics_by_id.get(dep.blocking_epic_id.as_str())?.due_date?;
+                let blocked_start = epics_by_id.get(dep.blocked_epic_id.as_str())?.start_date?;
+                (blocking_due > blocked_start).then_some(DateConflict { dependency: dep, blocking_due, blocked_start })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+
+    fn dep(id: &str, blocking: &str, blocked: &str) -> Dependency {
+        Dependency {
+            id: id.to_string(),
+            blocking_epic_id: blocking.to_string(),
+            blocked_epic_id: blocked.to_string(),
+            dependency_type: None,
+        }
+    }
+
+    fn epic(id: &str) -> Epic {
+        Epic {
+            id: id.to_string(),
+            column_id: String::from("column"),
+            assignee_id: None,
+            name: id.to_string(),
+            reporter_id: String::from("reporter"),
+            description: None,
+            start_date: None,
+            due_date: None,
+            updated_at: NaiveDateTime::default(),
+            created_at: NaiveDateTime::default(),
+            progress_percent: 0,
+            metadata: None,
+        }
+    }
+
+    fn ids(deps: &[Dependency]) -> Vec<&str> {
+        deps.iter().map(|dep| dep.id.as_str()).collect()
+    }
+
+    /// Serves `edges` the way the query in `blocking_path` does.
+    fn in_memory<'a>(edges: &'a [(&str, &str)]) -> impl FnMut(&[String]) -> Result<Vec<(String, String)>, Infallible> + 'a {
+        move |frontier| {
+            let mut out: Vec<(String, String)> = edges
+                .iter()
+                .filter(|(blocking, _)| frontier.iter().any(|epic_id| epic_id == blocking))
+                .map(|(blocking, blocked)| (blocking.to_string(), blocked.to_string()))
+                .collect();
+            out.sort();
+            Ok(out)
+        }
+    }
+
+    #[test]
+    fn audit_sorts_out_self_references_duplicates_and_a_two_cycle() {
+        let mut non_blocking = vec![dep("d6", "e", "f"), dep("d7", "f", "e")];
+        for dep in &mut non_blocking {
+            dep.dependency_type = Some(1);
+        }
+        let mut deps = vec![dep("d5", "a", "b"), dep("d2", "b", "a"), dep("d0", "x", "x"), dep("d1", "a", "b")];
+        deps.append(&mut non_blocking);
+
+        let audit = DependencyAudit::of(deps);
+
+        assert_eq!(ids(&audit.self_references), vec!["d0"]);
+        assert_eq!(ids(&audit.duplicates), vec!["d5"]);
+        let cycles: Vec<Vec<&str>> = audit.cycles.iter().map(|cycle| ids(cycle)).collect();
+        assert_eq!(cycles, vec![vec!["d1", "d2"]]);
+        assert_eq!(audit.cycle_epic_ids(), vec![vec![String::from("a"), String::from("b")]]);
+        assert_eq!(audit.offending_ids(), vec!["d0", "d5", "d2"]);
+    }
+
+    #[test]
+    fn audit_finds_each_of_several_disjoint_cycles() {
+        let audit = DependencyAudit::of(vec![
+            dep("d1", "a", "b"),
+            dep("d2", "b", "c"),
+            dep("d3", "c", "a"),
+            dep("d4", "x", "y"),
+            dep("d5", "y", "x"),
+            dep("d6", "c", "z"),
+        ]);
+
+        let cycles: Vec<Vec<&str>> = audit.cycles.iter().map(|cycle| ids(cycle)).collect();
+        assert_eq!(cycles, vec![vec!["d1", "d2", "d3"], vec!["d4", "d5"]]);
+        assert_eq!(audit.cycle_epic_ids(), vec![
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            vec![String::from("x"), String::from("y")],
+        ]);
+        assert_eq!(audit.offending_ids(), vec!["d3", "d5"]);
+    }
+
+    #[test]
+    fn audit_of_a_dag_is_clean() {
+        let audit = DependencyAudit::of(vec![dep("d1", "a", "b"), dep("d2", "a", "c"), dep("d3", "b", "d"), dep("d4", "c", "d")]);
+
+        assert!(audit.self_references.is_empty() && audit.duplicates.is_empty() && audit.cycles.is_empty());
+        assert!(audit.offending_ids().is_empty());
+    }
+
+    #[test]
+    fn schedule_order_releases_a_diamond_in_rounds() {
+        let board_epics: Vec<Epic> = ["a", "b", "c", "d"].into_iter().map(epic).collect();
+        let deps = [dep("d1", "d", "b"), dep("d2", "d", "c"), dep("d3", "b", "a"), dep("d4", "c", "a")];
+
+        assert_eq!(schedule_order(&board_epics, &deps), Ok(vec![3, 1, 2, 0]));
+    }
+
+    #[test]
+    fn schedule_order_reports_cycles_and_self_loops() {
+        let board_epics: Vec<Epic> = ["a", "b", "c"].into_iter().map(epic).collect();
+
+        let two_cycle = [dep("d1", "a", "b"), dep("d2", "b", "a")];
+        assert_eq!(schedule_order(&board_epics, &two_cycle), Err(vec![String::from("b"), String::from("a")]));
+
+        let self_loop = [dep("d1", "c", "c")];
+        assert_eq!(schedule_order(&board_epics, &self_loop), Err(vec![String::from("c")]));
+    }
+
+    #[test]
+    fn shortest_path_takes_the_first_shortest_chain() {
+        let edges = [("a", "d"), ("a", "b"), ("b", "c"), ("d", "c"), ("c", "e")];
+
+        let path = shortest_path("a", "e", in_memory(&edges)).unwrap();
+        assert_eq!(path, Some(vec![String::from("a"), String::from("b"), String::from("c"), String::from("e")]));
+    }
+
+    #[test]
+    fn shortest_path_is_none_when_unreachable_even_through_a_cycle() {
+        let edges = [("a", "b"), ("b", "a"), ("c", "a")];
+
+        assert_eq!(shortest_path("a", "c", in_memory(&edges)).unwrap(), None);
+        assert_eq!(shortest_path("a", "z", in_memory(&edges)).unwrap(), None);
+    }
+}
@@ -1,21 +1,35 @@
+use std::collections::{HashMap, HashSet};
 use diesel::result::Error;
 
 use crate::db;
-use db::schema::epics;
-
+use db::slow_queries::timed;
+use crate::ids::new_id;
+use db::{
+    schema::{epics, issues, dependencies},
+    repos::{
+        issue::{Issue, NewIssue, take_rank},
+        column::first_column_id,
+        dependency::{Dependency, blocking_path},
+    },
+};
 
 use diesel::{
     RunQueryDsl,
     r2d2::ConnectionManager,
     PgConnection,
     ExpressionMethods,
+    QueryDsl,
+    BoolExpressionMethods,
+    Connection,
     insert_into,
     update,
-    delete
+    delete,
+    dsl::now
 };
 use r2d2::PooledConnection;
 
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
+use serde_json::Value;
 
 #[derive(Queryable, PartialEq)]
 pub struct Epic {
@@ -25,21 +39,29 @@ pub struct Epic {
     pub name: String,
     pub reporter_id: String,
     pub description: Option<String>,
-    pub start_date: NaiveDateTime,
-    pub due_date: NaiveDateTime,
+    pub start_date: Option<NaiveDateTime>,
+    pub due_date: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub progress_percent: i32,
+    pub metadata: Option<Value>,
 }
 
 #[derive(Insertable)]
 #[table_name="epics"]
 pub struct NewEpic<'a> {
     pub id: &'a str,
-    pub column_id: &'a str,
+    /// `None` puts the epic in the first column of the board given to
+    /// `create`.
+    pub column_id: Option<&'a str>,
     pub assignee_id: Option<&'a str>,
     pub reporter_id: &'a str,
     pub name: &'a str,
     pub description: Option<&'a str>,
     pub start_date: Option<NaiveDateTime>,
     pub due_date: Option<NaiveDateTime>,
+    pub progress_percent: Option<i32>,
+    pub metadata: Option<Value>,
 }
 
 #[derive(AsChangeset)]
@@ -52,43 +74,88 @@ pub struct EpicChangeSet {
     pub description: Option<String>,
     pub start_date: Option<NaiveDateTime>,
     pub due_date: Option<NaiveDateTime>,
+    pub progress_percent: Option<i32>,
+    pub metadata: Option<Value>,
+}
+
+impl EpicChangeSet {
+    /// Names of the columns this change set would give a new value in `current`.
+    pub fn changed_fields(&self, current: &Epic) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.column_id.is_some() && self.column_id.as_ref() != Some(&current.column_id) {
+            changed.push("column_id");
+        }
+        if self.assignee_id.is_some() && self.assignee_id != current.assignee_id {
+            changed.push("assignee_id");
+        }
+        if self.name.is_some() && self.name.as_ref() != Some(&current.name) {
+            changed.push("name");
+        }
+        if self.reporter_id.is_some() && self.reporter_id.as_ref() != Some(&current.reporter_id) {
+            changed.push("reporter_id");
+        }
+        if self.description.is_some() && self.description != current.description {
+            changed.push("description");
+        }
+        if self.start_date.is_some() && self.start_date != current.start_date {
+            changed.push("start_date");
+        }
+        if self.due_date.is_some() && self.due_date != current.due_date {
+            changed.push("due_date");
+        }
+        if self.progress_percent.is_some() && self.progress_percent != Some(current.progress_percent) {
+            changed.push("progress_percent");
+        }
+        if self.metadata.is_some() && self.metadata != current.metadata {
+            changed.push("metadata");
+        }
+        changed
+    }
 }
 
 #[tonic::async_trait]
 pub trait CreateEpic {
     async fn create<'a>(
         new_epic: NewEpic<'a>,
+        board_id: Option<&'a str>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Epic, Error>;
 }
 
 #[tonic::async_trait]
 impl CreateEpic for Epic {
+    /// Without `new_epic.column_id` the epic goes to the first column of
+    /// `board_id`, looked up in the same transaction as the insert; a board
+    /// without columns, or no board either, is `Error::NotFound`.
     async fn create<'a>(
         new_epic: NewEpic<'a>,
+        board_id: Option<&'a str>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Epic, Error> {
-        let result: Vec<Epic> = match insert_into(epics::dsl::epics)
-            .values(new_epic)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
+        timed("epic", "create", || db_connection.transaction::<_, Error, _>(|| {
+            let column_id = match (new_epic.column_id, board_id) {
+                (Some(col_id), _) => col_id.to_string(),
+                (None, Some(brd_id)) => first_column_id(brd_id, &db_connection)?,
+                (None, None) => return Err(Error::NotFound),
             };
 
-        let epic: &Epic = result
-            .first()
-            .unwrap();
+            insert_into(epics::dsl::epics)
+                .values(NewEpic { column_id: Some(&column_id), ..new_epic })
+                .returning(epics::all_columns)
+                .get_result(&*db_connection)
+        }))
+    }
+}
 
-        Ok(Epic {
-            id: epic.id.clone(),
-            column_id: epic.column_id.clone(),
-            assignee_id: epic.assignee_id.clone(),
-            name: epic.name.clone(),
-            reporter_id: epic.reporter_id.clone(),
-            start_date: epic.start_date.clone(),
-            due_date: epic.due_date.clone(),
-            description: epic.description.clone(),
-        })
+pub enum UpdateEpicError {
+    /// The update would leave the due date before the start date.
+    DueBeforeStart,
+    Db(Error),
+}
+
+impl From<Error> for UpdateEpicError {
+    fn from(err: Error) -> Self {
+        UpdateEpicError::Db(err)
     }
 }
 
@@ -98,20 +165,61 @@ pub trait UpdateEpic {
         epic_id: &'a str,
         change_set: EpicChangeSet,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Epic, Error>;
+    ) -> Result<(Epic, Vec<&'static str>), UpdateEpicError>;
 }
 
 #[tonic::async_trait]
 impl UpdateEpic for Epic {
+    /// Also returns the fields the update changed, compared against the row
+    /// as locked before it. A date left out of the change set is checked
+    /// against the stored one, so the dates stay in order either way.
     async fn update<'a>(
         epic_id: &'a str,
         change_set: EpicChangeSet,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Epic, Vec<&'static str>), UpdateEpicError> {
+        timed("epic", "update", || db_connection.transaction(|| {
+            let previous: Epic = epics::dsl::epics
+                .filter(epics::dsl::id.eq(epic_id))
+                .for_update()
+                .first(&*db_connection)?;
+            let changed_fields = change_set.changed_fields(&previous);
+
+            let start = change_set.start_date.or(previous.start_date);
+            let due = change_set.due_date.or(previous.due_date);
+            if let (Some(start), Some(due)) = (start, due) {
+                if start > due {
+                    return Err(UpdateEpicError::DueBeforeStart);
+                }
+            }
+
+            let epic: Epic = update(epics::dsl::epics)
+                .filter(epics::dsl::id.eq(epic_id))
+                .set(change_set)
+                .get_result(&*db_connection)?;
+
+            Ok((epic, changed_fields))
+        }))
+    }
+}
+
+#[tonic::async_trait]
+pub trait DeleteEpic {
+    async fn delete<'a>(
+        epic_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Epic, Error>;
+}
+
+#[tonic::async_trait]
+impl DeleteEpic for Epic {
+    async fn delete<'a>(
+        epic_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Epic, Error> {
-        let result: Vec<Epic> = match update(epics::dsl::epics)
+        let result: Vec<Epic> = match timed("epic", "delete", || delete(epics::dsl::epics)
             .filter(epics::dsl::id.eq(epic_id))
-            .set(change_set)
-            .get_results(&*db_connection) {
+            .get_results(&*db_connection)) {
                 Ok(res) => res,
                 Err(err) => return Err(err),
             };
@@ -127,30 +235,35 @@ impl UpdateEpic for Epic {
             assignee_id: epic.assignee_id.clone(),
             name: epic.name.clone(),
             reporter_id: epic.reporter_id.clone(),
-            start_date: epic.start_date.clone(),
-            due_date: epic.due_date.clone(),
+            start_date: epic.start_date,
+            due_date: epic.due_date,
             description: epic.description.clone(),
+            updated_at: epic.updated_at,
+            created_at: epic.created_at,
+            progress_percent: epic.progress_percent,
+            metadata: epic.metadata.clone(),
         })
     }
 }
 
 #[tonic::async_trait]
-pub trait DeleteEpic {
-    async fn delete<'a>(
+pub trait TouchEpic {
+    async fn touch<'a>(
         epic_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Epic, Error>;
 }
 
 #[tonic::async_trait]
-impl DeleteEpic for Epic {
-    async fn delete<'a>(
+impl TouchEpic for Epic {
+    async fn touch<'a>(
         epic_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Epic, Error> {
-        let result: Vec<Epic> = match delete(epics::dsl::epics)
+        let result: Vec<Epic> = match timed("epic", "touch", || update(epics::dsl::epics)
             .filter(epics::dsl::id.eq(epic_id))
-            .get_results(&*db_connection) {
+            .set(epics::dsl::updated_at.eq(now))
+            .get_results(&*db_connection)) {
                 Ok(res) => res,
                 Err(err) => return Err(err),
             };
@@ -166,9 +279,448 @@ impl DeleteEpic for Epic {
             assignee_id: epic.assignee_id.clone(),
             name: epic.name.clone(),
             reporter_id: epic.reporter_id.clone(),
-            start_date: epic.start_date.clone(),
-            due_date: epic.due_date.clone(),
+            start_date: epic.start_date,
+            due_date: epic.due_date,
             description: epic.description.clone(),
+            updated_at: epic.updated_at,
+            created_at: epic.created_at,
+            progress_percent: epic.progress_percent,
+            metadata: epic.metadata.clone(),
+        })
+    }
+}
+
+/// Fields that replace the source epic's values when cloning it.
+pub struct EpicOverrides {
+    pub column_id: Option<String>,
+    pub assignee_id: Option<String>,
+    pub name: Option<String>,
+    pub start_date: Option<NaiveDateTime>,
+    pub due_date: Option<NaiveDateTime>,
+}
+
+#[tonic::async_trait]
+pub trait CloneEpic {
+    async fn clone_epic<'a>(
+        epic_id: &'a str,
+        overrides: EpicOverrides,
+        include_issues: bool,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Epic, Vec<Issue>), Error>;
+}
+
+#[tonic::async_trait]
+impl CloneEpic for Epic {
+    /// Copies the epic under a new id. With `include_issues` its issues are
+    /// copied too and point at the new epic; they go to the overriding
+    /// column when there is one and keep their own columns otherwise, ranked
+    /// after the issues already there.
+    async fn clone_epic<'a>(
+        epic_id: &'a str,
+        overrides: EpicOverrides,
+        include_issues: bool,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Epic, Vec<Issue>), Error> {
+        timed("epic", "clone_epic", || db_connection.transaction::<_, Error, _>(|| {
+            let source: Epic = epics::dsl::epics
+                .filter(epics::dsl::id.eq(epic_id))
+                .first(&*db_connection)?;
+
+            let cloned: Epic = insert_into(epics::dsl::epics)
+                .values(NewEpic {
+                    id: &new_id(),
+                    column_id: Some(overrides.column_id.as_deref().unwrap_or(&source.column_id)),
+                    assignee_id: overrides.assignee_id.as_deref().or(source.assignee_id.as_deref()),
+                    reporter_id: &source.reporter_id,
+                    name: overrides.name.as_deref().unwrap_or(&source.name),
+                    description: source.description.as_deref(),
+                    start_date: overrides.start_date.or(source.start_date),
+                    due_date: overrides.due_date.or(source.due_date),
+                    progress_percent: Some(source.progress_percent),
+                    metadata: source.metadata.clone(),
+                })
+                .get_result(&*db_connection)?;
+
+            if !include_issues {
+                return Ok((cloned, Vec::new()));
+            }
+
+            let source_issues: Vec<Issue> = issues::dsl::issues
+                .filter(issues::dsl::epic_id.eq(epic_id))
+                .order((issues::dsl::rank.asc(), issues::dsl::id.asc()))
+                .load(&*db_connection)?;
+
+            let mut next_ranks: HashMap<String, i32> = HashMap::new();
+            let mut cloned_issues: Vec<Issue> = Vec::with_capacity(source_issues.len());
+            for issue in &source_issues {
+                let column_id = overrides.column_id.as_deref().unwrap_or(&issue.column_id);
+                let rank = take_rank(&mut next_ranks, column_id, &db_connection)?;
+                let cloned_issue: Issue = insert_into(issues::dsl::issues)
+                    .values((NewIssue {
+                        id: &new_id(),
+                        column_id: Some(column_id),
+                        epic_id: &cloned.id,
+                        title: &issue.title,
+                        description: &issue.description,
+                    }, issues::dsl::rank.eq(rank)))
+                    .get_result(&*db_connection)?;
+                cloned_issues.push(cloned_issue);
+            }
+
+            Ok((cloned, cloned_issues))
+        }))
+    }
+}
+
+pub enum ShiftEpicDatesError {
+    /// The shifted dates of this epic would not be representable.
+    OutOfRange(String),
+    /// This epic's due date would precede its start date.
+    DueBeforeStart(String),
+    Db(Error),
+}
+
+impl From<Error> for ShiftEpicDatesError {
+    fn from(err: Error) -> Self {
+        ShiftEpicDatesError::Db(err)
+    }
+}
+
+#[tonic::async_trait]
+pub trait ShiftEpicDates {
+    async fn shift_dates<'a>(
+        epic_ids: &'a [String],
+        delta: Duration,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Epic>, ShiftEpicDatesError>;
+}
+
+#[tonic::async_trait]
+impl ShiftEpicDates for Epic {
+    /// Moves the start and due dates of every listed epic by `delta`, keeping
+    /// their durations; unset dates stay unset. Either all epics are shifted
+    /// or none is, and a missing id fails the whole call with `NotFound`.
+    /// Returns the shifted epics ordered by id.
+    async fn shift_dates<'a>(
+        epic_ids: &'a [String],
+        delta: Duration,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Epic>, ShiftEpicDatesError> {
+        timed("epic", "shift_dates", || db_connection.transaction::<_, ShiftEpicDatesError, _>(|| {
+            let mut requested_ids: Vec<&String> = epic_ids.iter().collect();
+            requested_ids.sort();
+            requested_ids.dedup();
+
+            let current: Vec<Epic> = epics::dsl::epics
+                .filter(epics::dsl::id.eq_any(epic_ids))
+                .order(epics::dsl::id.asc())
+                .for_update()
+                .load(&*db_connection)?;
+
+            if current.len() != requested_ids.len() {
+                return Err(ShiftEpicDatesError::Db(Error::NotFound));
+            }
+
+            let mut shifted: Vec<Epic> = Vec::with_capacity(current.len());
+            for epic in &current {
+                let shift = |date: Option<NaiveDateTime>| match date {
+                    Some(date) => date.checked_add_signed(delta)
+                        .map(Some)
+                        .ok_or_else(|| ShiftEpicDatesError::OutOfRange(epic.id.clone())),
+                    None => Ok(None),
+                };
+                let start = shift(epic.start_date)?;
+                let due = shift(epic.due_date)?;
+
+                if let (Some(start), Some(due)) = (start, due) {
+                    if due < start {
+                        return Err(ShiftEpicDatesError::DueBeforeStart(epic.id.clone()));
+                    }
+                }
+
+                let updated: Epic = update(epics::dsl::epics)
+                    .filter(epics::dsl::id.eq(&epic.id))
+                    .set((epics::dsl::start_date.eq(start), epics::dsl::due_date.eq(due)))
+                    .get_result(&*db_connection)?;
+                shifted.push(updated);
+            }
+
+            Ok(shifted)
+        }))
+    }
+}
+
+/// What still refers to an epic. The tables have no foreign keys, so
+/// deleting an epic with any of these would leave them dangling.
+pub struct EpicReferences {
+    pub issues: i64,
+    pub dependencies: i64,
+}
+
+#[tonic::async_trait]
+pub trait CountEpicReferences {
+    async fn count_references<'a>(
+        epic_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<EpicReferences, Error>;
+}
+
+#[tonic::async_trait]
+impl CountEpicReferences for Epic {
+    /// Fails with `NotFound` when the epic does not exist.
+    async fn count_references<'a>(
+        epic_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<EpicReferences, Error> {
+        timed("epic", "count_references", || {
+            epics::dsl::epics
+                .filter(epics::dsl::id.eq(epic_id))
+                .select(epics::dsl::id)
+                .first::<String>(&*db_connection)?;
+
+            let epic_issues: i64 = issues::dsl::issues
+                .filter(issues::dsl::epic_id.eq(epic_id))
+                .count()
+                .get_result(&*db_connection)?;
+
+            let epic_dependencies: i64 = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq(epic_id)
+                    .or(dependencies::dsl::blocked_epic_id.eq(epic_id)))
+                .count()
+                .get_result(&*db_connection)?;
+
+            Ok(EpicReferences {
+                issues: epic_issues,
+                dependencies: epic_dependencies,
+            })
         })
     }
-}
\ No newline at end of file
+}
+
+pub enum MergeEpicsError {
+    /// The source and the target are the same epic.
+    SameEpic,
+    /// The moved blocking dependencies would form this cycle.
+    Cycle(Vec<String>),
+    Db(Error),
+}
+
+impl From<Error> for MergeEpicsError {
+    fn from(err: Error) -> Self {
+        MergeEpicsError::Db(err)
+    }
+}
+
+/// The outcome of merging one epic into another.
+pub struct MergedEpics {
+    pub target: Epic,
+    pub source: Epic,
+    pub moved_issues: Vec<Issue>,
+    pub moved_dependencies: usize,
+    /// Dependencies deleted because they would have pointed from the target
+    /// to itself or duplicated one the target already had.
+    pub dropped_dependencies: usize,
+}
+
+#[tonic::async_trait]
+pub trait MergeEpics {
+    async fn merge<'a>(
+        source_id: &'a str,
+        target_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<MergedEpics, MergeEpicsError>;
+}
+
+#[tonic::async_trait]
+impl MergeEpics for Epic {
+    /// Moves the source epic's issues and dependencies to the target and
+    /// deletes the source, all or nothing. Runs serializable so that
+    /// dependencies created meanwhile cannot slip past the cycle check.
+    async fn merge<'a>(
+        source_id: &'a str,
+        target_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<MergedEpics, MergeEpicsError> {
+        if source_id == target_id {
+            return Err(MergeEpicsError::SameEpic);
+        }
+
+        timed("epic", "merge", || db_connection.build_transaction().serializable().run(|| {
+            let locked: Vec<Epic> = epics::dsl::epics
+                .filter(epics::dsl::id.eq_any([source_id, target_id]))
+                .order(epics::dsl::id.asc())
+                .for_update()
+                .load(&*db_connection)?;
+            if locked.len() != 2 {
+                return Err(MergeEpicsError::Db(Error::NotFound));
+            }
+
+            let moved_issues: Vec<Issue> = update(issues::dsl::issues)
+                .filter(issues::dsl::epic_id.eq(source_id))
+                .set((issues::dsl::epic_id.eq(target_id), issues::dsl::updated_at.eq(now)))
+                .get_results(&*db_connection)?;
+
+            let source_dependencies: Vec<Dependency> = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq(source_id)
+                    .or(dependencies::dsl::blocked_epic_id.eq(source_id)))
+                .order(dependencies::dsl::id.asc())
+                .load(&*db_connection)?;
+
+            // NULL and 0 both mean BLOCKS.
+            let edge = |dep: &Dependency| (
+                dep.blocking_epic_id.clone(),
+                dep.blocked_epic_id.clone(),
+                dep.dependency_type.unwrap_or(0),
+            );
+            let mut target_edges: HashSet<(String, String, i32)> = dependencies::dsl::dependencies
+                .filter(dependencies::dsl::blocking_epic_id.eq(target_id)
+                    .or(dependencies::dsl::blocked_epic_id.eq(target_id)))
+                .load::<Dependency>(&*db_connection)?
+                .iter()
+                .map(edge)
+                .collect();
+
+            let repoint = |epic_id: &str| if epic_id == source_id { target_id.to_string() } else { epic_id.to_string() };
+            let mut moved_blocking: Vec<(String, String)> = Vec::new();
+            let mut dropped_dependencies = 0;
+            for dep in &source_dependencies {
+                let blocking = repoint(&dep.blocking_epic_id);
+                let blocked = repoint(&dep.blocked_epic_id);
+                let moved_edge = (blocking.clone(), blocked.clone(), dep.dependency_type.unwrap_or(0));
+
+                if blocking == blocked || !target_edges.insert(moved_edge) {
+                    delete(dependencies::dsl::dependencies)
+                        .filter(dependencies::dsl::id.eq(&dep.id))
+                        .execute(&*db_connection)?;
+                    dropped_dependencies += 1;
+                    continue;
+                }
+
+                update(dependencies::dsl::dependencies)
+                    .filter(dependencies::dsl::id.eq(&dep.id))
+                    .set((
+                        dependencies::dsl::blocking_epic_id.eq(&blocking),
+                        dependencies::dsl::blocked_epic_id.eq(&blocked),
+                    ))
+                    .execute(&*db_connection)?;
+                if dep.dependency_type.unwrap_or(0) == 0 {
+                    moved_blocking.push((blocking, blocked));
+                }
+            }
+
+            // Any new cycle runs through one of the moved blocking edges.
+            for (blocking, blocked) in &moved_blocking {
+                if let Some(path) = blocking_path(blocked, blocking, &db_connection)? {
+                    return Err(MergeEpicsError::Cycle(path));
+                }
+            }
+
+            let source: Epic = delete(epics::dsl::epics)
+                .filter(epics::dsl::id.eq(source_id))
+                .get_result(&*db_connection)?;
+
+            let target: Epic = update(epics::dsl::epics)
+                .filter(epics::dsl::id.eq(target_id))
+                .set(epics::dsl::updated_at.eq(now))
+                .get_result(&*db_connection)?;
+
+            Ok(MergedEpics {
+                target,
+                source,
+                moved_issues,
+                moved_dependencies: source_dependencies.len() - dropped_dependencies,
+                dropped_dependencies,
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use db::connection::test_pool;
+    use db::repos::issue::CreateIssue;
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn clone_into_other_column_moves_issues_and_keeps_dates() {
+        let pool = test_pool();
+        let (source_column, target_column) = (new_id(), new_id());
+        let start = DateTime::from_timestamp(1_657_000_000, 123_456_000).unwrap().naive_utc();
+
+        let epic = Epic::create(NewEpic {
+            id: &new_id(),
+            column_id: Some(&source_column),
+            assignee_id: None,
+            reporter_id: "reporter",
+            name: "epic",
+            description: None,
+            start_date: Some(start),
+            due_date: None,
+            progress_percent: None,
+            metadata: None,
+        }, None, pool.get().unwrap()).await.unwrap();
+        Issue::create(NewIssue {
+            id: &new_id(),
+            column_id: Some(&source_column),
+            epic_id: &epic.id,
+            title: "issue",
+            description: "",
+        }, None, pool.get().unwrap()).await.unwrap();
+
+        let overrides = EpicOverrides {
+            column_id: Some(target_column.clone()),
+            assignee_id: None,
+            name: None,
+            start_date: None,
+            due_date: None,
+        };
+        let (cloned, cloned_issues) = Epic::clone_epic(&epic.id, overrides, true, pool.get().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(cloned.column_id, target_column);
+        assert_eq!(cloned.start_date, Some(start));
+        assert_eq!(cloned_issues.len(), 1);
+        assert!(cloned_issues.iter().all(|issue| issue.column_id == target_column));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn update_allows_start_on_the_due_date_but_not_after() {
+        let pool = test_pool();
+        let due = DateTime::from_timestamp(1_657_000_000, 0).unwrap().naive_utc();
+
+        let epic = Epic::create(NewEpic {
+            id: &new_id(),
+            column_id: Some(&new_id()),
+            assignee_id: None,
+            reporter_id: "reporter",
+            name: "epic",
+            description: None,
+            start_date: None,
+            due_date: Some(due),
+            progress_percent: None,
+            metadata: None,
+        }, None, pool.get().unwrap()).await.unwrap();
+
+        let start_on = |start| EpicChangeSet {
+            column_id: None,
+            assignee_id: None,
+            name: None,
+            reporter_id: None,
+            description: None,
+            start_date: Some(start),
+            due_date: None,
+            progress_percent: None,
+            metadata: None,
+        };
+
+        let result = Epic::update(&epic.id, start_on(due), pool.get().unwrap()).await;
+        assert!(matches!(result, Ok((ref updated, _)) if updated.start_date == Some(due)));
+
+        let result = Epic::update(&epic.id, start_on(due + Duration::seconds(1)), pool.get().unwrap()).await;
+        assert!(matches!(result, Err(UpdateEpicError::DueBeforeStart)));
+    }
+}
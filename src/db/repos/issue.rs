@@ -1,19 +1,32 @@
+use std::collections::HashMap;
 use diesel::result::Error;
 
 use crate::db;
-use db::schema::issues;
+use db::slow_queries::timed;
+use crate::ids::new_id;
+use db::schema::{issues, issue_moves, outbox, columns};
+use db::repos::{column::first_column_id, issue_move::NewIssueMove, outbox::NewOutboxEvent};
 
 use diesel::{
     RunQueryDsl,
+    QueryDsl,
+    Connection,
     r2d2::ConnectionManager,
     PgConnection,
     ExpressionMethods,
+    OptionalExtension,
     insert_into,
     update,
-    delete
+    delete,
+    sql_query,
+    dsl::now,
+    expression::dsl::max,
+    sql_types::{Text, SmallInt, BigInt},
 };
 use r2d2::PooledConnection;
 
+use chrono::NaiveDateTime;
+
 #[derive(Queryable)]
 pub struct Issue {
     pub id: String,
@@ -21,82 +34,413 @@ pub struct Issue {
     pub epic_id: String,
     pub title: String,
     pub description: String,
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    /// An `IssueStatus` value; new issues start as open.
+    pub status: i16,
+    /// Order of the issue within its column, lowest first.
+    pub rank: i32,
 }
 
 #[derive(Insertable)]
 #[table_name="issues"]
 pub struct NewIssue<'a> {
     pub id: &'a str,
-    pub column_id: &'a str,
+    /// `None` puts the issue in the first column of the board given to
+    /// `create`.
+    pub column_id: Option<&'a str>,
     pub epic_id: &'a str,
     pub title: &'a str,
     pub description: &'a str,
 }
 
-#[derive(AsChangeset)]
+#[derive(AsChangeset, Default)]
 #[table_name="issues"]
 pub struct IssueChangeSet {
     pub column_id: Option<String>,
     pub epic_id: Option<String>,
     pub title: Option<String>,
     pub description: Option<String>,
+    pub status: Option<i16>,
+}
+
+impl IssueChangeSet {
+    /// Names of the columns this change set would give a new value in `current`.
+    pub fn changed_fields(&self, current: &Issue) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.column_id.is_some() && self.column_id.as_ref() != Some(&current.column_id) {
+            changed.push("column_id");
+        }
+        if self.epic_id.is_some() && self.epic_id.as_ref() != Some(&current.epic_id) {
+            changed.push("epic_id");
+        }
+        if self.title.is_some() && self.title.as_ref() != Some(&current.title) {
+            changed.push("title");
+        }
+        if self.description.is_some() && self.description.as_ref() != Some(&current.description) {
+            changed.push("description");
+        }
+        if self.status.is_some() && self.status != Some(current.status) {
+            changed.push("status");
+        }
+        changed
+    }
+}
+
+/// The rank after the last issue of the column, 0 for an empty one. Takes
+/// the column's advisory lock first so that concurrent inserts and moves
+/// into the column cannot get the same rank.
+pub(crate) fn next_rank(column_id: &str, db_connection: &PgConnection) -> Result<i32, Error> {
+    sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind::<Text, _>(column_id)
+        .execute(db_connection)?;
+
+    let last: Option<i32> = issues::dsl::issues
+        .filter(issues::dsl::column_id.eq(column_id))
+        .select(max(issues::dsl::rank))
+        .first(db_connection)?;
+    Ok(last.map_or(0, |rank| rank + 1))
+}
+
+/// `next_rank` for several issues going into the same columns: looked up
+/// the first time a column comes up and counted on from there.
+pub(crate) fn take_rank(
+    next_ranks: &mut HashMap<String, i32>,
+    column_id: &str,
+    db_connection: &PgConnection,
+) -> Result<i32, Error> {
+    let next = match next_ranks.get(column_id) {
+        Some(next) => *next,
+        None => next_rank(column_id, db_connection)?,
+    };
+    next_ranks.insert(column_id.to_string(), next + 1);
+    Ok(next)
 }
 
 #[tonic::async_trait]
 pub trait CreateIssue {
     async fn create<'a>(
         new_issue: NewIssue<'a>,
+        board_id: Option<&'a str>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Issue, Error>;
 }
 
 #[tonic::async_trait]
 impl CreateIssue for Issue {
+    /// Without `new_issue.column_id` the issue goes to the first column of
+    /// `board_id`, looked up in the same transaction as the insert; a board
+    /// without columns, or no board either, is `Error::NotFound`. The issue
+    /// is ranked last in its column.
     async fn create<'a>(
         new_issue: NewIssue<'a>,
+        board_id: Option<&'a str>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Issue, Error> {
-        let result: Vec<Issue> = match insert_into(issues::dsl::issues)
-            .values(new_issue)
-            .get_results(&*db_connection) {
-                Ok(res) => res,
-                Err(err) => return Err(err),
+        timed("issue", "create", || db_connection.transaction::<_, Error, _>(|| {
+            let column_id = match (new_issue.column_id, board_id) {
+                (Some(col_id), _) => col_id.to_string(),
+                (None, Some(brd_id)) => first_column_id(brd_id, &db_connection)?,
+                (None, None) => return Err(Error::NotFound),
             };
 
-        let issue: &Issue = result
-            .first()
-            .unwrap();
+            let rank = next_rank(&column_id, &db_connection)?;
+            insert_into(issues::dsl::issues)
+                .values((NewIssue { column_id: Some(&column_id), ..new_issue }, issues::dsl::rank.eq(rank)))
+                .returning(issues::all_columns)
+                .get_result(&*db_connection)
+        }))
+    }
+}
 
-        Ok(Issue {
-            id: issue.id.clone(),
-            column_id: issue.column_id.clone(),
-            epic_id: issue.epic_id.clone(),
-            title: issue.title.clone(),
-            description: issue.description.clone(),
-        })
+#[tonic::async_trait]
+pub trait CreateIssues {
+    async fn create_all<'a>(
+        new_issues: Vec<(NewIssue<'a>, Option<&'a str>)>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Issue>, Error>;
+}
+
+#[tonic::async_trait]
+impl CreateIssues for Issue {
+    /// `create` for several issues at once, each with its own optional
+    /// board, inserted with a single statement: either all of them are
+    /// created or none is. Returns the issues in the order given.
+    async fn create_all<'a>(
+        new_issues: Vec<(NewIssue<'a>, Option<&'a str>)>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<Issue>, Error> {
+        timed("issue", "create_all", || db_connection.transaction::<_, Error, _>(|| {
+            let mut first_columns: HashMap<&str, String> = HashMap::new();
+            let mut column_ids = Vec::with_capacity(new_issues.len());
+            for (new_issue, board_id) in &new_issues {
+                let column_id = match (new_issue.column_id, board_id) {
+                    (Some(col_id), _) => col_id.to_string(),
+                    (None, Some(brd_id)) => match first_columns.get(brd_id) {
+                        Some(col_id) => col_id.clone(),
+                        None => {
+                            let col_id = first_column_id(brd_id, &db_connection)?;
+                            first_columns.insert(brd_id, col_id.clone());
+                            col_id
+                        }
+                    },
+                    (None, None) => return Err(Error::NotFound),
+                };
+                column_ids.push(column_id);
+            }
+
+            let mut next_ranks: HashMap<String, i32> = HashMap::new();
+            let mut rows = Vec::with_capacity(new_issues.len());
+            for ((new_issue, _), column_id) in new_issues.iter().zip(&column_ids) {
+                let rank = take_rank(&mut next_ranks, column_id, &db_connection)?;
+                rows.push((NewIssue { column_id: Some(column_id), ..*new_issue }, issues::dsl::rank.eq(rank)));
+            }
+
+            let mut created: Vec<Issue> = insert_into(issues::dsl::issues)
+                .values(&rows)
+                .returning(issues::all_columns)
+                .get_results(&*db_connection)?;
+
+            // RETURNING does not promise the order of the VALUES list.
+            let positions: HashMap<&str, usize> = rows
+                .iter()
+                .enumerate()
+                .map(|(position, (row, _))| (row.id, position))
+                .collect();
+            created.sort_by_key(|issue| positions.get(issue.id.as_str()).copied());
+            Ok(created)
+        }))
     }
 }
 
 #[tonic::async_trait]
 pub trait UpdateIssue {
-    async fn update<'a>(
+    async fn update<'a, E>(
         issue_id: &'a str,
         change_set: IssueChangeSet,
+        outbox_event: Option<E>,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
-    ) -> Result<Issue, Error>;
+    ) -> Result<(Issue, Vec<&'static str>), Error>
+    where
+        E: FnOnce(&Issue, &[&'static str]) -> NewOutboxEvent + Send + 'a;
 }
 
 #[tonic::async_trait]
 impl UpdateIssue for Issue {
-    async fn update<'a>(
+    /// Records an `issue_moves` row when the column changes and, when
+    /// `outbox_event` is given, the event built from the updated row, both in
+    /// the same transaction as the update itself. Also returns the fields the
+    /// update changed, compared against the row as locked before it.
+    async fn update<'a, E>(
         issue_id: &'a str,
         change_set: IssueChangeSet,
+        outbox_event: Option<E>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Issue, Vec<&'static str>), Error>
+    where
+        E: FnOnce(&Issue, &[&'static str]) -> NewOutboxEvent + Send + 'a,
+    {
+        timed("issue", "update", || db_connection.transaction::<_, Error, _>(|| {
+            update_locked(issue_id, change_set, outbox_event, &db_connection)
+        }))
+    }
+}
+
+/// The body of `UpdateIssue::update`, for use inside a transaction.
+fn update_locked<E>(
+    issue_id: &str,
+    change_set: IssueChangeSet,
+    outbox_event: Option<E>,
+    db_connection: &PgConnection,
+) -> Result<(Issue, Vec<&'static str>), Error>
+where
+    E: FnOnce(&Issue, &[&'static str]) -> NewOutboxEvent,
+{
+    let previous: Issue = issues::dsl::issues
+        .filter(issues::dsl::id.eq(issue_id))
+        .for_update()
+        .first(db_connection)?;
+    let changed_fields = change_set.changed_fields(&previous);
+
+    let mut issue: Issue = update(issues::dsl::issues)
+        .filter(issues::dsl::id.eq(issue_id))
+        .set(change_set)
+        .get_result(db_connection)?;
+
+    if issue.column_id != previous.column_id {
+        issue = update(issues::dsl::issues)
+            .filter(issues::dsl::id.eq(issue_id))
+            .set(issues::dsl::rank.eq(next_rank(&issue.column_id, db_connection)?))
+            .get_result(db_connection)?;
+        insert_into(issue_moves::dsl::issue_moves)
+            .values(NewIssueMove {
+                id: &new_id(),
+                issue_id,
+                from_column: &previous.column_id,
+                to_column: &issue.column_id,
+            })
+            .execute(db_connection)?;
+    }
+
+    if let Some(build_event) = outbox_event {
+        insert_into(outbox::dsl::outbox)
+            .values(build_event(&issue, &changed_fields))
+            .execute(db_connection)?;
+    }
+
+    Ok((issue, changed_fields))
+}
+
+pub enum MoveIssueError {
+    /// The target column does not exist.
+    ColumnNotFound,
+    Db(Error),
+}
+
+impl From<Error> for MoveIssueError {
+    fn from(err: Error) -> Self {
+        MoveIssueError::Db(err)
+    }
+}
+
+#[tonic::async_trait]
+pub trait MoveIssue {
+    async fn move_to<'a, E>(
+        issue_id: &'a str,
+        target_column_id: &'a str,
+        outbox_event: Option<E>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Issue, Vec<&'static str>), MoveIssueError>
+    where
+        E: FnOnce(&Issue, &[&'static str]) -> NewOutboxEvent + Send + 'a;
+}
+
+#[tonic::async_trait]
+impl MoveIssue for Issue {
+    /// `UpdateIssue::update` of the column alone. `issues.column_id` has no
+    /// foreign key, so the target column is share-locked in the same
+    /// transaction to keep it from being deleted before the move commits.
+    async fn move_to<'a, E>(
+        issue_id: &'a str,
+        target_column_id: &'a str,
+        outbox_event: Option<E>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(Issue, Vec<&'static str>), MoveIssueError>
+    where
+        E: FnOnce(&Issue, &[&'static str]) -> NewOutboxEvent + Send + 'a,
+    {
+        timed("issue", "move", || db_connection.transaction::<_, MoveIssueError, _>(|| {
+            columns::dsl::columns
+                .filter(columns::dsl::id.eq(target_column_id))
+                .select(columns::dsl::id)
+                .for_share()
+                .first::<String>(&*db_connection)
+                .optional()?
+                .ok_or(MoveIssueError::ColumnNotFound)?;
+
+            let change_set = IssueChangeSet {
+                column_id: Some(target_column_id.to_string()),
+                ..Default::default()
+            };
+            Ok(update_locked(issue_id, change_set, outbox_event, &db_connection)?)
+        }))
+    }
+}
+
+/// What `transition_all` did with one of the listed issues.
+pub enum TransitionOutcome {
+    /// The issue now has the target status.
+    Transitioned(Issue),
+    /// The issue already had the target status and was left untouched.
+    Unchanged(Issue),
+    /// Moving the issue out of its current status is not allowed.
+    NotAllowed(Issue),
+    NotFound,
+}
+
+#[tonic::async_trait]
+pub trait TransitionIssues {
+    async fn transition_all<'a, A, E>(
+        issues_ids: &'a [String],
+        target_status: i16,
+        allowed: A,
+        outbox_event: Option<E>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<TransitionOutcome>, Error>
+    where
+        A: Fn(i16) -> bool + Send + 'a,
+        E: Fn(&Issue, &[&'static str]) -> NewOutboxEvent + Send + 'a;
+}
+
+#[tonic::async_trait]
+impl TransitionIssues for Issue {
+    /// Gives every listed issue whose current status passes `allowed` the
+    /// target status, each like `UpdateIssue::update` of the status alone and
+    /// all in one transaction. The issues are locked up front in id order so
+    /// that overlapping calls cannot deadlock. Returns one outcome per id, in
+    /// the order given; the ids must be distinct.
+    async fn transition_all<'a, A, E>(
+        issues_ids: &'a [String],
+        target_status: i16,
+        allowed: A,
+        outbox_event: Option<E>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<TransitionOutcome>, Error>
+    where
+        A: Fn(i16) -> bool + Send + 'a,
+        E: Fn(&Issue, &[&'static str]) -> NewOutboxEvent + Send + 'a,
+    {
+        timed("issue", "transition_all", || db_connection.transaction::<_, Error, _>(|| {
+            let locked: Vec<Issue> = issues::dsl::issues
+                .filter(issues::dsl::id.eq_any(issues_ids))
+                .order(issues::dsl::id.asc())
+                .for_update()
+                .load(&*db_connection)?;
+            let mut by_id: HashMap<String, Issue> = locked
+                .into_iter()
+                .map(|issue| (issue.id.clone(), issue))
+                .collect();
+
+            let mut outcomes = Vec::with_capacity(issues_ids.len());
+            for issue_id in issues_ids {
+                let outcome = match by_id.remove(issue_id) {
+                    None => TransitionOutcome::NotFound,
+                    Some(issue) if issue.status == target_status => TransitionOutcome::Unchanged(issue),
+                    Some(issue) if !allowed(issue.status) => TransitionOutcome::NotAllowed(issue),
+                    Some(_) => {
+                        let change_set = IssueChangeSet {
+                            status: Some(target_status),
+                            ..Default::default()
+                        };
+                        let (issue, _) = update_locked(issue_id, change_set, outbox_event.as_ref(), &db_connection)?;
+                        TransitionOutcome::Transitioned(issue)
+                    }
+                };
+                outcomes.push(outcome);
+            }
+
+            Ok(outcomes)
+        }))
+    }
+}
+
+#[tonic::async_trait]
+pub trait DeleteIssue {
+    async fn delete<'a>(
+        issue_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Issue, Error>;
+}
+
+#[tonic::async_trait]
+impl DeleteIssue for Issue {
+    async fn delete<'a>(
+        issue_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Issue, Error> {
-        let result: Vec<Issue> = match update(issues::dsl::issues)
+        let result: Vec<Issue> = match timed("issue", "delete", || delete(issues::dsl::issues)
             .filter(issues::dsl::id.eq(issue_id))
-            .set(change_set)
-            .get_results(&*db_connection) {
+            .get_results(&*db_connection)) {
                 Ok(res) => res,
                 Err(err) => return Err(err),
             };
@@ -112,27 +456,32 @@ impl UpdateIssue for Issue {
             epic_id: issue.epic_id.clone(),
             title: issue.title.clone(),
             description: issue.description.clone(),
+            updated_at: issue.updated_at,
+            created_at: issue.created_at,
+            status: issue.status,
+            rank: issue.rank,
         })
     }
 }
 
 #[tonic::async_trait]
-pub trait DeleteIssue {
-    async fn delete<'a>(
+pub trait TouchIssue {
+    async fn touch<'a>(
         issue_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Issue, Error>;
 }
 
 #[tonic::async_trait]
-impl DeleteIssue for Issue {
-    async fn delete<'a>(
+impl TouchIssue for Issue {
+    async fn touch<'a>(
         issue_id: &'a str,
         db_connection: PooledConnection<ConnectionManager<PgConnection>>
     ) -> Result<Issue, Error> {
-        let result: Vec<Issue> = match delete(issues::dsl::issues)
+        let result: Vec<Issue> = match timed("issue", "touch", || update(issues::dsl::issues)
             .filter(issues::dsl::id.eq(issue_id))
-            .get_results(&*db_connection) {
+            .set(issues::dsl::updated_at.eq(now))
+            .get_results(&*db_connection)) {
                 Ok(res) => res,
                 Err(err) => return Err(err),
             };
@@ -148,6 +497,128 @@ impl DeleteIssue for Issue {
             epic_id: issue.epic_id.clone(),
             title: issue.title.clone(),
             description: issue.description.clone(),
+            updated_at: issue.updated_at,
+            created_at: issue.created_at,
+            status: issue.status,
+            rank: issue.rank,
         })
     }
-}
\ No newline at end of file
+}
+
+#[derive(QueryableByName)]
+struct StatusCountRow {
+    #[sql_type="SmallInt"]
+    status: i16,
+    #[sql_type="BigInt"]
+    count: i64,
+}
+
+#[tonic::async_trait]
+pub trait CountIssuesByStatus {
+    async fn count_by_status<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<(i16, i64)>, Error>;
+}
+
+#[tonic::async_trait]
+impl CountIssuesByStatus for Issue {
+    /// How many issues in the columns of the board have each status, lowest
+    /// status first. Statuses no issue has are left out.
+    async fn count_by_status<'a>(
+        board_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<(i16, i64)>, Error> {
+        let rows: Vec<StatusCountRow> = timed("issue", "count_by_status", || sql_query(
+            "SELECT issues.status, COUNT(*) AS count \
+             FROM issues JOIN columns ON columns.id = issues.column_id \
+             WHERE columns.board_id = $1 \
+             GROUP BY issues.status \
+             ORDER BY issues.status ASC"
+        )
+            .bind::<Text, _>(board_id)
+            .load(&*db_connection))?;
+
+        Ok(rows.into_iter().map(|row| (row.status, row.count)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::connection::test_pool;
+    use db::repos::{board::{Board, NewBoard, CreateBoard, DeleteBoard}, column::{Column, NewColumn, CreateColumn}};
+    use proto::issues::IssueStatus;
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn transition_all_reports_each_issue() {
+        let pool = test_pool();
+        let column_id = new_id();
+        let mut issues_ids = Vec::new();
+        for _ in 0..2 {
+            let issue = Issue::create(NewIssue {
+                id: &new_id(),
+                column_id: Some(&column_id),
+                epic_id: &new_id(),
+                title: "issue",
+                description: "",
+            }, None, pool.get().unwrap()).await.unwrap();
+            issues_ids.push(issue.id);
+        }
+        update(issues::dsl::issues)
+            .filter(issues::dsl::id.eq(&issues_ids[1]))
+            .set(issues::dsl::status.eq(IssueStatus::Closed as i16))
+            .execute(&*pool.get().unwrap())
+            .unwrap();
+        issues_ids.push(new_id());
+
+        let no_event: Option<fn(&Issue, &[&'static str]) -> NewOutboxEvent> = None;
+        let outcomes = Issue::transition_all(
+            &issues_ids,
+            IssueStatus::Done as i16,
+            |current| current != IssueStatus::Closed as i16,
+            no_event,
+            pool.get().unwrap(),
+        ).await.unwrap();
+
+        assert!(matches!(&outcomes[0], TransitionOutcome::Transitioned(issue) if issue.status == IssueStatus::Done as i16));
+        assert!(matches!(&outcomes[1], TransitionOutcome::NotAllowed(issue) if issue.status == IssueStatus::Closed as i16));
+        assert!(matches!(outcomes[2], TransitionOutcome::NotFound));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn count_by_status_tallies_the_board_issues() {
+        let pool = test_pool();
+        let board_id = new_id();
+        Board::create(NewBoard { id: &board_id, project_id: &new_id() }, pool.get().unwrap())
+            .await
+            .unwrap();
+        let column = Column::create(NewColumn { id: &new_id(), board_id: &board_id, name: "todo" }, pool.get().unwrap())
+            .await
+            .unwrap();
+
+        let mut issues_ids = Vec::new();
+        for _ in 0..3 {
+            let issue = Issue::create(NewIssue {
+                id: &new_id(),
+                column_id: Some(&column.id),
+                epic_id: &new_id(),
+                title: "issue",
+                description: "",
+            }, None, pool.get().unwrap()).await.unwrap();
+            issues_ids.push(issue.id);
+        }
+        update(issues::dsl::issues)
+            .filter(issues::dsl::id.eq(&issues_ids[2]))
+            .set(issues::dsl::status.eq(IssueStatus::Closed as i16))
+            .execute(&*pool.get().unwrap())
+            .unwrap();
+
+        let counted = Issue::count_by_status(&board_id, pool.get().unwrap()).await.unwrap();
+        assert!(Board::delete(&board_id, pool.get().unwrap()).await.is_ok(), "board was not deleted");
+
+        assert_eq!(counted, vec![(IssueStatus::Open as i16, 2), (IssueStatus::Closed as i16, 1)]);
+    }
+}
@@ -0,0 +1,102 @@
+use diesel::result::Error;
+
+use crate::db;
+use db::slow_queries::timed;
+use db::schema::issue_links;
+
+use diesel::{
+    RunQueryDsl,
+    QueryDsl,
+    r2d2::ConnectionManager,
+    PgConnection,
+    ExpressionMethods,
+    insert_into,
+    delete,
+};
+use r2d2::PooledConnection;
+
+use chrono::NaiveDateTime;
+
+/// A link from an issue to something outside the service (a pull request,
+/// a document, a design). Removed together with its issue.
+#[derive(Queryable)]
+pub struct IssueLink {
+    pub id: String,
+    pub issue_id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub kind: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="issue_links"]
+pub struct NewIssueLink<'a> {
+    pub id: &'a str,
+    pub issue_id: &'a str,
+    pub url: &'a str,
+    pub title: Option<&'a str>,
+    pub kind: i32,
+}
+
+#[tonic::async_trait]
+pub trait CreateIssueLink {
+    async fn create<'a>(
+        new_link: NewIssueLink<'a>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<IssueLink, Error>;
+}
+
+#[tonic::async_trait]
+impl CreateIssueLink for IssueLink {
+    async fn create<'a>(
+        new_link: NewIssueLink<'a>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<IssueLink, Error> {
+        timed("issue_link", "create", || insert_into(issue_links::dsl::issue_links)
+            .values(new_link)
+            .get_result(&*db_connection))
+    }
+}
+
+#[tonic::async_trait]
+pub trait DeleteIssueLink {
+    async fn delete<'a>(
+        link_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<IssueLink, Error>;
+}
+
+#[tonic::async_trait]
+impl DeleteIssueLink for IssueLink {
+    async fn delete<'a>(
+        link_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<IssueLink, Error> {
+        timed("issue_link", "delete", || delete(issue_links::dsl::issue_links)
+            .filter(issue_links::dsl::id.eq(link_id))
+            .get_result(&*db_connection))
+    }
+}
+
+#[tonic::async_trait]
+pub trait IssueLinks {
+    async fn for_issue<'a>(
+        issue_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<IssueLink>, Error>;
+}
+
+#[tonic::async_trait]
+impl IssueLinks for IssueLink {
+    /// Oldest link first.
+    async fn for_issue<'a>(
+        issue_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<IssueLink>, Error> {
+        timed("issue_link", "for_issue", || issue_links::dsl::issue_links
+            .filter(issue_links::dsl::issue_id.eq(issue_id))
+            .order((issue_links::dsl::created_at.asc(), issue_links::dsl::id.asc()))
+            .load::<IssueLink>(&*db_connection))
+    }
+}
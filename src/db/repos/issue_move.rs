@@ -0,0 +1,105 @@
+use diesel::result::Error;
+
+use crate::db;
+use db::slow_queries::timed;
+use db::schema::issue_moves;
+
+use diesel::{
+    RunQueryDsl,
+    QueryDsl,
+    r2d2::ConnectionManager,
+    PgConnection,
+    ExpressionMethods,
+    sql_query,
+    sql_types::{Text, BigInt, Timestamptz},
+};
+use r2d2::PooledConnection;
+
+use chrono::NaiveDateTime;
+
+/// One column change of an issue, written by `UpdateIssue::update` in the
+/// same transaction as the change itself.
+#[derive(Queryable)]
+pub struct IssueMove {
+    pub id: String,
+    pub issue_id: String,
+    pub from_column: String,
+    pub to_column: String,
+    pub moved_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="issue_moves"]
+pub struct NewIssueMove<'a> {
+    pub id: &'a str,
+    pub issue_id: &'a str,
+    pub from_column: &'a str,
+    pub to_column: &'a str,
+}
+
+#[tonic::async_trait]
+pub trait IssueMoveHistory {
+    async fn history<'a>(
+        issue_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<IssueMove>, Error>;
+}
+
+#[tonic::async_trait]
+impl IssueMoveHistory for IssueMove {
+    /// Oldest move first.
+    async fn history<'a>(
+        issue_id: &'a str,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<IssueMove>, Error> {
+        timed("issue_move", "history", || issue_moves::dsl::issue_moves
+            .filter(issue_moves::dsl::issue_id.eq(issue_id))
+            .order((issue_moves::dsl::moved_at.asc(), issue_moves::dsl::id.asc()))
+            .load::<IssueMove>(&*db_connection))
+    }
+}
+
+#[derive(QueryableByName)]
+struct ChurnRow {
+    #[sql_type="Text"]
+    issue_id: String,
+    #[sql_type="BigInt"]
+    moves: i64,
+}
+
+#[tonic::async_trait]
+pub trait IssueChurn {
+    async fn churn<'a>(
+        board_id: &'a str,
+        since: NaiveDateTime,
+        limit: i64,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<(String, i64)>, Error>;
+}
+
+#[tonic::async_trait]
+impl IssueChurn for IssueMove {
+    /// Issue ids with the number of moves into a column of the board since
+    /// `since`, most moved first and tie-broken by id.
+    async fn churn<'a>(
+        board_id: &'a str,
+        since: NaiveDateTime,
+        limit: i64,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<(String, i64)>, Error> {
+        let rows: Vec<ChurnRow> = timed("issue_move", "churn", || sql_query(
+            "SELECT issue_moves.issue_id, COUNT(*) AS moves \
+             FROM issue_moves JOIN columns ON columns.id = issue_moves.to_column \
+             WHERE columns.board_id = $1 AND issue_moves.moved_at >= $2 \
+             GROUP BY issue_moves.issue_id \
+             ORDER BY moves DESC, issue_moves.issue_id ASC \
+             LIMIT $3"
+        )
+            .bind::<Text, _>(board_id)
+            .bind::<Timestamptz, _>(since)
+            .bind::<BigInt, _>(limit)
+            .load(&*db_connection))?;
+
+        Ok(rows.into_iter().map(|row| (row.issue_id, row.moves)).collect())
+    }
+}
@@ -2,4 +2,7 @@ pub mod board;
 pub mod column;
 pub mod dependency;
 pub mod epic;
-pub mod issue;
\ No newline at end of file
+pub mod issue;
+pub mod issue_link;
+pub mod issue_move;
+pub mod outbox;
\ No newline at end of file
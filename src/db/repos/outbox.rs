@@ -0,0 +1,298 @@
+use std::time::Duration;
+use diesel::result::Error;
+
+use crate::db;
+use db::slow_queries::timed;
+use db::schema::outbox;
+
+use diesel::{
+    RunQueryDsl,
+    QueryDsl,
+    r2d2::ConnectionManager,
+    PgConnection,
+    Connection,
+    ExpressionMethods,
+    BoolExpressionMethods,
+    update,
+    delete,
+    sql_query,
+    dsl::{now, sql},
+    sql_types::{Nullable, Timestamptz, Text, Bool},
+};
+use r2d2::PooledConnection;
+
+use chrono::NaiveDateTime;
+
+/// An event committed together with the change it describes and waiting
+/// to be sent. `payload` is the prost-encoded request for `method`.
+#[derive(Queryable)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub method: String,
+    pub payload: Vec<u8>,
+}
+
+/// The columns an `OutboxEvent` is loaded from; the bookkeeping ones stay
+/// in the database.
+const EVENT_COLUMNS: (outbox::id, outbox::method, outbox::payload) =
+    (outbox::id, outbox::method, outbox::payload);
+
+#[derive(Insertable)]
+#[table_name="outbox"]
+pub struct NewOutboxEvent {
+    pub method: String,
+    pub payload: Vec<u8>,
+}
+
+#[tonic::async_trait]
+pub trait ClaimOutboxEvents {
+    async fn claim(
+        limit: i64,
+        lease: Duration,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<OutboxEvent>, Error>;
+
+    async fn release(
+        events_ids: Vec<i64>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(), Error>;
+}
+
+#[tonic::async_trait]
+impl ClaimOutboxEvents for OutboxEvent {
+    /// Undelivered events no other relay holds, oldest first, claimed for
+    /// `lease`. Rows another relay is claiming right now are skipped rather
+    /// than waited for, so each event goes to one relay at a time; one whose
+    /// relay died is claimed again once its lease is over.
+    async fn claim(
+        limit: i64,
+        lease: Duration,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<OutboxEvent>, Error> {
+        timed("outbox", "claim", || db_connection.transaction::<_, Error, _>(|| {
+            let events_ids: Vec<i64> = outbox::dsl::outbox
+                .select(outbox::dsl::id)
+                .filter(outbox::dsl::delivered_at.is_null())
+                .filter(outbox::dsl::claimed_until.is_null()
+                    .or(outbox::dsl::claimed_until.lt(now)))
+                .order(outbox::dsl::id.asc())
+                .limit(limit)
+                .for_update()
+                .skip_locked()
+                .load(&*db_connection)?;
+
+            let claimed_until = sql::<Nullable<Timestamptz>>(
+                &format!("now() + interval '{} milliseconds'", lease.as_millis())
+            );
+            let mut claimed: Vec<OutboxEvent> = update(outbox::dsl::outbox)
+                .filter(outbox::dsl::id.eq_any(&events_ids))
+                .set(outbox::dsl::claimed_until.eq(claimed_until))
+                .returning(EVENT_COLUMNS)
+                .get_results(&*db_connection)?;
+            claimed.sort_by_key(|event| event.id);
+
+            Ok(claimed)
+        }))
+    }
+
+    /// Gives claimed events back before their lease is over, for the next
+    /// pass of any relay.
+    async fn release(
+        events_ids: Vec<i64>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(), Error> {
+        timed("outbox", "release", || update(outbox::dsl::outbox)
+            .filter(outbox::dsl::id.eq_any(&events_ids))
+            .filter(outbox::dsl::delivered_at.is_null())
+            .set(outbox::dsl::claimed_until.eq(None::<NaiveDateTime>))
+            .execute(&*db_connection))
+            .map(|_| ())
+    }
+}
+
+#[tonic::async_trait]
+pub trait MarkOutboxEventDelivered {
+    async fn mark_delivered(
+        event_id: i64,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(), Error>;
+}
+
+#[tonic::async_trait]
+impl MarkOutboxEventDelivered for OutboxEvent {
+    async fn mark_delivered(
+        event_id: i64,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<(), Error> {
+        timed("outbox", "mark_delivered", || update(outbox::dsl::outbox)
+            .filter(outbox::dsl::id.eq(event_id))
+            .set(outbox::dsl::delivered_at.eq(now))
+            .execute(&*db_connection))
+            .map(|_| ())
+    }
+}
+
+#[tonic::async_trait]
+pub trait PurgeOutboxEvents {
+    async fn purge_delivered(
+        retention: Duration,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<usize, Error>;
+}
+
+#[tonic::async_trait]
+impl PurgeOutboxEvents for OutboxEvent {
+    /// Deletes the events delivered more than `retention` ago and returns
+    /// how many there were.
+    async fn purge_delivered(
+        retention: Duration,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<usize, Error> {
+        let cutoff = sql::<Nullable<Timestamptz>>(
+            &format!("now() - interval '{} seconds'", retention.as_secs())
+        );
+        timed("outbox", "purge_delivered", || delete(outbox::dsl::outbox)
+            .filter(outbox::dsl::delivered_at.lt(cutoff))
+            .execute(&*db_connection))
+    }
+}
+
+#[tonic::async_trait]
+pub trait ReplayOutboxEvents {
+    async fn delivered_between<'a>(
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        methods: Option<&'a [&'a str]>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<OutboxEvent>, Error>;
+}
+
+#[tonic::async_trait]
+impl ReplayOutboxEvents for OutboxEvent {
+    /// Delivered events written in `[from, to)`, oldest first, limited to
+    /// `methods` when given. Events not delivered yet are left to the relay.
+    async fn delivered_between<'a>(
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        methods: Option<&'a [&'a str]>,
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Vec<OutboxEvent>, Error> {
+        let mut query = outbox::dsl::outbox
+            .select(EVENT_COLUMNS)
+            .filter(outbox::dsl::delivered_at.is_not_null())
+            .filter(outbox::dsl::created_at.ge(from))
+            .filter(outbox::dsl::created_at.lt(to))
+            .into_boxed();
+        if let Some(methods) = methods {
+            query = query.filter(outbox::dsl::method.eq_any(methods));
+        }
+
+        timed("outbox", "delivered_between", || query
+            .order(outbox::dsl::id.asc())
+            .load(&*db_connection))
+    }
+}
+
+/// Key of the advisory lock that keeps replays from overlapping.
+const REPLAY_LOCK: &str = "outbox_replay";
+
+#[derive(QueryableByName)]
+struct LockRow {
+    #[sql_type="Bool"]
+    locked: bool,
+}
+
+/// A session-level advisory lock held for as long as a replay runs, on
+/// every replica, and released when dropped.
+pub struct ReplayLock {
+    db_connection: PooledConnection<ConnectionManager<PgConnection>>,
+}
+
+impl ReplayLock {
+    /// `None` when another replay holds the lock.
+    pub fn try_acquire(
+        db_connection: PooledConnection<ConnectionManager<PgConnection>>
+    ) -> Result<Option<ReplayLock>, Error> {
+        let row: LockRow = timed("outbox", "replay_lock", || {
+            sql_query("SELECT pg_try_advisory_lock(hashtext($1)) AS locked")
+                .bind::<Text, _>(REPLAY_LOCK)
+                .get_result(&*db_connection)
+        })?;
+
+        Ok(match row.locked {
+            true => Some(ReplayLock { db_connection }),
+            false => None,
+        })
+    }
+}
+
+impl Drop for ReplayLock {
+    fn drop(&mut self) {
+        let unlocked = sql_query("SELECT pg_advisory_unlock(hashtext($1)) AS locked")
+            .bind::<Text, _>(REPLAY_LOCK)
+            .get_result::<LockRow>(&*self.db_connection);
+        match unlocked {
+            Ok(row) if row.locked => {},
+            Ok(_) => eprintln!("warning: the outbox replay lock was not held when released"),
+            Err(err) => eprintln!("warning: could not release the outbox replay lock: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::insert_into;
+    use db::connection::test_pool;
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn delivered_between_skips_pending_events_and_other_methods() {
+        let pool = test_pool();
+        let db_connection = pool.get().unwrap();
+        let method = format!("test_event_{}", crate::ids::new_id());
+        let inserted: Vec<OutboxEvent> = insert_into(outbox::table)
+            .values(&vec![
+                NewOutboxEvent { method: method.clone(), payload: Vec::new() },
+                NewOutboxEvent { method: method.clone(), payload: Vec::new() },
+                NewOutboxEvent { method: format!("{}_other", method), payload: Vec::new() },
+            ])
+            .returning(EVENT_COLUMNS)
+            .get_results(&*db_connection)
+            .unwrap();
+        let events_ids: Vec<i64> = inserted.iter().map(|event| event.id).collect();
+        for event_id in [events_ids[0], events_ids[2]] {
+            OutboxEvent::mark_delivered(event_id, pool.get().unwrap()).await.unwrap();
+        }
+
+        let created_at: NaiveDateTime = outbox::dsl::outbox
+            .select(outbox::dsl::created_at)
+            .find(events_ids[0])
+            .get_result(&*db_connection)
+            .unwrap();
+        let from = created_at - chrono::Duration::seconds(1);
+        let to = created_at + chrono::Duration::seconds(1);
+        let methods = [method.as_str()];
+        let replayed = OutboxEvent::delivered_between(from, to, Some(&methods), pool.get().unwrap()).await.unwrap();
+
+        delete(outbox::dsl::outbox)
+            .filter(outbox::dsl::id.eq_any(&events_ids))
+            .execute(&*db_connection)
+            .unwrap();
+        let replayed_ids: Vec<i64> = replayed.iter().map(|event| event.id).collect();
+        assert_eq!(replayed_ids, vec![events_ids[0]]);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database in DATABASE_URL"]
+    async fn replay_lock_is_held_until_dropped() {
+        let pool = test_pool();
+
+        let lock = ReplayLock::try_acquire(pool.get().unwrap()).unwrap();
+        assert!(lock.is_some());
+        assert!(ReplayLock::try_acquire(pool.get().unwrap()).unwrap().is_none());
+
+        drop(lock);
+        assert!(ReplayLock::try_acquire(pool.get().unwrap()).unwrap().is_some());
+    }
+}
@@ -2,6 +2,7 @@ table! {
     boards (id) {
         id -> Bpchar,
         project_id -> Bpchar,
+        deletion_protected -> Bool,
     }
 }
 
@@ -10,6 +11,8 @@ table! {
         id -> Bpchar,
         board_id -> Bpchar,
         name -> Varchar,
+        position -> Int4,
+        archived -> Bool,
     }
 }
 
@@ -18,6 +21,7 @@ table! {
         id -> Bpchar,
         blocking_epic_id -> Bpchar,
         blocked_epic_id -> Bpchar,
+        dependency_type -> Nullable<Int4>,
     }
 }
 
@@ -29,8 +33,12 @@ table! {
         reporter_id -> Bpchar,
         name -> Varchar,
         description -> Nullable<Text>,
-        start_date -> Timestamptz,
-        due_date -> Timestamptz,
+        start_date -> Nullable<Timestamptz>,
+        due_date -> Nullable<Timestamptz>,
+        updated_at -> Timestamptz,
+        created_at -> Timestamptz,
+        progress_percent -> Int4,
+        metadata -> Nullable<Jsonb>,
     }
 }
 
@@ -41,6 +49,42 @@ table! {
         epic_id -> Bpchar,
         title -> Varchar,
         description -> Text,
+        updated_at -> Timestamptz,
+        created_at -> Timestamptz,
+        status -> Int2,
+        rank -> Int4,
+    }
+}
+
+table! {
+    issue_links (id) {
+        id -> Bpchar,
+        issue_id -> Bpchar,
+        url -> Text,
+        title -> Nullable<Varchar>,
+        kind -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    issue_moves (id) {
+        id -> Bpchar,
+        issue_id -> Bpchar,
+        from_column -> Bpchar,
+        to_column -> Bpchar,
+        moved_at -> Timestamptz,
+    }
+}
+
+table! {
+    outbox (id) {
+        id -> Int8,
+        method -> Varchar,
+        payload -> Bytea,
+        created_at -> Timestamptz,
+        delivered_at -> Nullable<Timestamptz>,
+        claimed_until -> Nullable<Timestamptz>,
     }
 }
 
@@ -49,5 +93,8 @@ allow_tables_to_appear_in_same_query!(
     columns,
     dependencies,
     epics,
+    issue_links,
+    issue_moves,
     issues,
+    outbox,
 );
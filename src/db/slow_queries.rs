@@ -0,0 +1,40 @@
+use std::{
+    env,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use crate::correlation::log_context;
+
+/// Repo calls slower than this many milliseconds are logged; 0 turns logging off.
+static SLOW_QUERY_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Reads `SLOW_QUERY_MS`. Slow query logging stays off when it is unset.
+pub fn configure_from_env() -> Result<(), String> {
+    if let Ok(value) = env::var("SLOW_QUERY_MS") {
+        let threshold: u64 = value.parse().map_err(|_| format!(
+            "SLOW_QUERY_MS must be a non-negative integer, got {}", value
+        ))?;
+        SLOW_QUERY_MS.store(threshold, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Runs a repo call and warns when it takes longer than `SLOW_QUERY_MS`.
+pub fn timed<T>(entity: &str, operation: &str, query: impl FnOnce() -> T) -> T {
+    let threshold = SLOW_QUERY_MS.load(Ordering::Relaxed);
+    if threshold == 0 {
+        return query();
+    }
+
+    let started = Instant::now();
+    let result = query();
+    let elapsed = started.elapsed().as_millis() as u64;
+    if elapsed > threshold {
+        eprintln!(
+            "Slow query: {} {} took {} ms (threshold {} ms){}",
+            entity, operation, elapsed, threshold, log_context(),
+        );
+    }
+    result
+}
@@ -0,0 +1,136 @@
+use std::env;
+use proto::issues::{BoardsOrder, DependenciesOrder};
+
+/// An order a search RPC can be asked to sort by, along with the env var
+/// that picks the default when a request does not.
+pub trait SortOrder: Copy + Sized + 'static {
+    const ENV_VAR: &'static str;
+    /// The accepted `ENV_VAR` values; the first one is used when it is unset.
+    const CHOICES: &'static [(&'static str, Self, bool)];
+
+    fn from_requested(value: i32) -> Option<Self>;
+}
+
+/// The order `searchBoards` and `getBoardsByProjectId` use when a request
+/// does not pick one, read from `BOARD_DEFAULT_SORT`.
+impl SortOrder for BoardsOrder {
+    const ENV_VAR: &'static str = "BOARD_DEFAULT_SORT";
+    const CHOICES: &'static [(&'static str, Self, bool)] = &[
+        ("id_asc", BoardsOrder::Id, false),
+        ("id_desc", BoardsOrder::Id, true),
+        ("project_id_asc", BoardsOrder::ProjectId, false),
+        ("project_id_desc", BoardsOrder::ProjectId, true),
+    ];
+
+    fn from_requested(value: i32) -> Option<Self> {
+        BoardsOrder::from_i32(value)
+    }
+}
+
+/// The order `searchDependencies` uses when a request does not pick one,
+/// read from `DEP_DEFAULT_SORT`.
+impl SortOrder for DependenciesOrder {
+    const ENV_VAR: &'static str = "DEP_DEFAULT_SORT";
+    const CHOICES: &'static [(&'static str, Self, bool)] = &[
+        ("id_asc", DependenciesOrder::Id, false),
+        ("id_desc", DependenciesOrder::Id, true),
+        ("blocking_epic_asc", DependenciesOrder::BlockingEpic, false),
+        ("blocking_epic_desc", DependenciesOrder::BlockingEpic, true),
+        ("blocked_epic_asc", DependenciesOrder::BlockedEpic, false),
+        ("blocked_epic_desc", DependenciesOrder::BlockedEpic, true),
+    ];
+
+    fn from_requested(value: i32) -> Option<Self> {
+        DependenciesOrder::from_i32(value)
+    }
+}
+
+pub type BoardSort = DefaultSort<BoardsOrder>;
+pub type DependencySort = DefaultSort<DependenciesOrder>;
+
+/// A search sort, either the configured default or what a request asked
+/// for on top of it. Ties are always broken by id.
+#[derive(Clone, Copy)]
+pub struct DefaultSort<O: SortOrder> {
+    pub order: O,
+    pub descending: bool,
+}
+
+impl<O: SortOrder> DefaultSort<O> {
+    pub fn from_env() -> Result<DefaultSort<O>, String> {
+        let value = match env::var(O::ENV_VAR) {
+            Err(_) => O::CHOICES[0].0.to_string(),
+            Ok(value) => value,
+        };
+
+        match O::CHOICES.iter().find(|(name, _, _)| *name == value) {
+            Some(&(_, order, descending)) => Ok(DefaultSort { order, descending }),
+            None => Err(format!("{} must be one of {}, got {}", O::ENV_VAR, choice_list(O::CHOICES), value)),
+        }
+    }
+
+    /// The sort a request asked for, with whatever it left out taken from
+    /// this default.
+    pub fn or_requested(self, order_by: Option<i32>, descending: Option<bool>) -> DefaultSort<O> {
+        DefaultSort {
+            order: order_by.and_then(O::from_requested).unwrap_or(self.order),
+            descending: descending.unwrap_or(self.descending),
+        }
+    }
+}
+
+fn choice_list<O>(choices: &[(&str, O, bool)]) -> String {
+    let names: Vec<&str> = choices.iter().map(|(name, _, _)| *name).collect();
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requested_fields_override_the_default() {
+        let default = BoardSort { order: BoardsOrder::ProjectId, descending: true };
+
+        let sort = default.or_requested(Some(BoardsOrder::Id as i32), None);
+        assert_eq!((sort.order, sort.descending), (BoardsOrder::Id, true));
+
+        let sort = default.or_requested(None, Some(false));
+        assert_eq!((sort.order, sort.descending), (BoardsOrder::ProjectId, false));
+
+        let sort = default.or_requested(Some(42), None);
+        assert_eq!(sort.order, BoardsOrder::ProjectId);
+    }
+
+    // The only test touching DEP_DEFAULT_SORT, so it can set it freely.
+    #[test]
+    fn default_sort_is_read_from_env() {
+        env::remove_var("DEP_DEFAULT_SORT");
+        let sort = DependencySort::from_env().unwrap();
+        assert_eq!((sort.order, sort.descending), (DependenciesOrder::Id, false));
+
+        env::set_var("DEP_DEFAULT_SORT", "blocking_epic_desc");
+        let sort = DependencySort::from_env().unwrap();
+        assert_eq!((sort.order, sort.descending), (DependenciesOrder::BlockingEpic, true));
+
+        env::set_var("DEP_DEFAULT_SORT", "created_at_desc");
+        assert!(DependencySort::from_env().is_err());
+
+        env::remove_var("DEP_DEFAULT_SORT");
+    }
+
+    #[test]
+    fn choice_list_reads_as_a_sentence() {
+        let choices = [("id_asc", BoardsOrder::Id, false)];
+        assert_eq!(choice_list(&choices), "id_asc");
+
+        assert_eq!(
+            choice_list(BoardsOrder::CHOICES),
+            "id_asc, id_desc, project_id_asc or project_id_desc"
+        );
+    }
+}
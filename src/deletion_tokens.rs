@@ -0,0 +1,117 @@
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Single-use tokens that confirm a board deletion prepared beforehand.
+/// Tokens live for `BOARD_DELETION_TOKEN_TTL_SECS` (default 300) and are
+/// only valid for the board they were issued for.
+#[derive(Clone)]
+pub struct DeletionTokens {
+    ttl: Duration,
+    tokens: Arc<Mutex<HashMap<String, (String, Instant)>>>,
+}
+
+impl DeletionTokens {
+    pub fn from_env() -> Result<DeletionTokens, String> {
+        let ttl = match env::var("BOARD_DELETION_TOKEN_TTL_SECS") {
+            Err(_) => 300,
+            Ok(value) => value.parse().map_err(|_| format!(
+                "BOARD_DELETION_TOKEN_TTL_SECS must be a non-negative integer, got {}", value
+            ))?,
+        };
+
+        Ok(DeletionTokens {
+            ttl: Duration::from_secs(ttl),
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn issue(&self, board_id: &str) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        let now = Instant::now();
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.retain(|_, (_, expires_at)| *expires_at > now);
+        tokens.insert(token.clone(), (board_id.to_string(), now + self.ttl));
+        token
+    }
+
+    /// Takes a live token for `board_id` out, so that no other call can use
+    /// it while the deletion it confirms runs. The token is put back when the
+    /// returned claim is dropped without being confirmed. An unknown, expired
+    /// or other board's token is removed and gives `None`.
+    pub fn claim(&self, token: &str, board_id: &str) -> Option<TokenClaim> {
+        match self.tokens.lock().unwrap().remove(token) {
+            Some((token_board_id, expires_at)) if token_board_id == board_id && expires_at > Instant::now() => {
+                Some(TokenClaim {
+                    tokens: self.tokens.clone(),
+                    entry: Some((token.to_string(), token_board_id, expires_at)),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A token taken out by `DeletionTokens::claim`.
+pub struct TokenClaim {
+    tokens: Arc<Mutex<HashMap<String, (String, Instant)>>>,
+    entry: Option<(String, String, Instant)>,
+}
+
+impl TokenClaim {
+    /// Uses the token up, once the deletion it confirmed went through.
+    pub fn confirm(mut self) {
+        self.entry = None;
+    }
+}
+
+impl Drop for TokenClaim {
+    /// Puts the token back with its original expiry.
+    fn drop(&mut self) {
+        if let Some((token, board_id, expires_at)) = self.entry.take() {
+            self.tokens.lock().unwrap().insert(token, (board_id, expires_at));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deletion_tokens() -> DeletionTokens {
+        DeletionTokens {
+            ttl: Duration::from_secs(300),
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[test]
+    fn claimed_token_comes_back_unless_confirmed() {
+        let deletion_tokens = deletion_tokens();
+        let token = deletion_tokens.issue("board");
+
+        let claim = deletion_tokens.claim(&token, "board").unwrap();
+        assert!(deletion_tokens.claim(&token, "board").is_none());
+        drop(claim);
+
+        let claim = deletion_tokens.claim(&token, "board").unwrap();
+        claim.confirm();
+        assert!(deletion_tokens.claim(&token, "board").is_none());
+    }
+
+    #[test]
+    fn token_for_another_board_is_used_up() {
+        let deletion_tokens = deletion_tokens();
+        let token = deletion_tokens.issue("board");
+
+        assert!(deletion_tokens.claim(&token, "other").is_none());
+        assert!(deletion_tokens.claim(&token, "board").is_none());
+    }
+}
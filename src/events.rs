@@ -0,0 +1,337 @@
+use std::{env, fmt::Debug, future::Future, sync::OnceLock, time::Duration};
+
+use proto::eventbus;
+use tonic::{Code, Request, Response, Status, transport::Uri};
+use diesel::{
+    r2d2::ConnectionManager,
+    result::{DatabaseErrorKind, Error},
+    PgConnection,
+};
+use r2d2::PooledConnection;
+
+use crate::{correlation::log_context, db::connection::PgPool};
+
+/// Address of one eventbus service, read from `<ENTITY>_EVENTBUS_URL`, e.g.
+/// `BOARDS_EVENTBUS_URL`, falling back to `EVENTBUS_URL` for services that
+/// share one address. When neither is set the client is not connected and
+/// the matching controller emits no events, so partial deployments can run
+/// without every eventbus service. A set but malformed URL is an error.
+pub fn eventbus_url(entity: &str) -> Result<Option<String>, String> {
+    let var = format!("{}_EVENTBUS_URL", entity);
+    let (var, url) = match (env::var(&var), env::var("EVENTBUS_URL")) {
+        (Ok(url), _) => (var, url),
+        (Err(_), Ok(url)) => (String::from("EVENTBUS_URL"), url),
+        (Err(_), Err(_)) => {
+            println!("{} and EVENTBUS_URL are unset, {} events are disabled", var, entity.to_lowercase());
+            return Ok(None);
+        }
+    };
+
+    let valid = match url.parse::<Uri>() {
+        Ok(uri) => matches!(uri.scheme_str(), Some("http" | "https")) && uri.host().is_some_and(|host| !host.is_empty()),
+        Err(_) => false,
+    };
+    if !valid {
+        return Err(format!("{} must be an http(s) URL such as http://eventbus:50057, got {}", var, url));
+    }
+    Ok(Some(url))
+}
+
+/// How much of an entity is copied into the events sent to the eventbus.
+/// `Minimal` keeps only ids (plus `board_id` on columns and `issue_id` on
+/// issue links for routing).
+#[derive(Clone, Copy, PartialEq)]
+pub enum EventPayload {
+    Full,
+    Minimal,
+}
+
+impl EventPayload {
+    pub fn from_env() -> Result<EventPayload, String> {
+        match env::var("EVENT_PAYLOAD") {
+            Err(_) => Ok(EventPayload::Full),
+            Ok(value) => match value.as_str() {
+                "full" => Ok(EventPayload::Full),
+                "minimal" => Ok(EventPayload::Minimal),
+                other => Err(format!("EVENT_PAYLOAD must be full or minimal, got {}", other)),
+            },
+        }
+    }
+
+    pub fn apply<T: Minimize>(&self, entity: T) -> T {
+        match self {
+            EventPayload::Full => entity,
+            EventPayload::Minimal => entity.minimize(),
+        }
+    }
+
+    pub fn apply_all<T: Minimize>(&self, entities: Vec<T>) -> Vec<T> {
+        entities
+            .into_iter()
+            .map(|entity| self.apply(entity))
+            .collect()
+    }
+}
+
+/// Builds the error for a request rejected before it reached the database,
+/// so the event and the returned status always carry the same code.
+pub fn rejection(code: Code, message: &str) -> (eventbus::Error, Status) {
+    let error = eventbus::Error {
+        code: code.into(),
+        message: message.to_string(),
+    };
+    let status = Status::new(code, message);
+    (error, status)
+}
+
+/// Builds the error for a failed query. A unique violation fails the same
+/// way however often it is retried, so it is `already_exists`; anything else
+/// is reported as the database being unavailable.
+pub fn db_failure(err: &Error) -> (eventbus::Error, Status) {
+    let (code, status) = match err {
+        Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => (
+            Code::AlreadyExists,
+            Status::already_exists("Already exists"),
+        ),
+        _ => (Code::Unavailable, Status::unavailable("Database is unavailable")),
+    };
+    let error = eventbus::Error {
+        code: code.into(),
+        message: err.to_string(),
+    };
+    (error, status)
+}
+
+/// A connection from `pool`, or the rejection to report when none is free
+/// in time because the pool is exhausted or Postgres is down. The rejection
+/// is boxed to keep the `Result` small.
+pub fn pooled_connection(pool: &PgPool) -> Result<PooledConnection<ConnectionManager<PgConnection>>, Box<(eventbus::Error, Status)>> {
+    pool.get().map_err(|err| {
+        eprintln!("warning: could not get a database connection: {}{}", err, log_context());
+        Box::new(rejection(Code::Unavailable, "database pool exhausted"))
+    })
+}
+
+/// How failed eventbus sends are handled. Only codes in `retryable` are
+/// retried, up to `max_attempts` sends in total with doubling backoff; any
+/// other failure means the event itself is bad and is logged with its payload.
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    retryable: Vec<Code>,
+}
+
+static RETRY_POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+impl RetryPolicy {
+    /// Reads `EVENTBUS_MAX_ATTEMPTS` (default 3), `EVENTBUS_RETRY_BACKOFF_MS`
+    /// (default 100) and `EVENTBUS_RETRYABLE_CODES`, a comma separated list of
+    /// gRPC code names (default `unavailable,deadline_exceeded,resource_exhausted,aborted`).
+    pub fn from_env() -> Result<RetryPolicy, String> {
+        let max_attempts = match env::var("EVENTBUS_MAX_ATTEMPTS") {
+            Err(_) => 3,
+            Ok(value) => match value.parse() {
+                Ok(attempts) if attempts > 0 => attempts,
+                _ => return Err(format!("EVENTBUS_MAX_ATTEMPTS must be a positive integer, got {}", value)),
+            },
+        };
+        let backoff_ms = match env::var("EVENTBUS_RETRY_BACKOFF_MS") {
+            Err(_) => 100,
+            Ok(value) => value.parse().map_err(|_| format!(
+                "EVENTBUS_RETRY_BACKOFF_MS must be a non-negative integer, got {}", value
+            ))?,
+        };
+        let retryable = match env::var("EVENTBUS_RETRYABLE_CODES") {
+            Err(_) => vec![Code::Unavailable, Code::DeadlineExceeded, Code::ResourceExhausted, Code::Aborted],
+            Ok(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| code_from_name(name).ok_or_else(|| format!(
+                    "EVENTBUS_RETRYABLE_CODES has an unknown gRPC code: {}", name
+                )))
+                .collect::<Result<Vec<Code>, String>>()?,
+        };
+
+        Ok(RetryPolicy {
+            max_attempts,
+            backoff: Duration::from_millis(backoff_ms),
+            retryable,
+        })
+    }
+
+    /// Makes this the policy `deliver` uses. Only the first call has an effect.
+    pub fn install(self) {
+        let _ = RETRY_POLICY.set(self);
+    }
+}
+
+fn code_from_name(name: &str) -> Option<Code> {
+    let code = match name.to_ascii_lowercase().as_str() {
+        "cancelled" => Code::Cancelled,
+        "unknown" => Code::Unknown,
+        "invalid_argument" => Code::InvalidArgument,
+        "deadline_exceeded" => Code::DeadlineExceeded,
+        "not_found" => Code::NotFound,
+        "already_exists" => Code::AlreadyExists,
+        "permission_denied" => Code::PermissionDenied,
+        "resource_exhausted" => Code::ResourceExhausted,
+        "failed_precondition" => Code::FailedPrecondition,
+        "aborted" => Code::Aborted,
+        "out_of_range" => Code::OutOfRange,
+        "unimplemented" => Code::Unimplemented,
+        "internal" => Code::Internal,
+        "unavailable" => Code::Unavailable,
+        "data_loss" => Code::DataLoss,
+        "unauthenticated" => Code::Unauthenticated,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Sends an event to the eventbus, retrying it while the failure is one the
+/// installed `RetryPolicy` treats as transient. Use through `send_event!`.
+/// Returns the last failure when the event was not delivered.
+pub async fn deliver<T, R, F, Fut>(method: &str, req: Request<T>, mut send: F) -> Result<(), Status>
+where
+    T: Clone + Debug,
+    F: FnMut(Request<T>) -> Fut,
+    Fut: Future<Output = Result<Response<R>, Status>>,
+{
+    let policy = retry_policy();
+    let event = req.into_inner();
+    let mut backoff = policy.backoff;
+
+    for attempt in 1..=policy.max_attempts {
+        let status = match send(Request::new(event.clone())).await {
+            Ok(_) => return Ok(()),
+            Err(status) => status,
+        };
+        if !policy.retryable.contains(&status.code()) {
+            eprintln!(
+                "error: eventbus rejected {} with {:?}: {}; event: {:?}{}",
+                method, status.code(), status.message(), event, log_context(),
+            );
+            return Err(status);
+        }
+        if attempt == policy.max_attempts {
+            eprintln!(
+                "warning: giving up on {} after {} attempts: {:?}: {}{}",
+                method, attempt, status.code(), status.message(), log_context(),
+            );
+            return Err(status);
+        }
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+    unreachable!("max_attempts is at least 1")
+}
+
+fn retry_policy() -> &'static RetryPolicy {
+    RETRY_POLICY.get_or_init(|| RetryPolicy {
+        max_attempts: 1,
+        backoff: Duration::ZERO,
+        retryable: Vec::new(),
+    })
+}
+
+/// Whether a failure returned by `deliver` is worth sending again later.
+pub fn is_transient(status: &Status) -> bool {
+    retry_policy().retryable.contains(&status.code())
+}
+
+/// Sends `$req` with `$client.$method` through `deliver`, cloning the client
+/// for each attempt. `$client` is an `Option`; nothing is sent when that
+/// eventbus service was not configured.
+macro_rules! send_event {
+    ($client:expr, $method:ident, $req:expr) => {
+        async {
+            if let Some(client) = &$client {
+                let _ = $crate::events::deliver(stringify!($method), $req, |req| {
+                    let mut client = client.clone();
+                    async move { client.$method(req).await }
+                }).await;
+            }
+        }
+    };
+}
+
+pub(crate) use send_event;
+
+pub trait Minimize {
+    fn minimize(self) -> Self;
+}
+
+impl Minimize for eventbus::Board {
+    fn minimize(self) -> Self {
+        eventbus::Board {
+            id: self.id,
+            project_id: None,
+            deletion_protected: None,
+        }
+    }
+}
+
+impl Minimize for eventbus::Column {
+    fn minimize(self) -> Self {
+        eventbus::Column {
+            id: self.id,
+            board_id: self.board_id,
+            name: None,
+            archived: None,
+        }
+    }
+}
+
+impl Minimize for eventbus::Issue {
+    fn minimize(self) -> Self {
+        eventbus::Issue {
+            id: self.id,
+            column_id: None,
+            epic_id: None,
+            title: None,
+            description: None,
+            status: None,
+        }
+    }
+}
+
+impl Minimize for eventbus::IssueLink {
+    fn minimize(self) -> Self {
+        eventbus::IssueLink {
+            id: self.id,
+            issue_id: self.issue_id,
+            url: None,
+            title: None,
+            kind: None,
+        }
+    }
+}
+
+impl Minimize for eventbus::Epic {
+    fn minimize(self) -> Self {
+        eventbus::Epic {
+            id: self.id,
+            column_id: None,
+            assignee_id: None,
+            reporter_id: None,
+            name: None,
+            description: None,
+            start_date: None,
+            due_date: None,
+            progress_percent: None,
+            metadata: None,
+        }
+    }
+}
+
+impl Minimize for eventbus::Dependency {
+    fn minimize(self) -> Self {
+        eventbus::Dependency {
+            id: self.id,
+            blocking_epic_id: None,
+            blocked_epic_id: None,
+            dependency_type: None,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::{env, sync::OnceLock};
+
+/// How new row ids are generated, read from `ID_SCHEME`: `v4` (default) gives
+/// random UUIDs, `v7` time-ordered ones that keep primary key inserts close
+/// together in the index.
+#[derive(Clone, Copy, PartialEq)]
+pub enum IdScheme {
+    V4,
+    V7,
+}
+
+static ID_SCHEME: OnceLock<IdScheme> = OnceLock::new();
+
+impl IdScheme {
+    pub fn from_env() -> Result<IdScheme, String> {
+        match env::var("ID_SCHEME") {
+            Err(_) => Ok(IdScheme::V4),
+            Ok(value) => match value.as_str() {
+                "v4" => Ok(IdScheme::V4),
+                "v7" => Ok(IdScheme::V7),
+                other => Err(format!("ID_SCHEME must be v4 or v7, got {}", other)),
+            },
+        }
+    }
+
+    /// Makes this the scheme `new_id` uses. Only the first call has an effect.
+    pub fn install(self) {
+        let _ = ID_SCHEME.set(self);
+    }
+}
+
+/// A fresh id for a new row, in the installed `IdScheme`.
+pub fn new_id() -> String {
+    match ID_SCHEME.get().copied().unwrap_or(IdScheme::V4) {
+        IdScheme::V4 => uuid::Uuid::new_v4().to_string(),
+        IdScheme::V7 => uuid::Uuid::now_v7().to_string(),
+    }
+}
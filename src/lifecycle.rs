@@ -0,0 +1,153 @@
+use std::{
+    env,
+    future::Future,
+    sync::{atomic::{AtomicUsize, Ordering}, Arc},
+    time::Duration,
+};
+use tokio::{sync::watch, time::Instant};
+use tonic::{service::Interceptor, Request, Status};
+
+use crate::correlation::{correlation_id, with_correlation_id};
+
+/// Where the process is in its life. The health service reports `Ready` as
+/// SERVING and everything else as NOT_SERVING; only `Ready` accepts new calls.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ServiceState {
+    Starting,
+    Ready,
+    Draining,
+    Stopped,
+}
+
+/// Shared handle on the current `ServiceState`. On SIGINT or SIGTERM the
+/// service switches to `Draining`, keeps answering in-flight calls for
+/// `SHUTDOWN_DRAIN_MS` (default 5000) so load balancers can notice, and then
+/// lets the server finish the remaining streams before exiting. Tasks
+/// started with `spawn_tracked`, such as event sends, then get up to
+/// `SHUTDOWN_GRACE_MS` (default 10000) to complete.
+#[derive(Clone)]
+pub struct Lifecycle {
+    drain: Duration,
+    grace: Duration,
+    sender: Arc<watch::Sender<ServiceState>>,
+    receiver: watch::Receiver<ServiceState>,
+}
+
+impl Lifecycle {
+    pub fn from_env() -> Result<Lifecycle, String> {
+        let drain_ms = match env::var("SHUTDOWN_DRAIN_MS") {
+            Err(_) => 5000,
+            Ok(value) => value.parse().map_err(|_| format!(
+                "SHUTDOWN_DRAIN_MS must be a non-negative integer, got {}", value
+            ))?,
+        };
+
+        let grace_ms = match env::var("SHUTDOWN_GRACE_MS") {
+            Err(_) => 10000,
+            Ok(value) => value.parse().map_err(|_| format!(
+                "SHUTDOWN_GRACE_MS must be a non-negative integer, got {}", value
+            ))?,
+        };
+
+        let (sender, receiver) = watch::channel(ServiceState::Starting);
+        Ok(Lifecycle {
+            drain: Duration::from_millis(drain_ms),
+            grace: Duration::from_millis(grace_ms),
+            sender: Arc::new(sender),
+            receiver,
+        })
+    }
+
+    pub fn state(&self) -> ServiceState {
+        *self.receiver.borrow()
+    }
+
+    pub fn set(&self, state: ServiceState) {
+        // The handle keeps its own receiver, so sending cannot fail.
+        let _ = self.sender.send(state);
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<ServiceState> {
+        self.sender.subscribe()
+    }
+
+    pub fn gate(&self) -> Gate {
+        Gate(self.clone())
+    }
+
+    /// Resolves once a shutdown signal arrived and the drain period is over;
+    /// meant to be passed to `serve_with_shutdown`.
+    pub async fn drain_on_signal(self) {
+        let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {},
+            _ = terminate.recv() => {},
+        }
+
+        self.set(ServiceState::Draining);
+        println!("Draining for {} ms before shutdown", self.drain.as_millis());
+        tokio::time::sleep(self.drain).await;
+    }
+
+    /// Waits for the tasks started with `spawn_tracked` to complete, for at
+    /// most the grace period. Call once the server stopped taking calls.
+    pub async fn finish_tasks(&self) {
+        let deadline = Instant::now() + self.grace;
+        // Let tasks spawned by the last calls start before counting them.
+        tokio::task::yield_now().await;
+        loop {
+            let running = TRACKED_TASKS.load(Ordering::SeqCst);
+            if running == 0 {
+                return;
+            }
+            if Instant::now() >= deadline {
+                eprintln!("warning: shutting down with {} background tasks still running", running);
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+/// Background tasks started by handlers that have not completed yet.
+static TRACKED_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+struct TrackedTask;
+
+impl Drop for TrackedTask {
+    fn drop(&mut self) {
+        TRACKED_TASKS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// `tokio::spawn` for work that should outlive the call that started it but
+/// not be cut off by a shutdown, like sending its events. The task counts as
+/// running from this call on, so `Lifecycle::finish_tasks` cannot miss it,
+/// and keeps the call's correlation id for its logs.
+pub fn spawn_tracked<F>(task: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    TRACKED_TASKS.fetch_add(1, Ordering::SeqCst);
+    let tracked = TrackedTask;
+    let task = with_correlation_id(correlation_id(), task);
+    tokio::spawn(async move {
+        let _tracked = tracked;
+        task.await;
+    });
+}
+
+/// Interceptor for the API services: rejects calls with `unavailable` unless
+/// the service is `Ready`.
+#[derive(Clone)]
+pub struct Gate(Lifecycle);
+
+impl Interceptor for Gate {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        match self.0.state() {
+            ServiceState::Ready => Ok(request),
+            _ => Err(Status::unavailable("Service is not accepting requests")),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::env;
+
+/// Width of the `VARCHAR` columns titles are stored in.
+const TITLE_COLUMN_WIDTH: usize = 50;
+
+/// Maximum lengths of user-supplied text, counted in Unicode scalar values.
+/// Titles (issue titles, epic and column names) are limited by `MAX_TITLE_LEN`
+/// (default and maximum 50, the width of those columns) and descriptions by
+/// `MAX_DESCRIPTION_LEN` (default 10000).
+#[derive(Clone, Copy)]
+pub struct TextLimits {
+    max_title_len: usize,
+    max_description_len: usize,
+}
+
+impl TextLimits {
+    pub fn from_env() -> Result<TextLimits, String> {
+        Ok(TextLimits {
+            max_title_len: title_len(parse_env("MAX_TITLE_LEN", TITLE_COLUMN_WIDTH)?)?,
+            max_description_len: parse_env("MAX_DESCRIPTION_LEN", 10_000)?,
+        })
+    }
+
+    pub fn check_title(&self, field: &str, value: Option<&str>) -> Result<(), String> {
+        check_len(field, value, self.max_title_len)
+    }
+
+    pub fn check_description(&self, field: &str, value: Option<&str>) -> Result<(), String> {
+        check_len(field, value, self.max_description_len)
+    }
+}
+
+/// Fails with `message` when `value` is given but empty after trimming.
+pub fn check_not_blank(value: Option<&str>, message: &str) -> Result<(), String> {
+    match value {
+        Some(text) if text.trim().is_empty() => Err(String::from(message)),
+        _ => Ok(()),
+    }
+}
+
+/// Accepts absolute http(s) URLs with a host and no whitespace, up to
+/// 2048 characters.
+pub fn check_url(field: &str, value: &str) -> Result<(), String> {
+    let rest = value.strip_prefix("https://").or_else(|| value.strip_prefix("http://"));
+    let host = rest.map(|rest| rest.split(['/', '?', '#']).next().unwrap_or_default());
+    let valid = value.chars().count() <= 2048
+        && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+        && host.is_some_and(|host| !host.is_empty());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{} must be an absolute http or https URL", field))
+    }
+}
+
+fn check_len(field: &str, value: Option<&str>, max: usize) -> Result<(), String> {
+    match value {
+        Some(text) if text.chars().count() > max => {
+            Err(format!("{} must be at most {} characters", field, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A longer limit would let titles through that the database then rejects.
+fn title_len(max_title_len: usize) -> Result<usize, String> {
+    match max_title_len <= TITLE_COLUMN_WIDTH {
+        true => Ok(max_title_len),
+        false => Err(format!(
+            "MAX_TITLE_LEN must be at most {}, the width of the title columns, got {}",
+            TITLE_COLUMN_WIDTH, max_title_len
+        )),
+    }
+}
+
+fn parse_env(name: &str, default: usize) -> Result<usize, String> {
+    match env::var(name) {
+        Err(_) => Ok(default),
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("{} must be a non-negative integer, got {}", name, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_len_is_capped_at_the_column_width() {
+        assert_eq!(title_len(20), Ok(20));
+        assert_eq!(title_len(TITLE_COLUMN_WIDTH), Ok(TITLE_COLUMN_WIDTH));
+        assert!(title_len(TITLE_COLUMN_WIDTH + 1).is_err());
+    }
+
+    #[test]
+    fn check_len_counts_characters_not_bytes() {
+        assert_eq!(check_len("title", Some("ééé"), 3), Ok(()));
+        assert_eq!(check_len("title", None, 0), Ok(()));
+        assert_eq!(
+            check_len("title", Some("abcd"), 3),
+            Err(String::from("title must be at most 3 characters")),
+        );
+    }
+
+    #[test]
+    fn check_url_accepts_only_absolute_http_urls() {
+        assert_eq!(check_url("url", "https://example.com/path?query#fragment"), Ok(()));
+        assert_eq!(check_url("url", "http://example.com"), Ok(()));
+
+        let rejected = [
+            "example.com",
+            "ftp://example.com",
+            "https://",
+            "https:///path",
+            "https://example.com/a path",
+            "https://example.com/\u{7}",
+        ];
+        for url in rejected {
+            assert!(check_url("url", url).is_err(), "{} was accepted", url);
+        }
+        let too_long = format!("https://example.com/{}", "a".repeat(2048));
+        assert!(check_url("url", &too_long).is_err());
+    }
+}
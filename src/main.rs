@@ -1,17 +1,33 @@
 #[macro_use]
 extern crate diesel;
 
+mod board_policy;
+mod cache;
 mod controllers;
+mod correlation;
 mod db;
+mod default_sort;
+mod deletion_tokens;
+mod events;
+mod ids;
+mod lifecycle;
+mod limits;
+mod outbox;
+mod pagination;
+mod streams;
+mod users;
 
 
-use tonic::transport::{Server, Channel};
+use tonic::transport::{Server, Channel, NamedService};
 use controllers::{
     boards::BoardsController,
     columns::ColumnsController,
     issues::IssuesController,
     epics::EpicsController,
     dependencies::DependenciesController,
+    admin::AdminController,
+    search::SearchController,
+    health::HealthController,
 };
 use proto::{
     issues::{
@@ -20,70 +36,199 @@ use proto::{
         issues_service_server::IssuesServiceServer,
         epics_service_server::EpicsServiceServer,
         dependencies_service_server::DependenciesServiceServer, 
+        admin_service_server::AdminServiceServer,
+        search_service_server::SearchServiceServer,
     },
+    health::health_server::HealthServer,
     eventbus::{
-        boards_events_service_client::BoardsEventsServiceClient, epics_events_service_client::EpicsEventsServiceClient, issues_events_service_client::IssuesEventsServiceClient, dependencies_events_service_client::DependenciesEventsServiceClient,columns_events_service_client::ColumnsEventsServiceClient
+        boards_events_service_client::BoardsEventsServiceClient, epics_events_service_client::EpicsEventsServiceClient, issues_events_service_client::IssuesEventsServiceClient, dependencies_events_service_client::DependenciesEventsServiceClient,columns_events_service_client::ColumnsEventsServiceClient, search_events_service_client::SearchEventsServiceClient
     }
 };
 use dotenv::dotenv;
 use std::env;
 
-use crate::db::connection::establish_connection;
+use crate::{
+    db::{connection::{establish_connection, establish_read_connection}, pool_metrics::PoolWaitMetrics, slow_queries, full_text},
+    events::{EventPayload, RetryPolicy, eventbus_url},
+    board_policy::BoardsPerProject,
+    cache::ReadCache,
+    correlation::CorrelationLayer,
+    deletion_tokens::DeletionTokens,
+    default_sort::{BoardSort, DependencySort},
+    lifecycle::{Lifecycle, ServiceState},
+    ids::IdScheme,
+    pagination::PageSizes,
+    limits::TextLimits,
+    outbox::OutboxRelay,
+    streams::StreamLimit,
+    users::UserValidation,
+};
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
     let app_url = env::var("APP_URL")?.parse()?;
 
-    let pool = establish_connection();
+    let pool_wait_metrics = PoolWaitMetrics::from_env()?;
+    let pool = establish_connection(pool_wait_metrics.clone())?;
+    let read_pool = establish_read_connection(pool_wait_metrics.clone())?
+        .unwrap_or_else(|| pool.clone());
+    let event_payload = EventPayload::from_env()?;
+    RetryPolicy::from_env()?.install();
+    IdScheme::from_env()?.install();
+    PageSizes::from_env()?.install();
+    let text_limits = TextLimits::from_env()?;
+    slow_queries::configure_from_env()?;
+    full_text::configure_from_env()?;
+    let lifecycle = Lifecycle::from_env()?;
+    let outbox_relay = OutboxRelay::from_env()?;
+    let stream_limit = StreamLimit::from_env()?;
     
-    let boards_events_service_client: BoardsEventsServiceClient<Channel> =
-    BoardsEventsServiceClient::connect("http://127.0.0.1:50057").await?;
-    let columns_events_service_client: ColumnsEventsServiceClient<Channel> =
-    ColumnsEventsServiceClient::connect("http://127.0.0.1:50057").await?;
-    let issues_events_service_client: IssuesEventsServiceClient<Channel> =
-    IssuesEventsServiceClient::connect("http://127.0.0.1:50057").await?;
-    let epics_events_service_client: EpicsEventsServiceClient<Channel> =
-    EpicsEventsServiceClient::connect("http://127.0.0.1:50057").await?;
-    let dependencies_events_service_client: DependenciesEventsServiceClient<Channel> =
-    DependenciesEventsServiceClient::connect("http://127.0.0.1:50057").await?;
+    let boards_events_service_client: Option<BoardsEventsServiceClient<Channel>> = match eventbus_url("BOARDS")? {
+        Some(url) => Some(BoardsEventsServiceClient::connect(url).await?),
+        None => None,
+    };
+    let columns_events_service_client: Option<ColumnsEventsServiceClient<Channel>> = match eventbus_url("COLUMNS")? {
+        Some(url) => Some(ColumnsEventsServiceClient::connect(url).await?),
+        None => None,
+    };
+    let issues_events_service_client: Option<IssuesEventsServiceClient<Channel>> = match eventbus_url("ISSUES")? {
+        Some(url) => Some(IssuesEventsServiceClient::connect(url).await?),
+        None => None,
+    };
+    let epics_events_service_client: Option<EpicsEventsServiceClient<Channel>> = match eventbus_url("EPICS")? {
+        Some(url) => Some(EpicsEventsServiceClient::connect(url).await?),
+        None => None,
+    };
+    let dependencies_events_service_client: Option<DependenciesEventsServiceClient<Channel>> = match eventbus_url("DEPENDENCIES")? {
+        Some(url) => Some(DependenciesEventsServiceClient::connect(url).await?),
+        None => None,
+    };
+    let search_events_service_client: Option<SearchEventsServiceClient<Channel>> = match eventbus_url("SEARCH")? {
+        Some(url) => Some(SearchEventsServiceClient::connect(url).await?),
+        None => None,
+    };
 
+    let admin_controller = AdminController {
+        pool: pool.clone(),
+        boards_eventbus_service_client: boards_events_service_client.clone(),
+        columns_eventbus_service_client: columns_events_service_client.clone(),
+        epics_eventbus_service_client: epics_events_service_client.clone(),
+        issues_eventbus_service_client: issues_events_service_client.clone(),
+        dependencies_eventbus_service_client: dependencies_events_service_client.clone(),
+        event_payload,
+        pool_wait_metrics,
+    };
+    // Global search only reads, so it runs entirely on the read pool.
+    let search_controller = SearchController {
+        pool: read_pool.clone(),
+        eventbus_service_client: search_events_service_client,
+    };
+    // Shared so that deleting a board also drops its columns from the cache.
+    let column_cache = ReadCache::from_env()?;
     let boards_controller = BoardsController {
         pool: pool.clone(),
-        eventbus_service_client: boards_events_service_client
+        read_pool: read_pool.clone(),
+        eventbus_service_client: boards_events_service_client,
+        columns_eventbus_service_client: columns_events_service_client.clone(),
+        epics_eventbus_service_client: epics_events_service_client.clone(),
+        issues_eventbus_service_client: issues_events_service_client.clone(),
+        dependencies_eventbus_service_client: dependencies_events_service_client.clone(),
+        event_payload,
+        stream_limit: stream_limit.clone(),
+        read_cache: ReadCache::from_env()?,
+        column_cache: column_cache.clone(),
+        deletion_tokens: DeletionTokens::from_env()?,
+        boards_per_project: BoardsPerProject::from_env()?,
+        text_limits,
+        default_sort: BoardSort::from_env()?,
     };
     let columns_controller = ColumnsController {
         pool: pool.clone(),
-        eventbus_service_client: columns_events_service_client
+        read_pool: read_pool.clone(),
+        eventbus_service_client: columns_events_service_client,
+        event_payload,
+        stream_limit: stream_limit.clone(),
+        read_cache: column_cache,
+        text_limits,
     };
+    // Stopped only after the last calls and their events are done, so the
+    // relay can flush what they wrote to the outbox.
+    let relay = issues_events_service_client.clone()
+        .map(|client| tokio::spawn(outbox_relay.run(pool.clone(), client, lifecycle.subscribe())));
     let issues_controller = IssuesController {
         pool: pool.clone(),
-        eventbus_service_client: issues_events_service_client
+        read_pool: read_pool.clone(),
+        eventbus_service_client: issues_events_service_client.clone(),
+        event_payload,
+        stream_limit: stream_limit.clone(),
+        text_limits,
     };
     let epics_controller = EpicsController {
         pool: pool.clone(),
-        eventbus_service_client: epics_events_service_client
+        read_pool: read_pool.clone(),
+        eventbus_service_client: epics_events_service_client,
+        issues_eventbus_service_client: issues_events_service_client,
+        event_payload,
+        stream_limit: stream_limit.clone(),
+        text_limits,
+        user_validation: UserValidation::from_env()?,
     };
     let dependencies_controller = DependenciesController {
         pool: pool.clone(),
-        eventbus_service_client: dependencies_events_service_client
+        read_pool: read_pool.clone(),
+        eventbus_service_client: dependencies_events_service_client,
+        event_payload,
+        stream_limit,
+        default_sort: DependencySort::from_env()?,
     };
 
-    let boards_service_server = BoardsServiceServer::new(boards_controller);
-    let columns_service_server = ColumnsServiceServer::new(columns_controller);
-    let issues_service_server = IssuesServiceServer::new(issues_controller);
-    let epics_service_server = EpicsServiceServer::new(epics_controller);
-    let dependencies_service_server = DependenciesServiceServer::new(dependencies_controller);
+    let health_controller = HealthController {
+        lifecycle: lifecycle.clone(),
+        services: vec![
+            BoardsServiceServer::<BoardsController>::NAME,
+            ColumnsServiceServer::<ColumnsController>::NAME,
+            IssuesServiceServer::<IssuesController>::NAME,
+            EpicsServiceServer::<EpicsController>::NAME,
+            DependenciesServiceServer::<DependenciesController>::NAME,
+            AdminServiceServer::<AdminController>::NAME,
+            SearchServiceServer::<SearchController>::NAME,
+        ],
+    };
+
+    let boards_service_server = BoardsServiceServer::with_interceptor(boards_controller, lifecycle.gate());
+    let columns_service_server = ColumnsServiceServer::with_interceptor(columns_controller, lifecycle.gate());
+    let issues_service_server = IssuesServiceServer::with_interceptor(issues_controller, lifecycle.gate());
+    let epics_service_server = EpicsServiceServer::with_interceptor(epics_controller, lifecycle.gate());
+    let dependencies_service_server = DependenciesServiceServer::with_interceptor(dependencies_controller, lifecycle.gate());
+    let admin_service_server = AdminServiceServer::with_interceptor(admin_controller, lifecycle.gate());
+    let search_service_server = SearchServiceServer::with_interceptor(search_controller, lifecycle.gate());
+    let health_service_server = HealthServer::new(health_controller);
 
+    lifecycle.set(ServiceState::Ready);
     println!("Issues service listening on {}", app_url);
+    // After the drain period the server stops accepting connections and
+    // waits for in-flight calls and streams to complete before returning;
+    // the events they spawned then get the grace period to be sent.
     Server::builder()
+        .layer(CorrelationLayer)
+        .add_service(health_service_server)
         .add_service(boards_service_server)
         .add_service(columns_service_server)
         .add_service(issues_service_server)
         .add_service(epics_service_server)
         .add_service(dependencies_service_server)
-        .serve(app_url)
+        .add_service(admin_service_server)
+        .add_service(search_service_server)
+        .serve_with_shutdown(app_url, lifecycle.clone().drain_on_signal())
         .await?;
+    lifecycle.finish_tasks().await;
+    lifecycle.set(ServiceState::Stopped);
+    if let Some(relay) = relay {
+        if let Err(err) = relay.await {
+            eprintln!("error: outbox relay failed: {}", err);
+        }
+    }
 
     Ok(())
 }
\ No newline at end of file
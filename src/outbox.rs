@@ -0,0 +1,227 @@
+use std::{env, time::Duration};
+use tokio::{sync::watch, time::Instant};
+use prost::Message;
+use chrono::NaiveDateTime;
+use diesel::{result::Error, r2d2::ConnectionManager, PgConnection};
+use r2d2::PooledConnection;
+use tonic::{Request, Status, transport::Channel};
+use proto::eventbus::{issues_events_service_client::IssuesEventsServiceClient, IssueEvent};
+
+use crate::{
+    correlation::with_correlation_id,
+    events::{deliver, is_transient},
+    lifecycle::ServiceState,
+    db::{
+        connection::PgPool,
+        repos::outbox::{
+            OutboxEvent, ClaimOutboxEvents, MarkOutboxEventDelivered, PurgeOutboxEvents,
+            ReplayOutboxEvents, ReplayLock,
+        },
+    },
+};
+
+/// How many outbox rows one pass of the relay picks up.
+const BATCH_SIZE: i64 = 100;
+
+/// How long a pass holds the rows it picked up. Longer than a pass takes,
+/// so another replica only picks them up after this one died.
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+
+/// How often delivered rows older than the retention are deleted.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sends events written to the `outbox` table in the same transaction as
+/// the change they describe, polling every `OUTBOX_POLL_MS` (default 500).
+/// Delivery is at least once: a crash between sending and marking a row
+/// delivered sends it again once its claim runs out. Replicas claim
+/// disjoint rows, so an event is not sent by two of them at once, but
+/// events claimed by different replicas may arrive out of order.
+/// Delivered rows are kept for `OUTBOX_RETENTION_SECS` (default 86400).
+pub struct OutboxRelay {
+    poll: Duration,
+    retention: Duration,
+}
+
+impl OutboxRelay {
+    pub fn from_env() -> Result<OutboxRelay, String> {
+        let poll_ms = match env::var("OUTBOX_POLL_MS") {
+            Err(_) => 500,
+            Ok(value) => match value.parse() {
+                Ok(poll_ms) if poll_ms > 0 => poll_ms,
+                _ => return Err(format!("OUTBOX_POLL_MS must be a positive integer, got {}", value)),
+            },
+        };
+
+        let retention_secs = match env::var("OUTBOX_RETENTION_SECS") {
+            Err(_) => 86400,
+            Ok(value) => value.parse().map_err(|_| format!(
+                "OUTBOX_RETENTION_SECS must be a non-negative integer, got {}", value
+            ))?,
+        };
+
+        Ok(OutboxRelay {
+            poll: Duration::from_millis(poll_ms),
+            retention: Duration::from_secs(retention_secs),
+        })
+    }
+
+    /// Relays until the service is `Stopped`, then makes one last pass for
+    /// the events the final calls wrote and returns.
+    pub async fn run(
+        self,
+        pool: PgPool,
+        issues_client: IssuesEventsServiceClient<Channel>,
+        mut state: watch::Receiver<ServiceState>,
+    ) {
+        let mut next_purge = Instant::now();
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(self.poll) => {},
+                _ = state.changed() => {},
+            }
+            let stopping = *state.borrow() == ServiceState::Stopped;
+
+            if Instant::now() >= next_purge {
+                next_purge = Instant::now() + PURGE_INTERVAL;
+                self.purge(&pool).await;
+            }
+            self.relay_pass(&pool, &issues_client).await;
+
+            if stopping {
+                return;
+            }
+        }
+    }
+
+    async fn purge(&self, pool: &PgPool) {
+        let purged = match pool.get() {
+            Ok(db_connection) => OutboxEvent::purge_delivered(self.retention, db_connection).await
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = purged {
+            eprintln!("warning: outbox relay could not purge delivered events: {}", err);
+        }
+    }
+
+    async fn relay_pass(&self, pool: &PgPool, issues_client: &IssuesEventsServiceClient<Channel>) {
+        let claimed = match pool.get() {
+            Ok(db_connection) => OutboxEvent::claim(BATCH_SIZE, CLAIM_LEASE, db_connection).await,
+            Err(err) => {
+                eprintln!("warning: outbox relay could not get a connection: {}", err);
+                return;
+            }
+        };
+        let claimed = match claimed {
+            Ok(claimed) => claimed,
+            Err(err) => {
+                eprintln!("warning: outbox relay could not claim pending events: {}", err);
+                return;
+            }
+        };
+
+        for (index, event) in claimed.iter().enumerate() {
+            let sent = send(event, issues_client).await;
+
+            // Keep the order of events: stop at the first one the
+            // eventbus could not take yet and give it and the rest back
+            // for the next pass. Anything else was logged and is not worth
+            // resending.
+            if let Err(status) = sent {
+                if is_transient(&status) {
+                    self.release(pool, &claimed[index..]).await;
+                    return;
+                }
+            }
+
+            let marked = match pool.get() {
+                Ok(db_connection) => OutboxEvent::mark_delivered(event.id, db_connection).await
+                    .map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+            if let Err(err) = marked {
+                eprintln!("warning: outbox event {} was sent but not marked delivered: {}", event.id, err);
+                self.release(pool, &claimed[index + 1..]).await;
+                return;
+            }
+        }
+    }
+
+    async fn release(&self, pool: &PgPool, events: &[OutboxEvent]) {
+        let events_ids = events.iter().map(|event| event.id).collect();
+        let released = match pool.get() {
+            Ok(db_connection) => OutboxEvent::release(events_ids, db_connection).await
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = released {
+            eprintln!("warning: outbox relay could not release claimed events: {}", err);
+        }
+    }
+}
+
+/// Sends one outbox event with the client for its method. An event that
+/// cannot be decoded or has an unknown method is logged and treated as
+/// sent, since sending it again would not help.
+async fn send(event: &OutboxEvent, issues_client: &IssuesEventsServiceClient<Channel>) -> Result<(), Status> {
+    match event.method.as_str() {
+        "update_issue_event" => match IssueEvent::decode(event.payload.as_slice()) {
+            Ok(req) => with_correlation_id(req.correlation_id.clone(), deliver(&event.method, Request::new(req), |req| {
+                let mut client = issues_client.clone();
+                async move { client.update_issue_event(req).await }
+            })).await,
+            Err(err) => {
+                eprintln!("error: outbox event {} has an undecodable payload: {}", event.id, err);
+                Ok(())
+            }
+        },
+        other => {
+            eprintln!("error: outbox event {} has an unknown method {}", event.id, other);
+            Ok(())
+        }
+    }
+}
+
+/// The outbox methods carrying events about `entity_type`, `None` for a
+/// kind of entity whose events do not go through the outbox.
+pub fn replay_methods(entity_type: &str) -> Option<&'static [&'static str]> {
+    match entity_type {
+        "issue" => Some(&["update_issue_event"]),
+        _ => None,
+    }
+}
+
+pub enum ReplayError {
+    /// Another replay is running.
+    InProgress,
+    Db(Error),
+    /// The eventbus failed after this many events were sent again.
+    Send(usize, Status),
+}
+
+/// Sends the delivered outbox events written in `[from, to)` again, in the
+/// order they were written, and returns how many were sent. The replay lock
+/// is taken on `lock_connection` and held throughout, so that two replays
+/// never interleave; `db_connection` loads the events.
+pub async fn replay(
+    lock_connection: PooledConnection<ConnectionManager<PgConnection>>,
+    db_connection: PooledConnection<ConnectionManager<PgConnection>>,
+    issues_client: &IssuesEventsServiceClient<Channel>,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    methods: Option<&[&str]>,
+) -> Result<usize, ReplayError> {
+    let _lock = match ReplayLock::try_acquire(lock_connection).map_err(ReplayError::Db)? {
+        Some(lock) => lock,
+        None => return Err(ReplayError::InProgress),
+    };
+
+    let events = OutboxEvent::delivered_between(from, to, methods, db_connection).await
+        .map_err(ReplayError::Db)?;
+
+    for (index, event) in events.iter().enumerate() {
+        send(event, issues_client).await.map_err(|status| ReplayError::Send(index, status))?;
+    }
+
+    Ok(events.len())
+}
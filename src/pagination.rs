@@ -0,0 +1,204 @@
+use std::{env, sync::OnceLock};
+use proto::issues::Page as ProtoPage;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+use tonic::Status;
+
+use crate::lifecycle::spawn_tracked;
+
+/// Page sizes for the search RPCs, read from `MAX_PAGE_SIZE` (default
+/// 1000), the most rows one search hands back, and `DEFAULT_PAGE_SIZE`
+/// (default 50), the rows returned when the caller sets no limit.
+#[derive(Clone, Copy)]
+pub struct PageSizes {
+    pub max: i64,
+    pub default: i64,
+}
+
+static PAGE_SIZES: OnceLock<PageSizes> = OnceLock::new();
+
+impl Default for PageSizes {
+    fn default() -> PageSizes {
+        PageSizes {
+            max: 1000,
+            default: 50,
+        }
+    }
+}
+
+impl PageSizes {
+    pub fn from_env() -> Result<PageSizes, String> {
+        let defaults = PageSizes::default();
+        let max = page_size_from_env("MAX_PAGE_SIZE", defaults.max)?;
+        let default = page_size_from_env("DEFAULT_PAGE_SIZE", defaults.default.min(max))?;
+        if default > max {
+            return Err(format!("DEFAULT_PAGE_SIZE must not exceed MAX_PAGE_SIZE ({}), got {}", max, default));
+        }
+
+        Ok(PageSizes {
+            max,
+            default,
+        })
+    }
+
+    /// Makes these the sizes searches use. Only the first call has an effect.
+    pub fn install(self) {
+        let _ = PAGE_SIZES.set(self);
+    }
+
+    fn installed() -> PageSizes {
+        PAGE_SIZES.get().copied().unwrap_or_default()
+    }
+}
+
+fn page_size_from_env(name: &str, default: i64) -> Result<i64, String> {
+    match env::var(name) {
+        Err(_) => Ok(default),
+        Ok(value) => match value.parse::<i32>() {
+            Ok(size) if size > 0 => Ok(i64::from(size)),
+            _ => Err(format!("{} must be a positive integer, got {}", name, value)),
+        },
+    }
+}
+
+/// Largest page a search hands back; bigger limits are clamped to this.
+pub fn max_page_size() -> i64 {
+    PageSizes::installed().max
+}
+
+/// A validated limit/offset window. The limit is always set so that no
+/// search returns a whole table.
+#[derive(Clone, Copy)]
+pub struct Page {
+    pub limit: i64,
+    pub offset: Option<i64>,
+}
+
+impl Page {
+    /// Applies `DEFAULT_PAGE_SIZE` when no limit is given.
+    pub fn from_params(limit: Option<i32>, offset: Option<i32>) -> Result<Page, String> {
+        Page::with_default(limit, offset, PageSizes::installed().default)
+    }
+
+    /// Like `from_params` with a default of the caller's own.
+    pub fn with_default(limit: Option<i32>, offset: Option<i32>, default: i64) -> Result<Page, String> {
+        if limit.is_some_and(|limit| limit < 0) {
+            return Err(String::from("limit must not be negative"));
+        }
+        if offset.is_some_and(|offset| offset < 0) {
+            return Err(String::from("offset must not be negative"));
+        }
+
+        Ok(Page {
+            limit: limit.map_or(default, i64::from).min(max_page_size()),
+            offset: offset.map(i64::from),
+        })
+    }
+
+    /// The limit actually applied, for the search params events echo back
+    /// so that consumers can tell a result was cut short.
+    pub fn effective_limit(&self) -> Option<i32> {
+        i32::try_from(self.limit).ok()
+    }
+}
+
+/// The limit and offset a search was asked for, preferring the shared `page`
+/// message over the older top-level fields. `Page::from_params` validates
+/// and clamps them.
+pub fn requested(page: Option<&ProtoPage>, limit: Option<i32>, offset: Option<i32>) -> (Option<i32>, Option<i32>) {
+    match page {
+        Some(page) => (page.limit, page.offset),
+        None => (limit, offset),
+    }
+}
+
+/// Rows per message for the chunked search RPCs. Unset means one row per
+/// message, like the plain search RPCs; larger values are clamped to
+/// `MAX_PAGE_SIZE`.
+pub fn chunk_size(requested: Option<i32>) -> Result<usize, String> {
+    match requested {
+        None => Ok(1),
+        Some(size) if size < 1 => Err(String::from("chunkSize must be positive")),
+        Some(size) => Ok(i64::from(size).min(max_page_size()) as usize),
+    }
+}
+
+/// Regroups a row stream into messages of `size` rows built by `wrap`; the
+/// last one may be shorter. An error ends the stream after the rows that
+/// came before it have been sent. Runs as a tracked task, so a shutdown
+/// lets the stream finish.
+pub fn chunked<S, T, C>(mut rows: S, size: usize, wrap: fn(Vec<T>) -> C) -> ReceiverStream<Result<C, Status>>
+where
+    S: Stream<Item = Result<T, Status>> + Unpin + Send + 'static,
+    T: Send + 'static,
+    C: Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(4);
+
+    spawn_tracked(async move {
+        let mut chunk: Vec<T> = Vec::with_capacity(size);
+        while let Some(row) = rows.next().await {
+            match row {
+                Ok(row) => {
+                    chunk.push(row);
+                    if chunk.len() == size {
+                        let full = std::mem::replace(&mut chunk, Vec::with_capacity(size));
+                        if sender.send(Ok(wrap(full))).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(status) => {
+                    if !chunk.is_empty() && sender.send(Ok(wrap(chunk))).await.is_err() {
+                        return;
+                    }
+                    let _ = sender.send(Err(status)).await;
+                    return;
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            let _ = sender.send(Ok(wrap(chunk))).await;
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_are_clamped_to_the_max_size() {
+        let max = max_page_size();
+
+        let page = Page::from_params(None, None).unwrap();
+        assert_eq!((page.limit, page.offset), (PageSizes::installed().default, None));
+
+        let page = Page::with_default(Some(20), Some(40), 10).unwrap();
+        assert_eq!((page.limit, page.offset), (20, Some(40)));
+
+        let page = Page::with_default(Some(i32::MAX), None, 10).unwrap();
+        assert_eq!(page.limit, max);
+
+        let page = Page::with_default(None, None, max + 1).unwrap();
+        assert_eq!(page.limit, max);
+    }
+
+    #[test]
+    fn negative_limits_and_offsets_are_rejected() {
+        assert!(Page::from_params(Some(-1), None).is_err());
+        assert!(Page::from_params(None, Some(-1)).is_err());
+        assert!(Page::from_params(Some(0), Some(0)).is_ok());
+    }
+
+    #[test]
+    fn chunk_size_defaults_to_one_row_and_is_clamped() {
+        assert_eq!(chunk_size(None), Ok(1));
+        assert_eq!(chunk_size(Some(25)), Ok(25));
+        assert_eq!(chunk_size(Some(i32::MAX)), Ok(max_page_size() as usize));
+        assert!(chunk_size(Some(0)).is_err());
+        assert!(chunk_size(Some(-3)).is_err());
+    }
+}
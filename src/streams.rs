@@ -0,0 +1,44 @@
+use std::{env, sync::Arc};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tonic::Status;
+
+/// Caps how many search streams are open at once across all services, as
+/// each one holds its results in memory until the client has read them.
+/// Set with `MAX_CONCURRENT_STREAMS` (default 256).
+#[derive(Clone)]
+pub struct StreamLimit {
+    permits: Arc<Semaphore>,
+}
+
+impl StreamLimit {
+    pub fn from_env() -> Result<StreamLimit, String> {
+        let max = match env::var("MAX_CONCURRENT_STREAMS") {
+            Err(_) => 256,
+            Ok(value) => match value.parse() {
+                Ok(max) if max > 0 => max,
+                _ => return Err(format!("MAX_CONCURRENT_STREAMS must be a positive integer, got {}", value)),
+            },
+        };
+
+        Ok(StreamLimit {
+            permits: Arc::new(Semaphore::new(max)),
+        })
+    }
+
+    /// A slot for one stream, to be moved into the task producing it. The
+    /// slot frees up when that task ends, which is also when the client
+    /// disconnects, since the next send then fails.
+    pub fn acquire(&self) -> Result<OwnedSemaphorePermit, TooManyStreams> {
+        self.permits.clone().try_acquire_owned().map_err(|_| TooManyStreams)
+    }
+}
+
+/// Every stream slot is taken. Becomes `resource_exhausted` through `?`.
+#[derive(Debug)]
+pub struct TooManyStreams;
+
+impl From<TooManyStreams> for Status {
+    fn from(_: TooManyStreams) -> Self {
+        Status::resource_exhausted("Too many open streams, try again later")
+    }
+}
@@ -0,0 +1,32 @@
+use std::env;
+
+/// How reporter and assignee ids are checked before an epic is written.
+/// Users live in another service, so by default ids are taken as given;
+/// `USER_VALIDATION=uuid` rejects ids that are not UUIDs.
+#[derive(Clone, Copy, PartialEq)]
+pub enum UserValidation {
+    Off,
+    UuidFormat,
+}
+
+impl UserValidation {
+    pub fn from_env() -> Result<UserValidation, String> {
+        match env::var("USER_VALIDATION") {
+            Err(_) => Ok(UserValidation::Off),
+            Ok(value) => match value.as_str() {
+                "off" => Ok(UserValidation::Off),
+                "uuid" => Ok(UserValidation::UuidFormat),
+                other => Err(format!("USER_VALIDATION must be off or uuid, got {}", other)),
+            },
+        }
+    }
+
+    pub fn check(&self, field: &str, user_id: Option<&str>) -> Result<(), String> {
+        match (self, user_id) {
+            (UserValidation::UuidFormat, Some(id)) if uuid::Uuid::parse_str(id).is_err() => {
+                Err(format!("{} must be a UUID", field))
+            }
+            _ => Ok(()),
+        }
+    }
+}